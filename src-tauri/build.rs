@@ -1,3 +1,49 @@
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// Embarque l'empreinte SHA-256 du sidecar backend (binaries/backend-<target>) pour que
+/// l'app refuse de lancer un backend modifié ou remplacé (HORIZON_BACKEND_SHA256)
+fn embed_backend_hash() {
+    println!("cargo:rerun-if-changed=binaries");
+
+    let target = std::env::var("TARGET").unwrap_or_default();
+    let extension = if target.contains("windows") { ".exe" } else { "" };
+    let sidecar = PathBuf::from("binaries").join(format!("backend-{}{}", target, extension));
+
+    if let Ok(bytes) = std::fs::read(&sidecar) {
+        let hash = hex::encode(Sha256::digest(&bytes));
+        println!("cargo:rustc-env=HORIZON_BACKEND_SHA256={}", hash);
+    } else {
+        println!("cargo:warning=Backend sidecar not found at {}, integrity check disabled", sidecar.display());
+    }
+}
+
+/// Embarque la clé publique Ed25519 de production du serveur de licences (64 caractères hex).
+/// Un build release sans clé valide échoue : aucune clé de remplacement n'est livrée.
+fn embed_license_public_key() {
+    println!("cargo:rerun-if-env-changed=HORIZON_LICENSE_PUBLIC_KEY");
+
+    let key = std::env::var("HORIZON_LICENSE_PUBLIC_KEY").unwrap_or_default();
+    let key = key.trim();
+    let valid = key.len() == 64 && hex::decode(key).is_ok();
+    if valid {
+        println!("cargo:rustc-env=HORIZON_LICENSE_PUBLIC_KEY={}", key.to_lowercase());
+        return;
+    }
+
+    let message = if key.is_empty() {
+        "HORIZON_LICENSE_PUBLIC_KEY is not set".to_string()
+    } else {
+        "HORIZON_LICENSE_PUBLIC_KEY must be a 32-byte Ed25519 public key in hex (64 characters)".to_string()
+    };
+    if std::env::var("PROFILE").as_deref() == Ok("release") {
+        panic!("{}: release builds require the production license public key", message);
+    }
+    println!("cargo:warning={}, offline license activation is disabled in this build", message);
+}
+
 fn main() {
+    embed_backend_hash();
+    embed_license_public_key();
     tauri_build::build()
-}
\ No newline at end of file
+}
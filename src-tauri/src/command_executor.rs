@@ -0,0 +1,602 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{State, Window, Wry};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::time::{timeout, Duration};
+use crate::context_reader::ContextReader;
+use crate::permission_commands::ensure_permission;
+use crate::permission_manager::{Permission, PermissionManager};
+use crate::settings_profile::{ConfigSource, ConfigSources};
+
+/// Caractères refusés dans le programme et les arguments (aucun shell n'est utilisé,
+/// mais on refuse tout ce qui ressemble à une tentative d'injection)
+const SHELL_METACHARACTERS: [char; 11] = [';', '&', '|', '$', '>', '<', '`', '\n', '\r', '(', ')'];
+
+/// Durée maximale configurable d'une commande (secondes)
+const MAX_TIMEOUT_SECS: u64 = 3600;
+
+/// Sous-commandes et options autorisées pour un programme connu
+struct ProgramPolicy {
+    program: &'static str,
+    subcommands: &'static [&'static str],  // Le premier argument doit en faire partie
+    forbidden_options: &'static [&'static str],  // Options qui chargent une config ou lancent un programme
+}
+
+/// Politiques des programmes connus : uniquement des sous-commandes en lecture qui n'exécutent
+/// ni script, ni alias, ni hook défini par le dépôt. Un programme absent de cette table et ajouté
+/// à l'allowlist par l'utilisateur accepte tous les arguments (confiance explicite).
+const PROGRAM_POLICIES: [ProgramPolicy; 3] = [
+    ProgramPolicy {
+        program: "git",
+        subcommands: &["status", "diff", "log", "show", "rev-parse", "ls-files", "blame"],
+        forbidden_options: &[
+            "-c", "--config", "--config-env", "--exec-path", "--upload-pack", "--receive-pack",
+            "--git-dir", "--work-tree", "--namespace", "--output", "--ext-diff", "--textconv",
+        ],
+    },
+    ProgramPolicy {
+        program: "cargo",
+        subcommands: &["metadata", "tree", "version", "--version"],
+        forbidden_options: &["--config", "-Z", "--manifest-path"],
+    },
+    ProgramPolicy {
+        program: "npm",
+        subcommands: &["ls", "list", "outdated", "view", "--version"],
+        forbidden_options: &["--userconfig", "--globalconfig", "--prefix", "--script-shell", "--node-options"],
+    },
+];
+
+/// Options ajoutées à chaque appel de git : pas de hook fsmonitor défini par le dépôt
+const GIT_HARDENING_ARGS: [&str; 2] = ["-c", "core.fsmonitor=false"];
+/// Sous-commandes git qui acceptent --no-ext-diff/--no-textconv (pilotes définis par le dépôt)
+const GIT_DIFF_SUBCOMMANDS: [&str; 3] = ["diff", "log", "show"];
+
+// Flag pour masquer la fenêtre CMD sur Windows
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Configuration de l'exécution de commandes (CommandExecute)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandExecutorConfig {
+    pub allowed_programs: Vec<String>,
+    pub timeout_secs: u64,  // Au-delà, le processus est tué
+    pub max_output_bytes: usize,  // Par flux (stdout, stderr), au-delà la sortie est tronquée
+}
+
+impl Default for CommandExecutorConfig {
+    fn default() -> Self {
+        Self {
+            allowed_programs: vec![
+                "git".to_string(),
+                "cargo".to_string(),
+                "npm".to_string(),
+            ],
+            timeout_secs: 60,
+            max_output_bytes: 1_000_000, // 1MB
+        }
+    }
+}
+
+/// Résultat d'une commande exécutée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandOutput {
+    pub program: String,
+    pub args: Vec<String>,
+    pub exit_code: Option<i32>,
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+    pub stdout_truncated: bool,
+    pub stderr_truncated: bool,
+    pub timed_out: bool,
+}
+
+/// Fichier modifié depuis une référence git (chemins relatifs au scope)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedFile {
+    pub path: String,
+    pub change: String,  // added, modified, deleted, renamed, copied, type_changed, unmerged
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub old_path: Option<String>,  // Ancien chemin (renamed, copied)
+}
+
+/// Résultat de list_changed_files
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangedFiles {
+    pub git_ref: String,
+    pub is_git_repo: bool,  // false : le scope n'est pas un dépôt git (liste vide)
+    pub files: Vec<ChangedFile>,
+    pub filtered_out: usize,  // Fichiers modifiés dont l'extension n'est pas autorisée
+    pub truncated: bool,  // Sortie de git tronquée (max_output_bytes)
+}
+
+/// Sortie capturée d'un flux (conservée même si la lecture est interrompue)
+#[derive(Default)]
+struct CapturedStream {
+    bytes: Vec<u8>,
+    truncated: bool,
+}
+
+/// Lit un flux jusqu'à `cap` octets puis continue de le vider (sans bloquer le processus)
+/// Les octets sont accumulés dans `captured` au fil de la lecture
+async fn read_capped<R: AsyncRead + Unpin>(mut reader: R, cap: usize, captured: &mut CapturedStream) {
+    let buffer = &mut captured.bytes;
+    let mut chunk = [0u8; 8192];
+
+    loop {
+        match reader.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => {
+                let room = cap.saturating_sub(buffer.len());
+                if n > room {
+                    buffer.extend_from_slice(&chunk[..room]);
+                    captured.truncated = true;
+                } else {
+                    buffer.extend_from_slice(&chunk[..n]);
+                }
+            }
+        }
+    }
+}
+
+/// Exécuteur de commandes restreint à une allowlist de programmes
+pub struct CommandExecutor {
+    config: CommandExecutorConfig,
+    config_sources: ConfigSources,
+}
+
+impl Default for CommandExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CommandExecutor {
+    pub fn new() -> Self {
+        Self {
+            config: CommandExecutorConfig::default(),
+            config_sources: ConfigSources::default(),
+        }
+    }
+
+    /// Obtient la configuration actuelle
+    pub fn get_config(&self) -> CommandExecutorConfig {
+        self.config.clone()
+    }
+
+    /// Provenance de chaque valeur de la configuration (diagnostic)
+    pub fn config_sources(&self) -> ConfigSources {
+        self.config_sources.clone()
+    }
+
+    fn record_config_sources(&mut self, config: &CommandExecutorConfig, source: ConfigSource) {
+        let sources = &mut self.config_sources;
+        sources.set_if_changed("allowed_programs", &self.config.allowed_programs, &config.allowed_programs, source);
+        sources.set_if_changed("timeout_secs", &self.config.timeout_secs, &config.timeout_secs, source);
+        sources.set_if_changed("max_output_bytes", &self.config.max_output_bytes, &config.max_output_bytes, source);
+    }
+
+    /// Remplace l'allowlist (noms de programmes simples uniquement, sans chemin)
+    pub fn set_allowed_programs(&mut self, programs: Vec<String>) -> Result<(), String> {
+        for program in &programs {
+            Self::validate_program_name(program)?;
+        }
+        self.config_sources
+            .set_if_changed("allowed_programs", &self.config.allowed_programs, &programs, ConfigSource::Runtime);
+        self.config.allowed_programs = programs;
+        Ok(())
+    }
+
+    /// Définit les limites d'exécution (durée maximale et taille de sortie par flux)
+    pub fn set_limits(&mut self, timeout_secs: u64, max_output_bytes: usize) -> Result<(), String> {
+        if timeout_secs == 0 || timeout_secs > MAX_TIMEOUT_SECS {
+            return Err(format!("Timeout must be between 1 and {} seconds", MAX_TIMEOUT_SECS));
+        }
+        if max_output_bytes == 0 {
+            return Err("Output cap must be greater than 0".to_string());
+        }
+        self.config_sources
+            .set_if_changed("timeout_secs", &self.config.timeout_secs, &timeout_secs, ConfigSource::Runtime);
+        self.config_sources
+            .set_if_changed("max_output_bytes", &self.config.max_output_bytes, &max_output_bytes, ConfigSource::Runtime);
+        self.config.timeout_secs = timeout_secs;
+        self.config.max_output_bytes = max_output_bytes;
+        Ok(())
+    }
+
+    /// Remplace toute la configuration (validée entièrement avant application)
+    pub fn apply_config(&mut self, config: CommandExecutorConfig, source: ConfigSource) -> Result<(), String> {
+        let mut candidate = Self::new();
+        candidate.set_allowed_programs(config.allowed_programs)?;
+        candidate.set_limits(config.timeout_secs, config.max_output_bytes)?;
+        self.record_config_sources(&candidate.config, source);
+        self.config = candidate.config;
+        Ok(())
+    }
+
+    /// Un programme doit être un nom simple : pas de chemin, pas de métacaractère
+    fn validate_program_name(program: &str) -> Result<(), String> {
+        if program.trim().is_empty() {
+            return Err("Program name cannot be empty".to_string());
+        }
+        if program.contains('/') || program.contains('\\') {
+            return Err(format!("Program must be a plain name, not a path: {}", program));
+        }
+        if program.contains(SHELL_METACHARACTERS) {
+            return Err(format!("Program name contains forbidden characters: {}", program));
+        }
+        Ok(())
+    }
+
+    /// Vérifie qu'une invocation est autorisée (allowlist + métacaractères)
+    pub fn validate(&self, program: &str, args: &[String]) -> Result<(), String> {
+        Self::validate_program_name(program)?;
+
+        if !self.config.allowed_programs.iter().any(|p| p == program) {
+            return Err(format!(
+                "Program {} is not allowed. Allowed programs: {:?}",
+                program, self.config.allowed_programs
+            ));
+        }
+
+        if let Some(arg) = args.iter().find(|a| a.contains(SHELL_METACHARACTERS)) {
+            return Err(format!("Argument contains forbidden shell characters: {}", arg));
+        }
+
+        if let Some(policy) = PROGRAM_POLICIES.iter().find(|p| p.program == program) {
+            Self::validate_policy(policy, args)?;
+        }
+
+        Ok(())
+    }
+
+    /// Sous-commande en tête et aucune option de configuration ou d'exécution
+    fn validate_policy(policy: &ProgramPolicy, args: &[String]) -> Result<(), String> {
+        let subcommand = args.first().map(String::as_str).unwrap_or_default();
+        if !policy.subcommands.contains(&subcommand) {
+            return Err(format!(
+                "{} {} is not allowed. Allowed subcommands: {:?}",
+                policy.program, subcommand, policy.subcommands
+            ));
+        }
+
+        for arg in &args[1..] {
+            let forbidden = policy.forbidden_options.iter().find(|option| {
+                if option.starts_with("--") {
+                    arg == *option || arg.starts_with(&format!("{}=", option))
+                } else {
+                    // Option courte : la valeur peut être collée (-ccore.pager=...)
+                    arg.starts_with(*option)
+                }
+            });
+            if let Some(option) = forbidden {
+                return Err(format!("Option {} is not allowed for {}", option, policy.program));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Arguments réellement passés au programme (durcissement de git)
+fn effective_args(program: &str, args: &[String]) -> Vec<String> {
+    if program != "git" {
+        return args.to_vec();
+    }
+    let mut effective: Vec<String> = GIT_HARDENING_ARGS.iter().map(|arg| arg.to_string()).collect();
+    let mut args = args.iter();
+    if let Some(subcommand) = args.next() {
+        effective.push(subcommand.clone());
+        if GIT_DIFF_SUBCOMMANDS.contains(&subcommand.as_str()) {
+            effective.extend(["--no-ext-diff".to_string(), "--no-textconv".to_string()]);
+        }
+    }
+    effective.extend(args.cloned());
+    effective
+}
+
+/// Lance un programme sans shell, avec la limite de durée et de sortie de la configuration
+/// (le processus est tué au-delà de timeout_secs)
+async fn execute(
+    program: &str,
+    args: &[String],
+    working_dir: Option<&Path>,
+    config: &CommandExecutorConfig,
+) -> Result<CommandOutput, String> {
+    let mut command = tokio::process::Command::new(program);
+    command
+        .args(effective_args(program, args))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(dir) = working_dir {
+        command.current_dir(dir);
+    }
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    let stdout = child.stdout.take().ok_or("Failed to capture stdout")?;
+    let stderr = child.stderr.take().ok_or("Failed to capture stderr")?;
+    let cap = config.max_output_bytes;
+    let mut captured_stdout = CapturedStream::default();
+    let mut captured_stderr = CapturedStream::default();
+
+    let outcome = timeout(Duration::from_secs(config.timeout_secs), async {
+        let (_, _, status) = tokio::join!(
+            read_capped(stdout, cap, &mut captured_stdout),
+            read_capped(stderr, cap, &mut captured_stderr),
+            child.wait()
+        );
+        status
+    })
+    .await;
+
+    let (exit_code, success, timed_out) = match outcome {
+        Ok(status) => {
+            let status = status.map_err(|e| format!("Failed to wait for {}: {}", program, e))?;
+            (status.code(), status.success(), false)
+        }
+        Err(_) => {
+            // Dépassement : tuer le processus, la sortie déjà lue est conservée
+            let _ = child.kill().await;
+            (None, false, true)
+        }
+    };
+
+    let mut stderr = String::from_utf8_lossy(&captured_stderr.bytes).into_owned();
+    if timed_out {
+        if !stderr.is_empty() && !stderr.ends_with('\n') {
+            stderr.push('\n');
+        }
+        stderr.push_str(&format!("Command timed out after {} seconds", config.timeout_secs));
+    }
+
+    Ok(CommandOutput {
+        program: program.to_string(),
+        args: args.to_vec(),
+        exit_code,
+        success,
+        stdout: String::from_utf8_lossy(&captured_stdout.bytes).into_owned(),
+        stderr,
+        stdout_truncated: captured_stdout.truncated,
+        stderr_truncated: captured_stderr.truncated,
+        timed_out,
+    })
+}
+
+/// Exécute un programme de l'allowlist (nécessite CommandExecute)
+/// Le répertoire de travail est le scope de la fenêtre appelante s'il est défini
+#[tauri::command]
+pub async fn run_command(
+    executor_state: State<'_, Mutex<CommandExecutor>>,
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    window: Window<Wry>,
+    program: String,
+    args: Vec<String>,
+) -> Result<CommandOutput, String> {
+    let invocation = format!("{} {}", program, args.join(" "));
+
+    // 1. Valider avant de consommer la permission
+    let config = {
+        let executor = executor_state.lock().map_err(|e| e.to_string())?;
+        executor.validate(&program, &args)?;
+        executor.get_config()
+    };
+
+    // 2. Vérifier la permission d'exécution
+    ensure_permission(
+        &permission_state,
+        Permission::CommandExecute,
+        &format!("Running command: {}", invocation),
+        "run_command",
+    ).await?;
+
+    let working_dir = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.window_scope(window.label()).cloned()
+    };
+
+    // 3. Exécuter sans shell, avec limite de durée et de sortie
+    let result = execute(&program, &args, working_dir.as_deref(), &config).await?;
+
+    // 4. Tracer l'invocation dans le journal d'audit
+    let (log, handle) = {
+        let mut manager = permission_state.lock().map_err(|e| e.to_string())?;
+        let log = manager.record_event(
+            Permission::CommandExecute,
+            &invocation,
+            if result.timed_out {
+                format!("Command killed after {} seconds timeout", config.timeout_secs)
+            } else {
+                format!(
+                    "Command executed (exit code: {})",
+                    result.exit_code.map(|c| c.to_string()).unwrap_or_else(|| "none".to_string())
+                )
+            },
+            "run_command",
+        );
+        (log, manager.async_handle())
+    };
+    handle.write_log(log).await?;
+
+    Ok(result)
+}
+
+/// Refuse une référence git qui pourrait être interprétée comme une option ou un chemin
+fn validate_git_ref(git_ref: &str) -> Result<(), String> {
+    if git_ref.is_empty() || git_ref.starts_with('-') || git_ref.chars().any(char::is_whitespace) {
+        return Err(format!("Invalid git reference: {:?}", git_ref));
+    }
+    Ok(())
+}
+
+/// Analyse la sortie de `git diff --name-status -z` (statut, chemin, puis nouveau chemin pour R/C)
+fn parse_name_status(output: &str) -> Vec<ChangedFile> {
+    let mut fields = output.split('\0').filter(|field| !field.is_empty());
+    let mut files = Vec::new();
+
+    while let Some(status) = fields.next() {
+        let change = match status.chars().next() {
+            Some('A') => "added",
+            Some('M') => "modified",
+            Some('D') => "deleted",
+            Some('R') => "renamed",
+            Some('C') => "copied",
+            Some('T') => "type_changed",
+            Some('U') => "unmerged",
+            _ => "unknown",
+        };
+        let Some(path) = fields.next() else {
+            break;
+        };
+        let (path, old_path) = if matches!(change, "renamed" | "copied") {
+            match fields.next() {
+                Some(new_path) => (new_path, Some(path.to_string())),
+                None => break,
+            }
+        } else {
+            (path, None)
+        };
+        files.push(ChangedFile {
+            path: path.to_string(),
+            change: change.to_string(),
+            old_path,
+        });
+    }
+
+    files
+}
+
+/// Liste les fichiers du scope modifiés depuis `git_ref` (`git diff --name-status <ref>`, copie de
+/// travail comprise), filtrés sur les extensions autorisées, pour préparer le contexte d'une revue.
+/// Nécessite RepoAnalyze et CommandExecute (git doit figurer dans l'allowlist).
+#[tauri::command]
+pub async fn list_changed_files(
+    executor_state: State<'_, Mutex<CommandExecutor>>,
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    window: Window<Wry>,
+    git_ref: String,
+) -> Result<ChangedFiles, String> {
+    validate_git_ref(&git_ref)?;
+    let diff_args: Vec<String> = ["diff", "--name-status", "-z", "--relative", git_ref.as_str(), "--"]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+
+    let config = {
+        let executor = executor_state.lock().map_err(|e| e.to_string())?;
+        executor.validate("git", &diff_args)?;
+        executor.get_config()
+    };
+    let context_config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+    let scope = context_config.current_scope
+        .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+    let invocation = format!("git {}", diff_args.join(" "));
+    ensure_permission(
+        &permission_state,
+        Permission::RepoAnalyze,
+        &format!("Listing files changed since {}: {}", git_ref, scope.display()),
+        "list_changed_files",
+    ).await?;
+    ensure_permission(
+        &permission_state,
+        Permission::CommandExecute,
+        &format!("Running command: {}", invocation),
+        "list_changed_files",
+    ).await?;
+
+    // Scope hors dépôt git : liste vide plutôt qu'une erreur
+    let probe_args = vec!["rev-parse".to_string(), "--is-inside-work-tree".to_string()];
+    let probe = execute("git", &probe_args, Some(&scope), &config).await?;
+    if !probe.success {
+        return Ok(ChangedFiles {
+            git_ref,
+            is_git_repo: false,
+            files: Vec::new(),
+            filtered_out: 0,
+            truncated: false,
+        });
+    }
+
+    let output = execute("git", &diff_args, Some(&scope), &config).await?;
+
+    let (log, handle) = {
+        let mut manager = permission_state.lock().map_err(|e| e.to_string())?;
+        let log = manager.record_event(
+            Permission::CommandExecute,
+            &invocation,
+            format!(
+                "Command executed (exit code: {})",
+                output.exit_code.map(|c| c.to_string()).unwrap_or_else(|| "none".to_string())
+            ),
+            "list_changed_files",
+        );
+        (log, manager.async_handle())
+    };
+    handle.write_log(log).await?;
+
+    if !output.success {
+        return Err(format!("git diff failed: {}", output.stderr.trim()));
+    }
+
+    // Même règle que la lecture : un fichier sans extension n'est pas filtré
+    let (files, excluded): (Vec<ChangedFile>, Vec<ChangedFile>) = parse_name_status(&output.stdout)
+        .into_iter()
+        .partition(|file| {
+            Path::new(&file.path)
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .map_or(true, |ext| context_config.allowed_extensions.contains(&ext.to_lowercase()))
+        });
+
+    Ok(ChangedFiles {
+        git_ref,
+        is_git_repo: true,
+        files,
+        filtered_out: excluded.len(),
+        truncated: output.stdout_truncated,
+    })
+}
+
+/// Obtient l'allowlist des programmes exécutables
+#[tauri::command]
+pub async fn get_command_allowlist(
+    executor_state: State<'_, Mutex<CommandExecutor>>,
+) -> Result<CommandExecutorConfig, String> {
+    let executor = executor_state.lock().map_err(|e| e.to_string())?;
+    Ok(executor.get_config())
+}
+
+/// Remplace l'allowlist des programmes exécutables
+#[tauri::command]
+pub async fn set_command_allowlist(
+    executor_state: State<'_, Mutex<CommandExecutor>>,
+    programs: Vec<String>,
+) -> Result<(), String> {
+    let mut executor = executor_state.lock().map_err(|e| e.to_string())?;
+    executor.set_allowed_programs(programs)
+}
+
+/// Définit la durée maximale et la taille de sortie maximale des commandes
+#[tauri::command]
+pub async fn set_command_limits(
+    executor_state: State<'_, Mutex<CommandExecutor>>,
+    timeout_secs: u64,
+    max_output_bytes: usize,
+) -> Result<(), String> {
+    let mut executor = executor_state.lock().map_err(|e| e.to_string())?;
+    executor.set_limits(timeout_secs, max_output_bytes)
+}
@@ -0,0 +1,184 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::SystemTime;
+use ignore::gitignore::GitignoreBuilder;
+use serde::Deserialize;
+use tauri::{AppHandle, Emitter, Manager, State, Wry};
+use tauri::async_runtime::JoinHandle;
+
+use crate::context_reader::{ConfigChanges, ContextReader, ContextReaderConfig};
+use crate::context_reader_commands::apply_context_config;
+use crate::settings_profile::ConfigSource;
+
+/// Intervalle de vérification du fichier surveillé (secondes)
+const CONFIG_WATCH_INTERVAL_SECS: u64 = 2;
+
+/// Configuration de contexte partagée par un projet (ex: .horizon/context.toml)
+/// Les champs absents conservent la valeur courante ; le scope n'est jamais modifié.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProjectContextFile {
+    allowed_extensions: Option<Vec<String>>,
+    max_file_size: Option<usize>,
+    max_total_size: Option<usize>,
+    redact_secrets: Option<bool>,
+    ignore: Option<Vec<String>>,
+}
+
+/// Surveillance en cours d'un fichier de configuration (une seule à la fois)
+#[derive(Default)]
+pub struct ConfigFileWatch {
+    task: Option<JoinHandle<()>>,
+    path: Option<PathBuf>,
+}
+
+impl ConfigFileWatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Arrête la surveillance en cours. Retourne false si aucune n'était active
+    fn stop(&mut self) -> bool {
+        self.path = None;
+        match self.task.take() {
+            Some(task) => {
+                task.abort();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Lit et valide le fichier, puis l'applique par-dessus la configuration `base`.
+/// Un fichier invalide ne modifie rien.
+fn load_config_file(path: &Path, base: ContextReaderConfig) -> Result<ContextReaderConfig, String> {
+    let raw = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read config file {}: {}", path.display(), e))?;
+    let file: ProjectContextFile = toml::from_str(&raw)
+        .map_err(|e| format!("Invalid config file {}: {}", path.display(), e))?;
+
+    let mut config = base;
+
+    if let Some(extensions) = file.allowed_extensions {
+        let mut allowed_extensions: Vec<String> = Vec::new();
+        for extension in extensions {
+            let extension = extension.trim().trim_start_matches('.').to_lowercase();
+            if !extension.is_empty() && !allowed_extensions.contains(&extension) {
+                allowed_extensions.push(extension);
+            }
+        }
+        if allowed_extensions.is_empty() {
+            return Err("Invalid config file: allowed_extensions cannot be empty".to_string());
+        }
+        config.allowed_extensions = allowed_extensions;
+    }
+
+    if let Some(size) = file.max_file_size {
+        config.max_file_size = size;
+    }
+    if let Some(size) = file.max_total_size {
+        config.max_total_size = size;
+    }
+    if config.max_file_size == 0 || config.max_total_size == 0 {
+        return Err("Invalid config file: size limits must be greater than 0".to_string());
+    }
+
+    if let Some(redact) = file.redact_secrets {
+        config.redact_secrets = redact;
+    }
+
+    if let Some(patterns) = file.ignore {
+        // Valider la syntaxe des règles avant application
+        let mut builder = GitignoreBuilder::new("");
+        for pattern in &patterns {
+            builder
+                .add_line(None, pattern)
+                .map_err(|e| format!("Invalid ignore pattern {}: {}", pattern, e))?;
+        }
+        config.ignore_patterns = patterns;
+    }
+
+    Ok(config)
+}
+
+/// Lit le fichier et l'applique à la configuration courante
+fn reload(app: &AppHandle<Wry>, path: &Path) -> Result<ConfigChanges, String> {
+    let base = {
+        let context_state = app.state::<Mutex<ContextReader<Wry>>>();
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.get_config()
+    };
+    let config = load_config_file(path, base)?;
+    apply_context_config(app, config, ConfigSource::File)
+}
+
+fn modified_at(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Recharge le fichier à chaque modification. Une erreur est signalée au frontend
+/// ("context-config-file-error") et la configuration précédente est conservée.
+fn spawn_watch(app: AppHandle<Wry>, path: PathBuf) -> JoinHandle<()> {
+    tauri::async_runtime::spawn(async move {
+        let mut last_modified = modified_at(&path);
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(CONFIG_WATCH_INTERVAL_SECS)).await;
+
+            let modified = modified_at(&path);
+            if modified == last_modified {
+                continue;
+            }
+            last_modified = modified;
+
+            if let Err(error) = reload(&app, &path) {
+                let _ = app.emit("context-config-file-error", serde_json::json!({
+                    "path": path.to_string_lossy(),
+                    "error": error
+                }));
+            }
+        }
+    })
+}
+
+/// Charge une configuration de contexte de projet (TOML : allowed_extensions, max_file_size,
+/// max_total_size, redact_secrets, ignore) et l'applique comme update_context_config.
+/// Avec `watch`, le fichier est rechargé automatiquement à chaque modification.
+#[tauri::command]
+pub async fn load_context_config_from_file(
+    app: AppHandle<Wry>,
+    watch_state: State<'_, Mutex<ConfigFileWatch>>,
+    path: String,
+    watch: Option<bool>,
+) -> Result<ConfigChanges, String> {
+    // Comme update_context_config, aucune permission requise (c'est une configuration)
+    let path = PathBuf::from(path);
+    let changes = reload(&app, &path)?;
+
+    let mut config_watch = watch_state.lock().map_err(|e| e.to_string())?;
+    config_watch.stop();
+    if watch.unwrap_or(false) {
+        config_watch.task = Some(spawn_watch(app.clone(), path.clone()));
+        config_watch.path = Some(path);
+    }
+
+    Ok(changes)
+}
+
+/// Arrête la surveillance du fichier de configuration. Retourne false si aucune n'était active
+#[tauri::command]
+pub async fn stop_context_config_watch(
+    watch_state: State<'_, Mutex<ConfigFileWatch>>,
+) -> Result<bool, String> {
+    let mut config_watch = watch_state.lock().map_err(|e| e.to_string())?;
+    Ok(config_watch.stop())
+}
+
+/// Fichier de configuration actuellement surveillé (None si aucun)
+#[tauri::command]
+pub async fn get_context_config_watch(
+    watch_state: State<'_, Mutex<ConfigFileWatch>>,
+) -> Result<Option<String>, String> {
+    let config_watch = watch_state.lock().map_err(|e| e.to_string())?;
+    Ok(config_watch.path.as_ref().map(|p| p.to_string_lossy().into_owned()))
+}
@@ -1,21 +1,66 @@
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::io::{Read, BufRead, BufReader};
+use std::io::{Read, BufRead, BufReader, Seek, SeekFrom};
 use serde::{Serialize, Deserialize};
-use tauri::{AppHandle, Runtime};
+use tauri::{AppHandle, Manager, Runtime};
 // use crate::permission_manager::Permission; // Non utilisé pour l'instant
 // use std::sync::Mutex; // Non utilisé pour l'instant
-use std::collections::HashMap;
-use chrono::{Utc, Duration};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use chrono::{DateTime, Utc, Duration};
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use crate::redaction::{redact_secrets, REDACTION_MARKER};
+use crate::egress_budget::record_egress;
+use crate::settings_profile::{ConfigSource, ConfigSources};
+
+/// Fichier d'exclusion à la racine du scope (syntaxe gitignore), indépendant de git
+pub(crate) const HORIZON_IGNORE_FILE: &str = ".horizonignore";
 
 /// Configuration pour la lecture de fichiers
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContextReaderConfig {
     pub allowed_extensions: Vec<String>,
     pub max_file_size: usize,
+    #[serde(default = "default_max_total_size")]
+    pub max_total_size: usize,  // Taille cumulée maximale pour read_multiple_files
     pub current_scope: Option<PathBuf>,
+    #[serde(default)]
+    pub redact_secrets: bool,  // Masquer les secrets détectés dans les lectures et previews
+    #[serde(default)]
+    pub ignore_patterns: Vec<String>,  // Exclusions (syntaxe gitignore) ajoutées à celles de .horizonignore
+    #[serde(default)]
+    pub lossy_decode: bool,  // Remplacer les octets UTF-8 invalides au lieu de refuser la lecture
+    #[serde(default = "default_confirmation_ttl_minutes")]
+    pub confirmation_ttl_minutes: u32,  // Validité d'un token de confirmation (preview -> lecture)
+    #[serde(default = "default_chars_per_token")]
+    pub chars_per_token: f32,  // Ratio caractères/token pour estimer la taille d'un contenu en tokens
+}
+
+fn default_max_total_size() -> usize {
+    5_000_000 // 5MB
+}
+
+fn default_confirmation_ttl_minutes() -> u32 {
+    5
+}
+
+fn default_chars_per_token() -> f32 {
+    4.0
 }
 
+/// Bornes de la validité d'un token de confirmation (minutes)
+pub const MIN_CONFIRMATION_TTL_MINUTES: u32 = 1;
+pub const MAX_CONFIRMATION_TTL_MINUTES: u32 = 60;
+
+/// Bornes du ratio caractères/token (une valeur aberrante fausserait toutes les estimations)
+const MIN_CHARS_PER_TOKEN: f32 = 1.0;
+const MAX_CHARS_PER_TOKEN: f32 = 16.0;
+/// Budget maximal accepté par read_file_by_token_budget
+pub const MAX_TOKEN_BUDGET: usize = 1_000_000;
+
 impl Default for ContextReaderConfig {
     fn default() -> Self {
         Self {
@@ -31,11 +76,331 @@ impl Default for ContextReaderConfig {
                 "yml".to_string(),
             ],
             max_file_size: 1_000_000, // 1MB
+            max_total_size: default_max_total_size(),
             current_scope: None,
+            redact_secrets: false,
+            ignore_patterns: Vec::new(),
+            lossy_decode: false,
+            confirmation_ttl_minutes: default_confirmation_ttl_minutes(),
+            chars_per_token: default_chars_per_token(),
         }
     }
 }
 
+/// Description d'une extension autorisée (pour l'UI des réglages)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionInfo {
+    pub extension: String,
+    pub label: String,
+    pub category: String,  // code | data | docs | other
+    pub is_default: bool,  // false = ajoutée par l'utilisateur
+}
+
+/// Libellé et catégorie connus d'une extension (table intégrée)
+fn describe_extension(extension: &str) -> Option<(&'static str, &'static str)> {
+    let described = match extension {
+        "py" => ("Python", "code"),
+        "js" => ("JavaScript", "code"),
+        "jsx" => ("JavaScript (JSX)", "code"),
+        "ts" => ("TypeScript", "code"),
+        "tsx" => ("TypeScript (TSX)", "code"),
+        "rs" => ("Rust", "code"),
+        "go" => ("Go", "code"),
+        "java" => ("Java", "code"),
+        "kt" => ("Kotlin", "code"),
+        "c" | "h" => ("C", "code"),
+        "cpp" | "hpp" | "cc" => ("C++", "code"),
+        "cs" => ("C#", "code"),
+        "rb" => ("Ruby", "code"),
+        "php" => ("PHP", "code"),
+        "swift" => ("Swift", "code"),
+        "sh" => ("Shell script", "code"),
+        "ps1" => ("PowerShell", "code"),
+        "sql" => ("SQL", "code"),
+        "html" => ("HTML", "code"),
+        "css" => ("CSS", "code"),
+        "json" => ("JSON", "data"),
+        "toml" => ("TOML", "data"),
+        "yaml" | "yml" => ("YAML", "data"),
+        "xml" => ("XML", "data"),
+        "csv" => ("CSV", "data"),
+        "ini" | "cfg" => ("INI configuration", "data"),
+        "md" => ("Markdown", "docs"),
+        "txt" => ("Plain text", "docs"),
+        "rst" => ("reStructuredText", "docs"),
+        _ => return None,
+    };
+    Some(described)
+}
+
+/// Type MIME d'une extension autorisée (text/plain par défaut)
+fn mime_type_for(extension: &str) -> &'static str {
+    match extension {
+        "js" | "jsx" => "text/javascript",
+        "ts" | "tsx" => "text/typescript",
+        "py" => "text/x-python",
+        "rs" => "text/x-rust",
+        "html" => "text/html",
+        "css" => "text/css",
+        "json" => "application/json",
+        "toml" => "application/toml",
+        "yaml" | "yml" => "application/yaml",
+        "xml" => "application/xml",
+        "csv" => "text/csv",
+        "md" => "text/markdown",
+        "sql" => "application/sql",
+        "sh" => "application/x-sh",
+        _ => "text/plain",
+    }
+}
+
+/// Métadonnées d'un fichier sans son contenu (budget de contexte, affichage)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStats {
+    pub path: String,
+    pub size_bytes: u64,
+    pub line_count: usize,
+    pub extension: String,
+    pub mime_type: String,
+    pub modified_at: Option<DateTime<Utc>>,
+}
+
+/// Résultat d'un contrôle de lecture ("exists", "is_file", "scope", "ignore", "extension", "size")
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadCheck {
+    pub check: String,
+    pub passed: bool,
+    pub error: Option<String>,
+}
+
+/// Pré-vérification d'une lecture : contrôles de read_file sans lire le contenu
+/// ni consommer la permission
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadPreflight {
+    pub path: String,
+    pub readable: bool,  // Tous les contrôles passent, FileRead est accordée et le mode sandbox inactif
+    pub checks: Vec<ReadCheck>,
+    pub permission_held: bool,
+    pub sandboxed: bool,
+}
+
+/// Taille d'un fichier (pour les classements)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileStat {
+    pub path: String,
+    pub size: usize,
+}
+
+/// Entrée d'une sélection écartée par resolve_selection
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedSelection {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Sélection mixte (fichiers + dossiers) transformée en liste plate de fichiers lisibles
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolvedSelection {
+    pub files: Vec<FileStat>,
+    pub total_size: u64,
+    pub skipped: Vec<SkippedSelection>,
+    pub truncated: bool,  // MAX_SELECTION_FILES atteint
+}
+
+/// Nombre maximal de fichiers retournés par resolve_selection
+const MAX_SELECTION_FILES: usize = 5000;
+
+/// Volume d'une extension dans le scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExtensionBreakdown {
+    pub extension: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+}
+
+/// Répartition du scope par extension (triée par volume décroissant, partielle si annulée)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScopeBreakdown {
+    pub root: String,
+    pub extensions: Vec<ExtensionBreakdown>,
+    pub cancelled: bool,
+}
+
+/// Fichiers au contenu identique
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateFileGroup {
+    pub sha256: String,
+    pub size: u64,
+    pub paths: Vec<String>,
+}
+
+/// Doublons du scope (partiel si annulé)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateFilesResult {
+    pub root: String,
+    pub groups: Vec<DuplicateFileGroup>,
+    pub wasted_bytes: u64,  // Octets occupés par les copies en trop
+    pub cancelled: bool,
+}
+
+/// Vue d'ensemble d'un repository (analyse RepoAnalyze, partielle si annulée)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositorySummary {
+    pub root: String,
+    pub file_count: usize,
+    pub total_bytes: u64,
+    pub total_lines: usize,
+    pub files_by_extension: BTreeMap<String, usize>,
+    pub largest_files: Vec<FileStat>,
+    pub languages: Vec<String>,  // Langages détectés, du plus représenté au moins représenté
+    pub cancelled: bool,
+}
+
+/// Part d'un langage dans le projet (pondérée par la taille)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LanguageStat {
+    pub language: String,
+    pub file_count: usize,
+    pub bytes: u64,
+    pub percentage: f64,  // Part des octets de code du projet (0-100)
+}
+
+/// Nombre de fichiers retournés dans le classement des plus gros fichiers
+const LARGEST_FILES_LIMIT: usize = 10;
+
+/// Nombre maximum de fichiers récents conservés
+const MAX_RECENT_FILES: usize = 50;
+/// Fichier de persistance des fichiers récents (dossier de données de l'app)
+const RECENT_FILES_FILE: &str = "recent_files.json";
+
+/// Fichier de persistance du dernier scope (dossier de données de l'app)
+const SCOPE_STATE_FILE: &str = "scope_state.json";
+
+/// Dernier scope utilisé et préférence de restauration au démarrage
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ScopeState {
+    pub last_scope: Option<PathBuf>,
+    #[serde(default)]
+    pub auto_restore_scope: bool,
+}
+
+/// Champs modifiés par une mise à jour de configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigChanges {
+    pub scope_changed: bool,
+    pub extensions_changed: bool,
+    pub limits_changed: bool,
+    #[serde(default)]
+    pub ignore_changed: bool,
+    pub invalidated_tokens: usize,  // Tokens de confirmation devenus invalides puis supprimés
+}
+
+/// Fichier lu avec succès via le context reader (panneau "récents")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentFile {
+    pub path: String,
+    pub accessed_at: DateTime<Utc>,
+}
+
+/// Corbeille des fichiers supprimés via delete_file (dossier de données de l'app)
+const TRASH_DIR: &str = "trash";
+/// Métadonnées d'origine conservées à côté de chaque fichier mis à la corbeille
+const TRASH_INFO_FILE: &str = "deleted.json";
+
+/// Fichier déplacé dans la corbeille de l'app (récupérable)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedFile {
+    pub path: String,
+    pub trash_path: String,
+    pub size: u64,
+    pub sha256: String,
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// Version du format de manifeste de contexte
+const CONTEXT_MANIFEST_VERSION: u32 = 1;
+
+/// Fichier fourni au contexte, tel qu'il était au moment de l'export
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextManifestEntry {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+    pub accessed_at: DateTime<Utc>,
+}
+
+/// Manifeste des fichiers lus récemment (reproductibilité d'une session)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextManifest {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub scope: Option<String>,
+    pub files: Vec<ContextManifestEntry>,
+    #[serde(default)]
+    pub missing: Vec<String>,  // Fichiers récents introuvables au moment de l'export
+}
+
+/// Échec de relecture d'un fichier du manifeste
+#[derive(Debug, Clone, Serialize)]
+pub struct ManifestReadError {
+    pub path: String,
+    pub error: String,
+}
+
+/// Contexte reconstruit à partir d'un manifeste
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadedContextManifest {
+    pub files: Vec<FileContent>,
+    pub changed: Vec<String>,  // Fichiers relus dont le contenu diffère de l'empreinte du manifeste
+    pub failed: Vec<ManifestReadError>,  // Fichiers hors scope, supprimés ou illisibles
+    pub not_read: Vec<String>,  // Fichiers non lus car la limite cumulée était atteinte
+}
+
+/// Résultat d'un scan de dossier (partiel si annulé)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanResult {
+    pub files: Vec<String>,
+    pub cancelled: bool,
+}
+
+/// Jetons d'annulation des scans en cours, indexés par id de requête
+#[derive(Default)]
+pub struct ScanRegistry {
+    tokens: HashMap<String, Arc<AtomicBool>>,
+}
+
+impl ScanRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre un scan et retourne son jeton d'annulation
+    pub fn register(&mut self, request_id: &str) -> Result<Arc<AtomicBool>, String> {
+        if self.tokens.contains_key(request_id) {
+            return Err(format!("A scan with request id '{}' is already running", request_id));
+        }
+        let token = Arc::new(AtomicBool::new(false));
+        self.tokens.insert(request_id.to_string(), token.clone());
+        Ok(token)
+    }
+
+    /// Signale l'annulation d'un scan. Retourne false si aucun scan ne correspond
+    pub fn cancel(&self, request_id: &str) -> bool {
+        match self.tokens.get(request_id) {
+            Some(token) => {
+                token.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Retire un scan terminé
+    pub fn unregister(&mut self, request_id: &str) {
+        self.tokens.remove(request_id);
+    }
+}
+
 /// Structure pour représenter un fichier lu
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileContent {
@@ -43,20 +408,199 @@ pub struct FileContent {
     pub content: String,
     pub size: usize,
     pub extension: String,
+    pub sha256: String,  // Empreinte du contenu (détection de changements)
+    #[serde(default)]
+    pub redactions: usize,  // Nombre de secrets masqués (si redact_secrets est activé)
+    #[serde(default)]
+    pub had_invalid_bytes: bool,  // Octets UTF-8 invalides remplacés (si lossy_decode est activé)
+}
+
+/// Résultat d'une lecture multiple (tronqué si la taille cumulée dépasse max_total_size)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultipleFilesResult {
+    pub files: Vec<FileContent>,
+    pub total_size: usize,
+    pub truncated: bool,
+    pub not_read: Vec<String>,  // Fichiers non lus car la limite cumulée était atteinte
+    #[serde(default)]
+    pub duplicates: Vec<DuplicatePath>,  // Entrées fusionnées car désignant un fichier déjà demandé
+}
+
+/// Entrée ignorée car elle désigne le même fichier qu'une entrée précédente
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicatePath {
+    pub path: String,
+    pub duplicate_of: String,
+}
+
+/// Contenu ajouté à un fichier depuis un offset (suivi de logs en cours d'écriture)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileChunk {
+    pub path: String,
+    pub content: String,  // Lignes complètes uniquement (une ligne en cours d'écriture attend le prochain appel)
+    pub start_offset: u64,
+    pub end_offset: u64,  // Offset à repasser au prochain appel
+    pub file_size: u64,
+    pub reset: bool,  // Le fichier a rétréci (rotation/troncature) : lecture reprise depuis le début
+    #[serde(default)]
+    pub redactions: usize,
+}
+
+/// Début d'un fichier tenant dans un budget de tokens estimé
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenBudgetedContent {
+    pub path: String,
+    pub content: String,
+    pub size: usize,  // Taille totale du fichier (octets)
+    pub extension: String,
+    pub max_tokens: usize,
+    pub tokens_estimated: usize,  // Estimation pour le contenu retourné
+    pub chars_per_token: f32,
+    pub truncated: bool,  // Le fichier ne tenait pas dans le budget : seul son début est retourné
+    #[serde(default)]
+    pub redactions: usize,
+    #[serde(default)]
+    pub had_invalid_bytes: bool,
+}
+
+/// Morceau d'un fichier découpé pour respecter une limite de contexte (lignes numérotées à partir de 1)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentChunk {
+    pub index: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub content: String,
+}
+
+/// Fichier découpé en morceaux ordonnés
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkedFile {
+    pub path: String,
+    pub total_lines: usize,
+    pub chunks: Vec<ContentChunk>,
+    pub redactions: usize,
+}
+
+/// Diff unifié entre deux fichiers du scope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileDiff {
+    pub path_a: String,
+    pub path_b: String,
+    pub unified_diff: String,
+    pub added_lines: usize,
+    pub removed_lines: usize,
+}
+
+impl FileDiff {
+    /// Calcule le diff ligne à ligne entre deux contenus lus
+    pub fn between(a: &FileContent, b: &FileContent) -> Self {
+        let diff = TextDiff::from_lines(&a.content, &b.content);
+
+        let mut added_lines = 0;
+        let mut removed_lines = 0;
+        for change in diff.iter_all_changes() {
+            match change.tag() {
+                ChangeTag::Insert => added_lines += 1,
+                ChangeTag::Delete => removed_lines += 1,
+                ChangeTag::Equal => {}
+            }
+        }
+
+        let unified_diff = diff
+            .unified_diff()
+            .context_radius(3)
+            .header(&a.path, &b.path)
+            .to_string();
+
+        Self {
+            path_a: a.path.clone(),
+            path_b: b.path.clone(),
+            unified_diff,
+            added_lines,
+            removed_lines,
+        }
+    }
+}
+
+/// Calcule l'empreinte SHA-256 (hexadécimale) d'un contenu
+pub fn content_sha256(bytes: &[u8]) -> String {
+    hex::encode(Sha256::digest(bytes))
+}
+
+/// Empreinte mémorisée par un token de confirmation, à calculer hors Mutex
+/// Les fichiers au-delà de `max_file_size` ne sont pas hachés (pas de détection de modification)
+pub fn confirmation_content_hash(file_path: &Path, max_file_size: usize) -> Option<String> {
+    let metadata = fs::metadata(file_path).ok().filter(|m| m.is_file())?;
+    if metadata.len() > max_file_size as u64 {
+        return None;
+    }
+    fs::read(file_path).ok().map(|bytes| content_sha256(&bytes))
+}
+
+/// Nombre maximum de tokens de confirmation conservés simultanément
+const MAX_CONFIRMATION_TOKENS: usize = 256;
+
+/// Usage d'un token de confirmation : un token n'est accepté que par le flux qui l'a émis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenPurpose {
+    Read,  // Preview -> read_file_confirmed
+    Delete,  // prepare_delete_file -> delete_file
+    Remote,  // prepare_remote_read -> read_remote_file
 }
 
 /// Structure pour stocker les tokens de confirmation
 struct ConfirmationToken {
     token: String,
     expiration: chrono::DateTime<Utc>,
+    content_hash: Option<String>,  // Empreinte du fichier au moment du preview
+}
+
+/// Token de confirmation en attente, sans sa valeur (diagnostic du flux preview -> lecture)
+#[derive(Debug, Clone, Serialize)]
+pub struct PendingConfirmationToken {
+    pub path: String,
+    pub purpose: TokenPurpose,
+    pub expires_at: String,
+    pub expired: bool,  // Expiré mais pas encore évincé (l'éviction a lieu à la génération)
+    pub has_content_hash: bool,
+}
+
+/// Token de confirmation validé (et consommé)
+pub struct ValidatedToken {
+    /// Empreinte du fichier au moment du preview (None pour les ressources distantes)
+    pub content_hash: Option<String>,
+}
+
+/// Règles d'exclusion compilées, réutilisées tant que le scope, les exclusions de la configuration
+/// et la date de modification de .horizonignore ne changent pas
+struct IgnoreRulesCache {
+    root: PathBuf,
+    patterns: Vec<String>,
+    modified: Option<std::time::SystemTime>,  // None : pas de fichier .horizonignore
+    rules: Option<Arc<Gitignore>>,
 }
 
 /// Gestionnaire de contexte local
 pub struct ContextReader<R: Runtime> {
     config: ContextReaderConfig,
     app_handle: AppHandle<R>,
-    // Tokens de confirmation pour lecture complète (path -> (token, expiration))
-    confirmation_tokens: HashMap<String, ConfirmationToken>,
+    // Tokens de confirmation ((usage, path) -> (token, expiration)) : un token de preview
+    // ne peut pas confirmer une suppression, et inversement
+    confirmation_tokens: HashMap<(TokenPurpose, String), ConfirmationToken>,
+    // Fichiers lus récemment (le plus récent en tête), persistés sur disque
+    recent_files: VecDeque<RecentFile>,
+    // Dernier scope défini (persisté pour le restaurer au prochain lancement)
+    scope_state: ScopeState,
+    // Scopes propres à une fenêtre (label -> dossier), prioritaires sur le scope partagé
+    window_scopes: HashMap<String, PathBuf>,
+    // Incrémenté à chaque changement de scope (partagé ou de fenêtre) : une lecture faite
+    // avec une config clonée vérifie qu'aucun changement n'a eu lieu pendant l'accès disque
+    scope_generation: u64,
+    // Provenance des valeurs de la configuration partagée (diagnostic)
+    config_sources: ConfigSources,
+    // Dernières règles d'exclusion compilées (une lecture par fichier ne les reconstruit pas)
+    ignore_cache: std::sync::Mutex<Option<IgnoreRulesCache>>,
 }
 
 impl<R: Runtime> ContextReader<R> {
@@ -66,65 +610,406 @@ impl<R: Runtime> ContextReader<R> {
             config: ContextReaderConfig::default(),
             app_handle: app_handle.clone(),
             confirmation_tokens: HashMap::new(),
+            recent_files: VecDeque::new(),
+            scope_state: ScopeState::default(),
+            window_scopes: HashMap::new(),
+            scope_generation: 0,
+            config_sources: ConfigSources::default(),
+            ignore_cache: std::sync::Mutex::new(None),
         }
     }
 
-    /// Génère un token de confirmation pour un fichier
-    /// Le token est valide pendant 5 minutes
-    pub fn generate_confirmation_token(&mut self, file_path: &Path) -> String {
-        let path_str = file_path.to_string_lossy().to_string();
-        let expiration = Utc::now() + Duration::minutes(5);
+    /// Chemin d'un fichier d'état dans le dossier de données de l'app
+    fn app_data_file(&self, name: &str) -> Option<PathBuf> {
+        self.app_handle
+            .path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| dir.join(name))
+    }
+
+    /// Écrit un état JSON dans le dossier de données (best-effort)
+    fn persist_app_data<T: Serialize>(&self, name: &str, value: &T) {
+        if let Some(path) = self.app_data_file(name) {
+            if let Some(dir) = path.parent() {
+                let _ = fs::create_dir_all(dir);
+            }
+            if let Ok(data) = serde_json::to_vec_pretty(value) {
+                if let Err(e) = fs::write(&path, data) {
+                    eprintln!("⚠️ Failed to persist {}: {}", name, e);
+                }
+            }
+        }
+    }
+
+    /// Recharge le dernier scope persisté et le réapplique si la restauration automatique
+    /// est activée et que le dossier existe toujours (appelé une fois au démarrage)
+    pub fn restore_scope_state(&mut self) {
+        let restored = self
+            .app_data_file(SCOPE_STATE_FILE)
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|data| serde_json::from_slice::<ScopeState>(&data).ok());
+        if let Some(state) = restored {
+            self.scope_state = state;
+            self.config_sources.set("auto_restore_scope", ConfigSource::File);
+        }
+
+        if self.scope_state.auto_restore_scope {
+            if let Some(last_scope) = self.scope_state.last_scope.clone() {
+                match self.set_scope(last_scope) {
+                    Ok(()) => self.config_sources.set("current_scope", ConfigSource::File),
+                    Err(e) => eprintln!("⚠️ Could not restore last scope: {}", e),
+                }
+            }
+        }
+    }
+
+    /// Dernier scope défini et préférence de restauration
+    pub fn scope_state(&self) -> ScopeState {
+        self.scope_state.clone()
+    }
+
+    /// Active/désactive la restauration du dernier scope au démarrage
+    pub fn set_auto_restore_scope(&mut self, enabled: bool) {
+        self.config_sources.set("auto_restore_scope", ConfigSource::Runtime);
+        self.scope_state.auto_restore_scope = enabled;
+        self.persist_app_data(SCOPE_STATE_FILE, &self.scope_state);
+    }
+
+    /// Recharge les fichiers récents persistés (appelé une fois au démarrage)
+    pub fn restore_recent_files(&mut self) {
+        let restored = self
+            .app_data_file(RECENT_FILES_FILE)
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|data| serde_json::from_slice::<VecDeque<RecentFile>>(&data).ok());
+        if let Some(mut recent) = restored {
+            recent.truncate(MAX_RECENT_FILES);
+            self.recent_files = recent;
+        }
+    }
+
+    /// Enregistre une lecture réussie (déplace le fichier en tête s'il est déjà présent)
+    pub fn record_recent_file(&mut self, path: &str) {
+        self.recent_files.retain(|f| f.path != path);
+        self.recent_files.push_front(RecentFile {
+            path: path.to_string(),
+            accessed_at: Utc::now(),
+        });
+        self.recent_files.truncate(MAX_RECENT_FILES);
+
+        // Persistance best-effort : un échec ne doit pas faire échouer la lecture
+        self.persist_app_data(RECENT_FILES_FILE, &self.recent_files);
+    }
+
+    /// Retourne les fichiers récents (les plus récents d'abord)
+    pub fn recent_files(&self, limit: usize) -> Vec<RecentFile> {
+        self.recent_files.iter().take(limit).cloned().collect()
+    }
+
+    /// Construit le manifeste de fichiers récents (taille et empreinte actuelles de chaque fichier)
+    /// Lit et hache les fichiers : à appeler hors Mutex, sur une liste clonée de `recent_files`
+    pub fn context_manifest(recent_files: &[RecentFile], scope: Option<String>) -> ContextManifest {
+        let mut files = Vec::new();
+        let mut missing = Vec::new();
+        for recent in recent_files {
+            let hashed = fs::metadata(&recent.path)
+                .map_err(|e| e.to_string())
+                .and_then(|metadata| {
+                    crate::ollama_installer::file_sha256(Path::new(&recent.path))
+                        .map(|sha256| (metadata.len(), sha256))
+                });
+            match hashed {
+                Ok((size, sha256)) => files.push(ContextManifestEntry {
+                    path: recent.path.clone(),
+                    size,
+                    sha256,
+                    accessed_at: recent.accessed_at,
+                }),
+                Err(_) => missing.push(recent.path.clone()),
+            }
+        }
+
+        ContextManifest {
+            version: CONTEXT_MANIFEST_VERSION,
+            created_at: Utc::now(),
+            scope,
+            files,
+            missing,
+        }
+    }
+
+    /// Relit les fichiers d'un manifeste avec les contrôles habituels (scope, extensions, taille)
+    /// Un fichier en échec n'interrompt pas les autres
+    pub async fn load_context_manifest(&self, manifest: &ContextManifest) -> Result<LoadedContextManifest, String> {
+        if manifest.version > CONTEXT_MANIFEST_VERSION {
+            return Err(format!(
+                "Unsupported context manifest version {} (max supported: {})",
+                manifest.version, CONTEXT_MANIFEST_VERSION
+            ));
+        }
+
+        let mut files = Vec::new();
+        let mut changed = Vec::new();
+        let mut failed = Vec::new();
+        let mut not_read = Vec::new();
+        let mut total_size: usize = 0;
+
+        for entry in &manifest.files {
+            if total_size + entry.size as usize > self.config.max_total_size {
+                not_read.push(entry.path.clone());
+                continue;
+            }
+            match self.read_file_with_permission(PathBuf::from(&entry.path)).await {
+                Ok(content) => {
+                    if content.sha256 != entry.sha256 {
+                        changed.push(content.path.clone());
+                    }
+                    total_size += content.size;
+                    files.push(content);
+                }
+                Err(error) => failed.push(ManifestReadError {
+                    path: entry.path.clone(),
+                    error,
+                }),
+            }
+        }
+
+        Ok(LoadedContextManifest { files, changed, failed, not_read })
+    }
+
+    /// Vérifie qu'un fichier peut être supprimé (mêmes règles que la lecture : scope,
+    /// exclusions et extensions autorisées, sans limite de taille)
+    pub fn validate_deletable(&self, file_path: &Path) -> Result<(), String> {
+        if !file_path.exists() {
+            return Err(format!("File does not exist: {}", file_path.display()));
+        }
+        if !file_path.is_file() {
+            return Err(format!("Path is not a file: {}", file_path.display()));
+        }
+        self.is_in_scope(file_path)?;
+        self.check_not_ignored(file_path)?;
+        self.is_allowed_extension(file_path)?;
+        Ok(())
+    }
+
+    /// Déplace un fichier dans la corbeille de l'app au lieu de le supprimer définitivement
+    /// (un sous-dossier par suppression, avec les métadonnées d'origine pour la restauration)
+    pub fn move_to_trash(&self, file_path: &Path) -> Result<DeletedFile, String> {
+        // Empreinte calculée par blocs : la taille du fichier n'est pas bornée ici
+        let size = fs::metadata(file_path)
+            .map_err(|e| format!("Failed to read file {}: {}", file_path.display(), e))?
+            .len();
+        let sha256 = crate::ollama_installer::file_sha256(file_path)?;
+        let file_name = file_path
+            .file_name()
+            .ok_or_else(|| format!("Invalid file path: {}", file_path.display()))?;
+
+        let entry_dir = self
+            .app_data_file(TRASH_DIR)
+            .ok_or("App data directory is unavailable")?
+            .join(uuid::Uuid::new_v4().to_string());
+        fs::create_dir_all(&entry_dir)
+            .map_err(|e| format!("Failed to create trash directory: {}", e))?;
+        let trash_path = entry_dir.join(file_name);
+
+        // rename échoue entre deux volumes : copier puis supprimer l'original
+        if fs::rename(file_path, &trash_path).is_err() {
+            fs::copy(file_path, &trash_path)
+                .map_err(|e| format!("Failed to move {} to trash: {}", file_path.display(), e))?;
+            if let Err(e) = fs::remove_file(file_path) {
+                let _ = fs::remove_file(&trash_path);
+                return Err(format!("Failed to delete {}: {}", file_path.display(), e));
+            }
+        }
+
+        let deleted = DeletedFile {
+            path: file_path.to_string_lossy().into_owned(),
+            trash_path: trash_path.to_string_lossy().into_owned(),
+            size,
+            sha256,
+            deleted_at: Utc::now(),
+        };
+        if let Ok(data) = serde_json::to_vec_pretty(&deleted) {
+            let _ = fs::write(entry_dir.join(TRASH_INFO_FILE), data);
+        }
+        Ok(deleted)
+    }
+
+    /// Génère un token de confirmation pour un fichier, utilisable uniquement pour `purpose`
+    /// Le token est valide pendant `confirmation_ttl_minutes` (5 par défaut) et mémorise
+    /// l'empreinte du fichier (`confirmation_content_hash`, calculée par l'appelant hors Mutex)
+    /// pour détecter une modification entre le preview et la lecture confirmée
+    pub fn generate_confirmation_token(
+        &mut self,
+        file_path: &Path,
+        purpose: TokenPurpose,
+        content_hash: Option<String>,
+    ) -> String {
+        let key = (purpose, file_path.to_string_lossy().to_string());
+        let expiration = Utc::now() + Duration::minutes(i64::from(self.confirmation_ttl_minutes()));
         
         // Token simple basé sur UUID
         use uuid::Uuid;
         let token = Uuid::new_v4().to_string();
+
+        // Éviction à l'insertion : tokens expirés, puis les plus anciens au-delà du plafond
+        self.evict_confirmation_tokens(&key);
         
-        // Stocker le token avec expiration
+        // Stocker le token avec expiration (remplace seulement un token de même usage)
         self.confirmation_tokens.insert(
-            path_str.clone(),
+            key,
             ConfirmationToken {
                 token: token.clone(),
                 expiration,
+                content_hash,
             },
         );
         
         token
     }
 
-    /// Valide un token de confirmation
-    pub fn validate_confirmation_token(&mut self, file_path: &Path, token: &str) -> bool {
-        let path_str = file_path.to_string_lossy().to_string();
+    /// Tokens de confirmation mémorisés (chemins et expirations, jamais les valeurs), triés par expiration
+    pub fn pending_confirmation_tokens(&self) -> Vec<PendingConfirmationToken> {
+        let now = Utc::now();
+        let mut tokens: Vec<(&(TokenPurpose, String), &ConfirmationToken)> = self.confirmation_tokens.iter().collect();
+        tokens.sort_by_key(|(_, ct)| ct.expiration);
+        tokens
+            .into_iter()
+            .map(|((purpose, path), ct)| PendingConfirmationToken {
+                path: path.clone(),
+                purpose: *purpose,
+                expires_at: ct.expiration.to_rfc3339(),
+                expired: ct.expiration <= now,
+                has_content_hash: ct.content_hash.is_some(),
+            })
+            .collect()
+    }
+
+    /// Supprime tous les tokens de confirmation. Retourne le nombre de tokens supprimés
+    pub fn clear_confirmation_tokens(&mut self) -> usize {
+        let cleared = self.confirmation_tokens.len();
+        self.confirmation_tokens.clear();
+        cleared
+    }
+
+    /// Retire les tokens expirés et, si le plafond est atteint, les plus anciens
+    /// (tous les tokens ont la même durée de vie : plus ancien = expiration la plus proche)
+    fn evict_confirmation_tokens(&mut self, incoming: &(TokenPurpose, String)) {
+        let now = Utc::now();
+        self.confirmation_tokens.retain(|_, ct| ct.expiration > now);
+
+        // Le token du même chemin et du même usage sera remplacé, il ne compte pas dans le plafond
+        while self.confirmation_tokens.len() >= MAX_CONFIRMATION_TOKENS
+            && !self.confirmation_tokens.contains_key(incoming)
+        {
+            let oldest = self.confirmation_tokens
+                .iter()
+                .min_by_key(|(_, ct)| ct.expiration)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(key) => {
+                    self.confirmation_tokens.remove(&key);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Valide un token de confirmation émis pour `purpose` (un token d'un autre usage est refusé)
+    /// Retourne le token consommé (avec l'empreinte du preview) s'il est valide
+    pub fn validate_confirmation_token(&mut self, file_path: &Path, token: &str, purpose: TokenPurpose) -> Option<ValidatedToken> {
+        let key = (purpose, file_path.to_string_lossy().to_string());
         let now = Utc::now();
         
         // Nettoyer les tokens expirés
         self.confirmation_tokens.retain(|_, ct| ct.expiration > now);
         
         // Vérifier si le path a un token valide qui correspond
-        if let Some(confirmation_token) = self.confirmation_tokens.get(&path_str) {
+        if let Some(confirmation_token) = self.confirmation_tokens.get(&key) {
             if confirmation_token.expiration > now && confirmation_token.token == token {
                 // Token valide, le consommer (une seule utilisation)
-                self.confirmation_tokens.remove(&path_str);
-                return true;
+                return self.confirmation_tokens
+                    .remove(&key)
+                    .map(|ct| ValidatedToken { content_hash: ct.content_hash });
             }
         }
         
-        false
+        None
     }
 
-    /// Définit le scope actuel (dossier de projet)
-    pub fn set_scope(&mut self, path: PathBuf) -> Result<(), String> {
-        // Vérifier que le chemin existe et est un dossier
+    /// Vérifie qu'un scope candidat existe et est un dossier
+    fn validate_scope_dir(path: &Path) -> Result<(), String> {
         if !path.exists() {
             return Err(format!("Path does not exist: {}", path.display()));
         }
         if !path.is_dir() {
             return Err(format!("Path is not a directory: {}", path.display()));
         }
+        Ok(())
+    }
+
+    /// Définit le scope actuel (dossier de projet)
+    pub fn set_scope(&mut self, path: PathBuf) -> Result<(), String> {
+        Self::validate_scope_dir(&path)?;
+
+        self.config.current_scope = Some(path.clone());
+        self.config_sources.set("current_scope", ConfigSource::Runtime);
+        self.scope_generation += 1;
+
+        // Mémoriser le scope pour pouvoir le restaurer au prochain lancement
+        self.scope_state.last_scope = Some(path);
+        self.persist_app_data(SCOPE_STATE_FILE, &self.scope_state);
+        Ok(())
+    }
+
+    /// Obtient le scope actuel (sans cloner toute la configuration)
+    pub fn current_scope(&self) -> Option<&PathBuf> {
+        self.config.current_scope.as_ref()
+    }
 
-        self.config.current_scope = Some(path);
+    /// Définit un scope propre à une fenêtre (non persisté, n'affecte pas les autres fenêtres)
+    pub fn set_window_scope(&mut self, label: &str, path: PathBuf) -> Result<(), String> {
+        Self::validate_scope_dir(&path)?;
+        self.window_scopes.insert(label.to_string(), path);
+        self.scope_generation += 1;
         Ok(())
     }
 
+    /// Supprime le scope propre à une fenêtre (elle revient au scope partagé)
+    pub fn clear_window_scope(&mut self, label: &str) -> bool {
+        let removed = self.window_scopes.remove(label).is_some();
+        if removed {
+            self.scope_generation += 1;
+        }
+        removed
+    }
+
+    /// Indique si la fenêtre a son propre scope
+    pub fn has_window_scope(&self, label: &str) -> bool {
+        self.window_scopes.contains_key(label)
+    }
+
+    /// Scope effectif d'une fenêtre : son scope propre, sinon le scope partagé
+    pub fn window_scope(&self, label: &str) -> Option<&PathBuf> {
+        self.window_scopes
+            .get(label)
+            .or(self.config.current_scope.as_ref())
+    }
+
+    /// Génération courante du scope (change à chaque modification de scope)
+    pub fn scope_generation(&self) -> u64 {
+        self.scope_generation
+    }
+
+    /// Configuration effective d'une fenêtre (scope propre substitué au scope partagé)
+    pub fn config_for_window(&self, label: &str) -> ContextReaderConfig {
+        let mut config = self.config.clone();
+        if let Some(scope) = self.window_scopes.get(label) {
+            config.current_scope = Some(scope.clone());
+        }
+        config
+    }
+
     /// Vérifie si un fichier est dans le scope autorisé
     fn is_in_scope(&self, file_path: &Path) -> Result<(), String> {
         if let Some(scope) = &self.config.current_scope {
@@ -139,6 +1024,102 @@ impl<R: Runtime> ContextReader<R> {
         Ok(())
     }
 
+    /// Règles .horizonignore de la racine du scope et exclusions de la configuration
+    /// (None si aucune règle ou sans scope). Recompilées seulement si le scope, les exclusions
+    /// ou la date de modification de .horizonignore ont changé.
+    fn ignore_rules(&self) -> Option<Arc<Gitignore>> {
+        let root = self.config.current_scope.as_ref()?;
+        let modified = fs::metadata(root.join(HORIZON_IGNORE_FILE))
+            .ok()
+            .filter(|metadata| metadata.is_file())
+            .map(|metadata| metadata.modified().unwrap_or(std::time::UNIX_EPOCH));
+
+        let mut cache = self.ignore_cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(cached) = cache.as_ref() {
+            if &cached.root == root && cached.patterns == self.config.ignore_patterns && cached.modified == modified {
+                return cached.rules.clone();
+            }
+        }
+
+        let rules = self.build_ignore_rules(root, modified.is_some()).map(Arc::new);
+        *cache = Some(IgnoreRulesCache {
+            root: root.clone(),
+            patterns: self.config.ignore_patterns.clone(),
+            modified,
+            rules: rules.clone(),
+        });
+        rules
+    }
+
+    /// Compile les règles d'exclusion du scope `root`
+    fn build_ignore_rules(&self, root: &Path, has_file: bool) -> Option<Gitignore> {
+        let ignore_file = root.join(HORIZON_IGNORE_FILE);
+        if !has_file && self.config.ignore_patterns.is_empty() {
+            return None;
+        }
+
+        let mut builder = GitignoreBuilder::new(root);
+        // Une ligne invalide est ignorée, les autres règles restent appliquées
+        if has_file {
+            if let Some(err) = builder.add(&ignore_file) {
+                eprintln!("⚠️ Invalid rule in {}: {}", ignore_file.display(), err);
+            }
+        }
+        for pattern in &self.config.ignore_patterns {
+            if let Err(err) = builder.add_line(None, pattern) {
+                eprintln!("⚠️ Invalid ignore pattern {}: {}", pattern, err);
+            }
+        }
+        builder.build().ok()
+    }
+
+    /// Indique si un chemin est exclu par les règles (lui-même ou un de ses dossiers parents)
+    fn is_ignored_by(rules: Option<&Gitignore>, path: &Path, is_dir: bool) -> bool {
+        match rules {
+            Some(rules) if path.starts_with(rules.path()) => {
+                rules.matched_path_or_any_parents(path, is_dir).is_ignore()
+            }
+            _ => false,
+        }
+    }
+
+    /// Vérifie qu'un fichier n'est pas exclu par .horizonignore
+    fn check_not_ignored(&self, path: &Path) -> Result<(), String> {
+        if Self::is_ignored_by(self.ignore_rules().as_deref(), path, false) {
+            return Err(format!(
+                "File {} is excluded from context by {}",
+                path.display(),
+                HORIZON_IGNORE_FILE
+            ));
+        }
+        Ok(())
+    }
+
+    /// Résout un chemin existant et vérifie qu'il est dans le scope
+    /// Contrairement à is_in_scope, un scope doit être défini et les chemins sont canonicalisés
+    /// (les `..` et liens symboliques ne permettent pas d'en sortir)
+    /// Le scope utilisé est le scope effectif de la fenêtre (voir window_scope)
+    pub fn resolve_in_window_scope(&self, label: &str, path: &Path) -> Result<PathBuf, String> {
+        let scope = self
+            .window_scope(label)
+            .ok_or("No scope set. Please select a project folder first.")?;
+        let scope = scope
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve scope {}: {}", scope.display(), e))?;
+        let resolved = path
+            .canonicalize()
+            .map_err(|e| format!("Failed to resolve path {}: {}", path.display(), e))?;
+
+        if !resolved.starts_with(&scope) {
+            return Err(format!(
+                "Path {} is outside the allowed scope {}",
+                path.display(),
+                scope.display()
+            ));
+        }
+        Ok(resolved)
+    }
+
     /// Vérifie si l'extension est autorisée
     fn is_allowed_extension(&self, path: &Path) -> Result<(), String> {
         if let Some(ext) = path.extension() {
@@ -167,78 +1148,495 @@ impl<R: Runtime> ContextReader<R> {
                 ));
             }
         }
-        Ok(())
+        Ok(())
+    }
+
+    /// Contrôles de `read_file_with_permission`, tous évalués (pas d'arrêt au premier échec)
+    /// et sans lire le contenu du fichier
+    pub fn read_checks(&self, file_path: &Path) -> Vec<ReadCheck> {
+        let exists = if file_path.exists() {
+            Ok(())
+        } else {
+            Err(format!("File does not exist: {}", file_path.display()))
+        };
+        let is_file = if file_path.is_file() {
+            Ok(())
+        } else {
+            Err(format!("Path is not a file: {}", file_path.display()))
+        };
+
+        [
+            ("exists", exists),
+            ("is_file", is_file),
+            ("scope", self.is_in_scope(file_path)),
+            ("ignore", self.check_not_ignored(file_path)),
+            ("extension", self.is_allowed_extension(file_path)),
+            ("size", self.check_file_size(file_path)),
+        ]
+        .into_iter()
+        .map(|(check, result)| ReadCheck {
+            check: check.to_string(),
+            passed: result.is_ok(),
+            error: result.err(),
+        })
+        .collect()
+    }
+
+    /// Lit le contenu d'un fichier avec toutes les vérifications de sécurité
+    pub async fn read_file_with_permission(
+        &self,
+        file_path: PathBuf,
+    ) -> Result<FileContent, String> {
+        // 1. Vérifier que le fichier existe
+        if !file_path.exists() {
+            return Err(format!("File does not exist: {}", file_path.display()));
+        }
+
+        // 2. Vérifier que c'est un fichier (pas un dossier)
+        if !file_path.is_file() {
+            return Err(format!("Path is not a file: {}", file_path.display()));
+        }
+
+        // 3. Vérifier le scope et les exclusions .horizonignore
+        self.is_in_scope(&file_path)?;
+        self.check_not_ignored(&file_path)?;
+
+        // 4. Vérifier l'extension
+        self.is_allowed_extension(&file_path)?;
+
+        // 5. Vérifier la taille
+        self.check_file_size(&file_path)?;
+
+        // 6. Demander la permission de lecture via événement Tauri
+        // Note: La vérification de permission est maintenant gérée dans les commandes Tauri
+        // via le PermissionManager. Cette méthode se contente de faire les validations
+        // de scope, extension et taille du fichier.
+
+        // 7. Lire le contenu du fichier
+        let mut file = fs::File::open(&file_path)
+            .map_err(|e| format!("Failed to open file {}: {}", file_path.display(), e))?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)
+            .map_err(|e| format!("Failed to read file {}: {}", file_path.display(), e))?;
+
+        // 8. Créer la structure de retour
+        let extension = file_path.extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        let metadata = fs::metadata(&file_path)
+            .map_err(|e| format!("Failed to get file metadata: {}", e))?;
+
+        // L'empreinte porte sur le contenu réel (pas sur la version décodée ou masquée)
+        let sha256 = content_sha256(&bytes);
+        let (content, had_invalid_bytes) = self.decode_text(bytes, &file_path.to_string_lossy())?;
+        let (content, redactions) = self.apply_redaction(content);
+
+        Ok(FileContent {
+            path: file_path.to_string_lossy().into_owned(),
+            content,
+            size: metadata.len() as usize,
+            extension,
+            sha256,
+            redactions,
+            had_invalid_bytes,
+        })
+    }
+
+    /// Décode un contenu en UTF-8 : strict par défaut, avec remplacement des octets
+    /// invalides si lossy_decode est activé (retourne alors un flag)
+    fn decode_text(&self, bytes: Vec<u8>, source: &str) -> Result<(String, bool), String> {
+        match String::from_utf8(bytes) {
+            Ok(content) => Ok((content, false)),
+            Err(err) if self.config.lossy_decode => {
+                Ok((String::from_utf8_lossy(err.as_bytes()).into_owned(), true))
+            }
+            Err(_) => Err(format!(
+                "File {} is not valid UTF-8 text (enable lossy_decode to read it anyway)",
+                source
+            )),
+        }
+    }
+
+    /// Lit les lignes ajoutées à un fichier depuis `offset` (mêmes vérifications que la lecture complète,
+    /// hormis la taille totale du fichier : chaque appel retourne au plus max_file_size octets).
+    /// Le contenu est décodé comme par read_file (UTF-8 strict sauf lossy_decode).
+    pub fn read_file_from_offset(&self, file_path: PathBuf, offset: u64) -> Result<FileChunk, String> {
+        if !file_path.is_file() {
+            return Err(format!("Path is not a file: {}", file_path.display()));
+        }
+        self.is_in_scope(&file_path)?;
+        self.check_not_ignored(&file_path)?;
+        self.is_allowed_extension(&file_path)?;
+
+        let mut file = fs::File::open(&file_path)
+            .map_err(|e| format!("Failed to open file {}: {}", file_path.display(), e))?;
+        let file_size = file
+            .metadata()
+            .map_err(|e| format!("Failed to get file metadata: {}", e))?
+            .len();
+
+        // Un offset au-delà de la fin signifie que le fichier a été tronqué ou remplacé
+        let reset = offset > file_size;
+        let start_offset = if reset { 0 } else { offset };
+
+        let to_read = (file_size - start_offset).min(self.config.max_file_size as u64);
+        let mut buffer = Vec::with_capacity(to_read as usize);
+        file.seek(SeekFrom::Start(start_offset))
+            .map_err(|e| format!("Failed to seek in file {}: {}", file_path.display(), e))?;
+        file.take(to_read)
+            .read_to_end(&mut buffer)
+            .map_err(|e| format!("Failed to read file {}: {}", file_path.display(), e))?;
+
+        // Ne rendre que des lignes complètes, sauf si une seule ligne dépasse la limite
+        let hit_cap = buffer.len() as u64 == self.config.max_file_size as u64;
+        match buffer.iter().rposition(|&b| b == b'\n') {
+            Some(last_newline) => buffer.truncate(last_newline + 1),
+            None if !hit_cap => buffer.clear(),
+            None => {
+                // Ligne coupée à la limite : s'arrêter au dernier caractère UTF-8 complet,
+                // la suite sera relue à partir de end_offset
+                if let Err(err) = std::str::from_utf8(&buffer) {
+                    if err.error_len().is_none() {
+                        buffer.truncate(err.valid_up_to());
+                    }
+                }
+            }
+        }
+
+        let end_offset = start_offset + buffer.len() as u64;
+        let (content, _) = self.decode_text(buffer, &file_path.to_string_lossy())?;
+        let (content, redactions) = self.apply_redaction(content);
+
+        Ok(FileChunk {
+            path: file_path.to_string_lossy().into_owned(),
+            content,
+            start_offset,
+            end_offset,
+            file_size,
+            reset,
+            redactions,
+        })
     }
 
-    /// Lit le contenu d'un fichier avec toutes les vérifications de sécurité
-    pub async fn read_file_with_permission(
-        &self,
-        file_path: PathBuf,
-    ) -> Result<FileContent, String> {
-        // 1. Vérifier que le fichier existe
-        if !file_path.exists() {
-            return Err(format!("File does not exist: {}", file_path.display()));
+    /// Ratio caractères/token effectif (borné même si la config a été modifiée à la main)
+    pub fn chars_per_token(&self) -> f32 {
+        if self.config.chars_per_token.is_finite() {
+            self.config.chars_per_token.clamp(MIN_CHARS_PER_TOKEN, MAX_CHARS_PER_TOKEN)
+        } else {
+            default_chars_per_token()
         }
+    }
 
-        // 2. Vérifier que c'est un fichier (pas un dossier)
+    /// Lit le début d'un fichier tenant dans `max_tokens` tokens estimés (ratio chars_per_token).
+    /// Mêmes vérifications qu'une lecture complète hormis la taille du fichier : seuls les octets
+    /// nécessaires sont lus, un gros fichier livre donc son début au lieu d'être refusé.
+    pub fn read_file_by_token_budget(&self, file_path: PathBuf, max_tokens: usize) -> Result<TokenBudgetedContent, String> {
+        if max_tokens == 0 || max_tokens > MAX_TOKEN_BUDGET {
+            return Err(format!("max_tokens must be between 1 and {}", MAX_TOKEN_BUDGET));
+        }
         if !file_path.is_file() {
             return Err(format!("Path is not a file: {}", file_path.display()));
         }
-
-        // 3. Vérifier le scope
         self.is_in_scope(&file_path)?;
-
-        // 4. Vérifier l'extension
+        self.check_not_ignored(&file_path)?;
         self.is_allowed_extension(&file_path)?;
 
-        // 5. Vérifier la taille
-        self.check_file_size(&file_path)?;
-
-        // 6. Demander la permission de lecture via événement Tauri
-        // Note: La vérification de permission est maintenant gérée dans les commandes Tauri
-        // via le PermissionManager. Cette méthode se contente de faire les validations
-        // de scope, extension et taille du fichier.
+        let chars_per_token = self.chars_per_token();
+        let max_chars = (max_tokens as f64 * chars_per_token as f64).floor() as usize;
 
-        // 7. Lire le contenu du fichier
-        let mut file = fs::File::open(&file_path)
+        let file = fs::File::open(&file_path)
             .map_err(|e| format!("Failed to open file {}: {}", file_path.display(), e))?;
+        let file_size = file
+            .metadata()
+            .map_err(|e| format!("Failed to get file metadata: {}", e))?
+            .len();
 
-        let mut content = String::new();
-        file.read_to_string(&mut content)
+        // Un caractère UTF-8 fait au plus 4 octets : lire au-delà serait inutile
+        let to_read = file_size.min(max_chars as u64 * 4);
+        let mut bytes = Vec::with_capacity(to_read as usize);
+        file.take(to_read)
+            .read_to_end(&mut bytes)
             .map_err(|e| format!("Failed to read file {}: {}", file_path.display(), e))?;
 
-        // 8. Créer la structure de retour
+        // La lecture partielle peut couper un caractère multi-octets en fin de buffer
+        let partial_read = (bytes.len() as u64) < file_size;
+        if partial_read {
+            if let Err(err) = std::str::from_utf8(&bytes) {
+                if err.error_len().is_none() {
+                    bytes.truncate(err.valid_up_to());
+                }
+            }
+        }
+
+        let (mut content, had_invalid_bytes) = self.decode_text(bytes, &file_path.to_string_lossy())?;
+        let mut truncated = partial_read;
+        if let Some((cut, _)) = content.char_indices().nth(max_chars) {
+            content.truncate(cut);
+            truncated = true;
+        }
+
+        let tokens_estimated = (content.chars().count() as f64 / chars_per_token as f64).ceil() as usize;
+        let (content, redactions) = self.apply_redaction(content);
         let extension = file_path.extension()
             .and_then(|ext| ext.to_str())
             .unwrap_or("")
             .to_string();
 
-        let metadata = fs::metadata(&file_path)
+        Ok(TokenBudgetedContent {
+            path: file_path.to_string_lossy().into_owned(),
+            content,
+            size: file_size as usize,
+            extension,
+            max_tokens,
+            tokens_estimated,
+            chars_per_token,
+            truncated,
+            redactions,
+            had_invalid_bytes,
+        })
+    }
+
+    /// Retourne taille, nombre de lignes et type d'un fichier sans le charger en mémoire
+    /// (mêmes vérifications de scope, d'exclusion et d'extension qu'une lecture)
+    pub fn get_file_stats(&self, file_path: PathBuf) -> Result<FileStats, String> {
+        if !file_path.is_file() {
+            return Err(format!("Path is not a file: {}", file_path.display()));
+        }
+        self.is_in_scope(&file_path)?;
+        self.check_not_ignored(&file_path)?;
+        self.is_allowed_extension(&file_path)?;
+
+        let file = fs::File::open(&file_path)
+            .map_err(|e| format!("Failed to open file {}: {}", file_path.display(), e))?;
+        let metadata = file
+            .metadata()
             .map_err(|e| format!("Failed to get file metadata: {}", e))?;
 
-        Ok(FileContent {
+        // Compter les lignes par blocs (une dernière ligne sans retour chariot compte aussi)
+        let mut reader = BufReader::new(file);
+        let mut line_count = 0;
+        let mut last_byte = None;
+        loop {
+            let buffer = reader
+                .fill_buf()
+                .map_err(|e| format!("Failed to read file {}: {}", file_path.display(), e))?;
+            if buffer.is_empty() {
+                break;
+            }
+            line_count += buffer.iter().filter(|&&b| b == b'\n').count();
+            last_byte = buffer.last().copied();
+            let consumed = buffer.len();
+            reader.consume(consumed);
+        }
+        if matches!(last_byte, Some(b) if b != b'\n') {
+            line_count += 1;
+        }
+
+        let extension = file_path.extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+
+        Ok(FileStats {
             path: file_path.to_string_lossy().into_owned(),
+            size_bytes: metadata.len(),
+            line_count,
+            mime_type: mime_type_for(&extension).to_string(),
+            extension,
+            modified_at: metadata.modified().ok().map(DateTime::<Utc>::from),
+        })
+    }
+
+    /// Valide une URL distante (HTTPS uniquement, extension autorisée)
+    pub fn validate_remote_url(&self, url: &str) -> Result<reqwest::Url, String> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|e| format!("Invalid URL {}: {}", url, e))?;
+
+        if parsed.scheme() != "https" {
+            return Err(format!("Only HTTPS URLs are allowed: {}", url));
+        }
+
+        self.is_allowed_extension(Path::new(parsed.path()))?;
+
+        Ok(parsed)
+    }
+
+    /// Lit un fichier distant (HTTPS) avec les mêmes limites de taille que les fichiers locaux
+    /// La vérification de permission (RemoteAccess) est gérée dans les commandes Tauri
+    pub async fn read_remote_file(&self, url: &str) -> Result<FileContent, String> {
+        let parsed = self.validate_remote_url(url)?;
+
+        // Pas de redirection automatique : une redirection pourrait mener vers http ou un hôte
+        // interne sans repasser par validate_remote_url
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+        let mut response = client
+            .get(parsed.clone())
+            .send()
+            .await
+            .map_err(|e| format!("HTTP request failed for {}: {}", url, e))?;
+
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or("unknown location");
+            return Err(format!(
+                "Remote file {} redirects to {} ({}). Redirects are not followed: read the final URL directly",
+                url, location, response.status()
+            ));
+        }
+
+        if !response.status().is_success() {
+            return Err(format!("HTTP error for {}: {}", url, response.status()));
+        }
+
+        // Refuser d'emblée si la taille annoncée dépasse la limite
+        if let Some(length) = response.content_length() {
+            if length > self.config.max_file_size as u64 {
+                return Err(format!(
+                    "Remote file {} is too large ({} bytes). Max allowed: {} bytes",
+                    url, length, self.config.max_file_size
+                ));
+            }
+        }
+
+        // Lire par morceaux pour ne jamais dépasser la limite (Content-Length absent ou mensonger)
+        let mut bytes: Vec<u8> = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to read response from {}: {}", url, e))?
+        {
+            record_egress(&self.app_handle, chunk.len() as u64);
+            if bytes.len() + chunk.len() > self.config.max_file_size {
+                return Err(format!(
+                    "Remote file {} is too large. Max allowed: {} bytes",
+                    url, self.config.max_file_size
+                ));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let size = bytes.len();
+        let sha256 = content_sha256(&bytes);
+        let (content, had_invalid_bytes) = self.decode_text(bytes, url)?;
+        let (content, redactions) = self.apply_redaction(content);
+
+        let extension = Path::new(parsed.path())
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        Ok(FileContent {
+            path: url.to_string(),
             content,
-            size: metadata.len() as usize,
+            size,
             extension,
+            sha256,
+            redactions,
+            had_invalid_bytes,
+        })
+    }
+
+    /// Lit un fichier (mêmes vérifications qu'une lecture complète) et le découpe en morceaux
+    pub async fn chunk_file(
+        &self,
+        file_path: PathBuf,
+        max_chunk_bytes: usize,
+        overlap: usize,
+    ) -> Result<ChunkedFile, String> {
+        let file = self.read_file_with_permission(file_path).await?;
+        let is_code = matches!(describe_extension(&file.extension.to_lowercase()), Some((_, "code")));
+        let chunks = split_into_chunks(&file.content, max_chunk_bytes, overlap, is_code);
+
+        Ok(ChunkedFile {
+            total_lines: count_lines(file.content.as_bytes()),
+            path: file.path,
+            chunks,
+            redactions: file.redactions,
         })
     }
 
-    /// Lit plusieurs fichiers avec validation
+    /// Masque les secrets si l'option redact_secrets est activée
+    fn apply_redaction(&self, content: String) -> (String, usize) {
+        if self.config.redact_secrets {
+            redact_secrets(&content)
+        } else {
+            (content, 0)
+        }
+    }
+
+    /// Dédoublonne les chemins (formes relatives/absolues d'un même fichier) en conservant
+    /// l'ordre des premières occurrences. Les chemins d'origine sont conservés pour la lecture.
+    fn dedupe_paths(file_paths: Vec<PathBuf>) -> (Vec<PathBuf>, Vec<DuplicatePath>) {
+        let mut seen: HashMap<PathBuf, String> = HashMap::new();
+        let mut unique = Vec::new();
+        let mut duplicates = Vec::new();
+
+        for path in file_paths {
+            let key = path.canonicalize().unwrap_or_else(|_| path.clone());
+            let display = path.to_string_lossy().into_owned();
+            match seen.get(&key) {
+                Some(first) => duplicates.push(DuplicatePath {
+                    path: display,
+                    duplicate_of: first.clone(),
+                }),
+                None => {
+                    seen.insert(key, display);
+                    unique.push(path);
+                }
+            }
+        }
+
+        (unique, duplicates)
+    }
+
+    /// Lit plusieurs fichiers avec validation (les doublons ne sont lus qu'une fois)
     pub async fn read_multiple_files(
         &self,
         file_paths: Vec<PathBuf>,
-    ) -> Result<Vec<FileContent>, String> {
+    ) -> Result<MultipleFilesResult, String> {
         let mut results = Vec::new();
+        let mut total_size: usize = 0;
+        let mut not_read = Vec::new();
+
+        let (file_paths, duplicates) = Self::dedupe_paths(file_paths);
+        let mut paths = file_paths.into_iter();
+        while let Some(path) = paths.next() {
+            // Vérifier la taille cumulée avant de lire (évite de charger un module entier)
+            let size = fs::metadata(&path).map(|m| m.len() as usize).unwrap_or(0);
+            if total_size + size > self.config.max_total_size {
+                not_read.push(path.to_string_lossy().into_owned());
+                not_read.extend(paths.map(|p| p.to_string_lossy().into_owned()));
+                break;
+            }
 
-        for path in file_paths {
             match self.read_file_with_permission(path).await {
-                Ok(content) => results.push(content),
+                Ok(content) => {
+                    total_size += content.size;
+                    results.push(content);
+                }
                 Err(e) => return Err(format!("Failed to read file: {}", e)),
             }
         }
 
-        Ok(results)
+        Ok(MultipleFilesResult {
+            files: results,
+            total_size,
+            truncated: !not_read.is_empty(),
+            not_read,
+            duplicates,
+        })
     }
 
     /// Scanne un dossier pour lister les fichiers (sans lire le contenu)
@@ -246,12 +1644,82 @@ impl<R: Runtime> ContextReader<R> {
         &self,
         dir_path: &Path,
         recursive: bool,
+    ) -> Result<Vec<PathBuf>, String> {
+        self.scan_directory_cancellable(dir_path, recursive, &AtomicBool::new(false))
+    }
+
+    /// Transforme une sélection de fichiers et de dossiers en liste plate de fichiers prêts à lire :
+    /// un fichier est gardé s'il passe les contrôles de lecture, un dossier est développé
+    /// (filtres d'extension et .horizonignore). Les doublons (même fichier canonique) sont retirés.
+    pub fn resolve_selection(&self, paths: &[String], recursive: bool) -> ResolvedSelection {
+        let mut seen = HashSet::new();
+        let mut resolved = ResolvedSelection {
+            files: Vec::new(),
+            total_size: 0,
+            skipped: Vec::new(),
+            truncated: false,
+        };
+
+        for input in paths {
+            let input_path = Path::new(input);
+            let candidates = if input_path.is_dir() {
+                match self.scan_directory(input_path, recursive) {
+                    Ok(files) => files,
+                    Err(reason) => {
+                        resolved.skipped.push(SkippedSelection { path: input.clone(), reason });
+                        continue;
+                    }
+                }
+            } else {
+                vec![input_path.to_path_buf()]
+            };
+
+            for path in candidates {
+                if resolved.files.len() >= MAX_SELECTION_FILES {
+                    resolved.truncated = true;
+                    return resolved;
+                }
+                let key = path.canonicalize().unwrap_or_else(|_| path.clone());
+                if !seen.insert(key) {
+                    continue;
+                }
+                if let Some(reason) = self.read_checks(&path).into_iter().find_map(|check| check.error) {
+                    resolved.skipped.push(SkippedSelection {
+                        path: path.to_string_lossy().into_owned(),
+                        reason,
+                    });
+                    continue;
+                }
+                let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                resolved.total_size += size;
+                resolved.files.push(FileStat {
+                    path: path.to_string_lossy().into_owned(),
+                    size: size as usize,
+                });
+            }
+        }
+
+        resolved
+    }
+
+    /// Scanne un dossier en vérifiant le jeton d'annulation à chaque dossier visité
+    /// Si le scan est annulé, les fichiers déjà trouvés sont retournés (résultat partiel)
+    pub fn scan_directory_cancellable(
+        &self,
+        dir_path: &Path,
+        recursive: bool,
+        cancel: &AtomicBool,
     ) -> Result<Vec<PathBuf>, String> {
         // Vérifier le scope
         self.is_in_scope(dir_path)?;
 
+        // Les fichiers et dossiers exclus par .horizonignore ne sont pas listés
+        let rules = self.ignore_rules();
         let mut files = Vec::new();
-        self.scan_directory_recursive(dir_path, recursive, &mut files)?;
+        self.scan_directory_recursive(dir_path, recursive, rules.as_deref(), cancel, &mut |path| {
+            files.push(path);
+            true
+        })?;
 
         // Filtrer par extensions autorisées
         let files: Vec<PathBuf> = files
@@ -262,25 +1730,278 @@ impl<R: Runtime> ContextReader<R> {
         Ok(files)
     }
 
+    /// Scanne un dossier en transmettant les fichiers par lots de `batch_size` à `on_batch`
+    /// (mêmes filtres que scan_directory). Le parcours s'arrête à `max_files` fichiers.
+    /// Retourne le nombre de fichiers transmis et si le parcours a été interrompu
+    pub fn scan_directory_streaming(
+        &self,
+        dir_path: &Path,
+        recursive: bool,
+        max_files: Option<usize>,
+        batch_size: usize,
+        cancel: &AtomicBool,
+        on_batch: &mut dyn FnMut(Vec<String>),
+    ) -> Result<(usize, bool), String> {
+        self.is_in_scope(dir_path)?;
+
+        let rules = self.ignore_rules();
+        let mut batch: Vec<String> = Vec::with_capacity(batch_size);
+        let mut total = 0;
+        let completed = self.scan_directory_recursive(dir_path, recursive, rules.as_deref(), cancel, &mut |path| {
+            if self.is_allowed_extension(&path).is_err() {
+                return true;
+            }
+            if max_files.is_some_and(|max| total >= max) {
+                return false;
+            }
+            total += 1;
+            batch.push(path.to_string_lossy().into_owned());
+            if batch.len() >= batch_size {
+                on_batch(std::mem::replace(&mut batch, Vec::with_capacity(batch_size)));
+            }
+            true
+        })?;
+        if !batch.is_empty() {
+            on_batch(batch);
+        }
+
+        Ok((total, !completed))
+    }
+
+    /// Répartit les fichiers du scope courant par extension (mêmes filtres que le scan)
+    pub fn extension_breakdown(&self, cancel: &AtomicBool) -> Result<ScopeBreakdown, String> {
+        let root = self.config.current_scope.clone()
+            .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+        let files = self.scan_directory_cancellable(&root, true, cancel)?;
+
+        let mut by_extension: HashMap<String, ExtensionBreakdown> = HashMap::new();
+        for path in &files {
+            let extension = path.extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            let entry = by_extension
+                .entry(extension.clone())
+                .or_insert_with(|| ExtensionBreakdown { extension, file_count: 0, total_bytes: 0 });
+            entry.file_count += 1;
+            entry.total_bytes += size;
+        }
+
+        let mut extensions: Vec<ExtensionBreakdown> = by_extension.into_values().collect();
+        extensions.sort_by(|a, b| b.total_bytes.cmp(&a.total_bytes).then(a.extension.cmp(&b.extension)));
+
+        Ok(ScopeBreakdown {
+            root: root.to_string_lossy().into_owned(),
+            extensions,
+            cancelled: cancel.load(Ordering::SeqCst),
+        })
+    }
+
+    /// Regroupe les fichiers identiques du scope courant (mêmes filtres que le scan)
+    /// Seuls les fichiers de même taille sont hachés ; les fichiers vides et ceux au-delà
+    /// de la taille maximale sont ignorés
+    pub fn find_duplicate_files(&self, cancel: &AtomicBool) -> Result<DuplicateFilesResult, String> {
+        let root = self.config.current_scope.clone()
+            .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+        let files = self.scan_directory_cancellable(&root, true, cancel)?;
+
+        let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        for path in files {
+            let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+            if size > 0 && size <= self.config.max_file_size as u64 {
+                by_size.entry(size).or_default().push(path);
+            }
+        }
+
+        let mut groups = Vec::new();
+        'sizes: for (size, paths) in by_size.into_iter().filter(|(_, paths)| paths.len() > 1) {
+            let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+            for path in paths {
+                if cancel.load(Ordering::SeqCst) {
+                    break 'sizes;
+                }
+                if let Ok(bytes) = fs::read(&path) {
+                    by_hash
+                        .entry(content_sha256(&bytes))
+                        .or_default()
+                        .push(path.to_string_lossy().into_owned());
+                }
+            }
+            groups.extend(
+                by_hash
+                    .into_iter()
+                    .filter(|(_, paths)| paths.len() > 1)
+                    .map(|(sha256, mut paths)| {
+                        paths.sort();
+                        DuplicateFileGroup { sha256, size, paths }
+                    }),
+            );
+        }
+
+        // Les groupes les plus coûteux d'abord
+        groups.sort_by(|a, b| {
+            let waste = |g: &DuplicateFileGroup| g.size * (g.paths.len() as u64 - 1);
+            waste(b).cmp(&waste(a)).then(a.paths.cmp(&b.paths))
+        });
+        let wasted_bytes = groups.iter().map(|g| g.size * (g.paths.len() as u64 - 1)).sum();
+
+        Ok(DuplicateFilesResult {
+            root: root.to_string_lossy().into_owned(),
+            groups,
+            wasted_bytes,
+            cancelled: cancel.load(Ordering::SeqCst),
+        })
+    }
+
+    /// Analyse le scope courant : fichiers par extension, lignes, plus gros fichiers, langages
+    /// La vérification de permission (RepoAnalyze) est gérée dans les commandes Tauri
+    pub fn analyze_repository(&self, cancel: &AtomicBool) -> Result<RepositorySummary, String> {
+        let root = self.config.current_scope.clone()
+            .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+        let mut files = self.scan_directory_cancellable(&root, true, cancel)?;
+
+        let mut files_by_extension: BTreeMap<String, usize> = BTreeMap::new();
+        let mut language_counts: HashMap<&'static str, usize> = HashMap::new();
+        let mut file_stats: Vec<FileStat> = Vec::new();
+        let mut total_bytes: u64 = 0;
+        let mut total_lines: usize = 0;
+
+        for (index, path) in files.iter().enumerate() {
+            // Annulé pendant le comptage des lignes : seuls les fichiers déjà comptés sont résumés
+            if cancel.load(Ordering::SeqCst) {
+                files.truncate(index);
+                break;
+            }
+            let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            total_bytes += size;
+
+            let extension = path.extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            *files_by_extension.entry(extension.clone()).or_insert(0) += 1;
+
+            if let Some((label, "code")) = describe_extension(&extension) {
+                *language_counts.entry(label).or_insert(0) += 1;
+            }
+
+            // Compter les lignes uniquement pour les fichiers sous la limite de taille
+            if size <= self.config.max_file_size as u64 {
+                if let Ok(bytes) = fs::read(path) {
+                    total_lines += count_lines(&bytes);
+                }
+            }
+
+            file_stats.push(FileStat {
+                path: path.to_string_lossy().into_owned(),
+                size: size as usize,
+            });
+        }
+
+        file_stats.sort_by_key(|f| std::cmp::Reverse(f.size));
+        file_stats.truncate(LARGEST_FILES_LIMIT);
+
+        let mut languages: Vec<(&'static str, usize)> = language_counts.into_iter().collect();
+        languages.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+        Ok(RepositorySummary {
+            root: root.to_string_lossy().into_owned(),
+            file_count: files.len(),
+            total_bytes,
+            total_lines,
+            files_by_extension,
+            largest_files: file_stats,
+            languages: languages.into_iter().map(|(label, _)| label.to_string()).collect(),
+            cancelled: cancel.load(Ordering::SeqCst),
+        })
+    }
+
+    /// Détecte les langages du scope courant, classés par volume de code
+    /// La contribution de chaque fichier est plafonnée à max_file_size pour que
+    /// quelques gros fichiers générés ne faussent pas le classement.
+    /// Un classement partiel serait trompeur : une détection annulée retourne une erreur
+    pub fn detect_project_language(&self, cancel: &AtomicBool) -> Result<Vec<LanguageStat>, String> {
+        let root = self.config.current_scope.clone()
+            .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+        let files = self.scan_directory_cancellable(&root, true, cancel)?;
+        if cancel.load(Ordering::SeqCst) {
+            return Err("Language detection cancelled".to_string());
+        }
+        let cap = self.config.max_file_size as u64;
+
+        let mut tally: HashMap<&'static str, (usize, u64)> = HashMap::new();
+        for path in &files {
+            let extension = path.extension()
+                .and_then(|ext| ext.to_str())
+                .unwrap_or("")
+                .to_lowercase();
+            if let Some((label, "code")) = describe_extension(&extension) {
+                let size = fs::metadata(path).map(|m| m.len()).unwrap_or(0).min(cap);
+                let entry = tally.entry(label).or_insert((0, 0));
+                entry.0 += 1;
+                entry.1 += size;
+            }
+        }
+
+        let total_bytes: u64 = tally.values().map(|(_, bytes)| bytes).sum();
+        let mut stats: Vec<LanguageStat> = tally
+            .into_iter()
+            .map(|(language, (file_count, bytes))| LanguageStat {
+                language: language.to_string(),
+                file_count,
+                bytes,
+                percentage: if total_bytes > 0 {
+                    (bytes as f64 * 10_000.0 / total_bytes as f64).round() / 100.0
+                } else {
+                    0.0
+                },
+            })
+            .collect();
+
+        stats.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.language.cmp(&b.language)));
+        Ok(stats)
+    }
+
+    /// Parcourt un dossier et passe chaque fichier trouvé à `on_file`
+    /// Retourne false si le parcours a été interrompu (annulation, ou `on_file` a retourné false)
     fn scan_directory_recursive(
         &self,
         dir_path: &Path,
         recursive: bool,
-        files: &mut Vec<PathBuf>,
-    ) -> Result<(), String> {
+        rules: Option<&Gitignore>,
+        cancel: &AtomicBool,
+        on_file: &mut dyn FnMut(PathBuf) -> bool,
+    ) -> Result<bool, String> {
+        if cancel.load(Ordering::SeqCst) {
+            return Ok(false);
+        }
         if let Ok(entries) = fs::read_dir(dir_path) {
             for entry in entries {
                 if let Ok(entry) = entry {
                     let path = entry.path();
+                    let is_dir = path.is_dir();
+                    if Self::is_ignored_by(rules, &path, is_dir) {
+                        continue;
+                    }
                     if path.is_file() {
-                        files.push(path);
-                    } else if path.is_dir() && recursive {
-                        self.scan_directory_recursive(&path, recursive, files)?;
+                        if !on_file(path) {
+                            return Ok(false);
+                        }
+                    } else if is_dir
+                        && recursive
+                        && !self.scan_directory_recursive(&path, recursive, rules, cancel, on_file)?
+                    {
+                        return Ok(false);
                     }
                 }
             }
         }
-        Ok(())
+        Ok(true)
     }
 
     /// Obtient la configuration actuelle
@@ -289,21 +2010,141 @@ impl<R: Runtime> ContextReader<R> {
     }
 
     /// Met à jour la configuration
+    /// Validité des tokens de confirmation (bornée même si la config a été modifiée à la main)
+    pub fn confirmation_ttl_minutes(&self) -> u32 {
+        self.config
+            .confirmation_ttl_minutes
+            .clamp(MIN_CONFIRMATION_TTL_MINUTES, MAX_CONFIRMATION_TTL_MINUTES)
+    }
+
+    /// Modifie la validité des prochains tokens (les tokens déjà émis gardent leur expiration)
+    pub fn set_confirmation_ttl_minutes(&mut self, minutes: u32) -> Result<(), String> {
+        if !(MIN_CONFIRMATION_TTL_MINUTES..=MAX_CONFIRMATION_TTL_MINUTES).contains(&minutes) {
+            return Err(format!(
+                "Confirmation token TTL must be between {} and {} minutes",
+                MIN_CONFIRMATION_TTL_MINUTES, MAX_CONFIRMATION_TTL_MINUTES
+            ));
+        }
+        self.config_sources.set_if_changed(
+            "confirmation_ttl_minutes",
+            &self.config.confirmation_ttl_minutes,
+            &minutes,
+            ConfigSource::Runtime,
+        );
+        self.config.confirmation_ttl_minutes = minutes;
+        Ok(())
+    }
+
     pub fn update_config(&mut self, new_config: ContextReaderConfig) {
         self.config = new_config;
     }
 
+    /// Applique une nouvelle configuration au ContextReader partagé en gardant l'état dérivé
+    /// cohérent : les tokens de confirmation hors du nouveau scope ou dont l'extension n'est
+    /// plus autorisée sont supprimés, et un nouveau scope est mémorisé comme dernier scope.
+    /// Les valeurs modifiées sont attribuées à `source` (fichier de configuration ou session).
+    pub fn apply_config(&mut self, mut new_config: ContextReaderConfig, source: ConfigSource) -> ConfigChanges {
+        new_config.confirmation_ttl_minutes = new_config
+            .confirmation_ttl_minutes
+            .clamp(MIN_CONFIRMATION_TTL_MINUTES, MAX_CONFIRMATION_TTL_MINUTES);
+        self.record_config_sources(&new_config, source);
+        let mut changes = ConfigChanges {
+            scope_changed: new_config.current_scope != self.config.current_scope,
+            extensions_changed: new_config.allowed_extensions != self.config.allowed_extensions,
+            limits_changed: new_config.max_file_size != self.config.max_file_size
+                || new_config.max_total_size != self.config.max_total_size,
+            ignore_changed: new_config.ignore_patterns != self.config.ignore_patterns,
+            invalidated_tokens: 0,
+        };
+        self.config = new_config;
+        if changes.scope_changed {
+            self.scope_generation += 1;
+        }
+
+        if changes.scope_changed || changes.extensions_changed {
+            let before = self.confirmation_tokens.len();
+            let tokens = std::mem::take(&mut self.confirmation_tokens);
+            self.confirmation_tokens = tokens
+                .into_iter()
+                .filter(|((_, path), _)| self.token_still_valid(path))
+                .collect();
+            changes.invalidated_tokens = before - self.confirmation_tokens.len();
+        }
+
+        if changes.scope_changed {
+            if let Some(scope) = self.config.current_scope.clone() {
+                self.scope_state.last_scope = Some(scope);
+                self.persist_app_data(SCOPE_STATE_FILE, &self.scope_state);
+            }
+        }
+
+        changes
+    }
+
+    fn record_config_sources(&mut self, new: &ContextReaderConfig, source: ConfigSource) {
+        let (old, sources) = (&self.config, &mut self.config_sources);
+        sources.set_if_changed("allowed_extensions", &old.allowed_extensions, &new.allowed_extensions, source);
+        sources.set_if_changed("max_file_size", &old.max_file_size, &new.max_file_size, source);
+        sources.set_if_changed("max_total_size", &old.max_total_size, &new.max_total_size, source);
+        sources.set_if_changed("current_scope", &old.current_scope, &new.current_scope, source);
+        sources.set_if_changed("redact_secrets", &old.redact_secrets, &new.redact_secrets, source);
+        sources.set_if_changed("ignore_patterns", &old.ignore_patterns, &new.ignore_patterns, source);
+        sources.set_if_changed("lossy_decode", &old.lossy_decode, &new.lossy_decode, source);
+        sources.set_if_changed("confirmation_ttl_minutes", &old.confirmation_ttl_minutes, &new.confirmation_ttl_minutes, source);
+        sources.set_if_changed("chars_per_token", &old.chars_per_token, &new.chars_per_token, source);
+    }
+
+    /// Provenance de chaque valeur de la configuration partagée (diagnostic)
+    pub fn config_sources(&self) -> ConfigSources {
+        self.config_sources.clone()
+    }
+
+    /// Un token reste valide si son fichier respecte encore le scope et les extensions
+    /// (les URLs distantes ne dépendent pas du scope)
+    fn token_still_valid(&self, path: &str) -> bool {
+        let is_remote = path.starts_with("https://");
+        let path = Path::new(path);
+        (is_remote || self.is_in_scope(path).is_ok()) && self.is_allowed_extension(path).is_ok()
+    }
+
     /// Ajoute une extension autorisée
     pub fn add_allowed_extension(&mut self, extension: String) {
         let ext = extension.to_lowercase();
         if !self.config.allowed_extensions.contains(&ext) {
             self.config.allowed_extensions.push(ext);
+            self.config_sources.set("allowed_extensions", ConfigSource::Runtime);
         }
     }
 
+    /// Liste les extensions autorisées avec libellé, catégorie et origine (défaut / ajoutée)
+    pub fn allowed_extensions_detailed(&self) -> Vec<ExtensionInfo> {
+        let defaults = ContextReaderConfig::default().allowed_extensions;
+
+        self.config
+            .allowed_extensions
+            .iter()
+            .map(|ext| {
+                let (label, category) = match describe_extension(ext) {
+                    Some((label, category)) => (label.to_string(), category.to_string()),
+                    None => (format!(".{} file", ext), "other".to_string()),
+                };
+                ExtensionInfo {
+                    extension: ext.clone(),
+                    label,
+                    category,
+                    is_default: defaults.contains(ext),
+                }
+            })
+            .collect()
+    }
+
     /// Supprime une extension autorisée
     pub fn remove_allowed_extension(&mut self, extension: &str) {
+        let before = self.config.allowed_extensions.len();
         self.config.allowed_extensions.retain(|e| e != &extension.to_lowercase());
+        if self.config.allowed_extensions.len() != before {
+            self.config_sources.set("allowed_extensions", ConfigSource::Runtime);
+        }
     }
 
     /// Obtient un preview d'un fichier (premières lignes seulement, sans permission)
@@ -323,8 +2164,9 @@ impl<R: Runtime> ContextReader<R> {
             return Err(format!("Path is not a file: {}", file_path.display()));
         }
 
-        // 3. Vérifier le scope (sécurité)
+        // 3. Vérifier le scope et les exclusions .horizonignore (sécurité)
         self.is_in_scope(&file_path)?;
+        self.check_not_ignored(&file_path)?;
 
         // 4. Vérifier l'extension (sécurité)
         self.is_allowed_extension(&file_path)?;
@@ -352,7 +2194,7 @@ impl<R: Runtime> ContextReader<R> {
             }
         }
 
-        let preview = preview_lines.join("\n");
+        let (preview, redactions) = self.apply_redaction(preview_lines.join("\n"));
         let metadata = fs::metadata(&file_path)
             .map_err(|e| format!("Failed to get file metadata: {}", e))?;
 
@@ -368,10 +2210,73 @@ impl<R: Runtime> ContextReader<R> {
             size: metadata.len() as usize,
             extension,
             line_count,
+            redactions,
         })
     }
 }
 
+/// Début probable d'une définition (fonction, classe, bloc) en colonne 0 : bonne frontière de découpe
+fn starts_definition(line: &str) -> bool {
+    const DEFINITION_PREFIXES: [&str; 14] = [
+        "fn ", "pub ", "impl ", "struct ", "enum ", "#[", "def ", "class ",
+        "async ", "function ", "export ", "const ", "func ", "@",
+    ];
+    !line.starts_with(char::is_whitespace) && DEFINITION_PREFIXES.iter().any(|p| line.starts_with(p))
+}
+
+/// Découpe un contenu en morceaux d'au plus `max_chunk_bytes` sur des frontières de ligne
+/// (une ligne plus longue que la limite forme un morceau à elle seule). Pour le code, la découpe
+/// recule jusqu'à une ligne vide ou un début de définition situé dans la seconde moitié du morceau.
+/// Chaque morceau reprend les `overlap` dernières lignes du précédent.
+pub fn split_into_chunks(content: &str, max_chunk_bytes: usize, overlap: usize, is_code: bool) -> Vec<ContentChunk> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let mut chunks = Vec::new();
+    let mut start = 0;
+
+    while start < lines.len() {
+        let mut end = start;
+        let mut size = 0;
+        while end < lines.len() && (end == start || size + lines[end].len() <= max_chunk_bytes) {
+            size += lines[end].len();
+            end += 1;
+        }
+
+        if is_code && end < lines.len() {
+            let min_end = start + (end - start) / 2;
+            if let Some(boundary) = (min_end.max(start + 1)..end)
+                .rev()
+                .find(|&i| lines[i].trim().is_empty() || starts_definition(lines[i]))
+            {
+                end = boundary;
+            }
+        }
+
+        chunks.push(ContentChunk {
+            index: chunks.len(),
+            start_line: start + 1,
+            end_line: end,
+            content: lines[start..end].concat(),
+        });
+
+        if end >= lines.len() {
+            break;
+        }
+        // Toujours avancer, même si le recouvrement couvre tout le morceau
+        start = end.saturating_sub(overlap).max(start + 1);
+    }
+
+    chunks
+}
+
+/// Compte les lignes d'un contenu (une dernière ligne sans retour chariot compte aussi)
+fn count_lines(bytes: &[u8]) -> usize {
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    match bytes.last() {
+        Some(&last) if last != b'\n' => newlines + 1,
+        _ => newlines,
+    }
+}
+
 /// Structure pour représenter un fichier avec preview
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FilePreview {
@@ -380,6 +2285,8 @@ pub struct FilePreview {
     pub size: usize,
     pub extension: String,
     pub line_count: usize,
+    #[serde(default)]
+    pub redactions: usize,
 }
 
 impl FilePreview {
@@ -388,6 +2295,8 @@ impl FilePreview {
         let preview_lines: Vec<&str> = content.content.lines().take(max_lines).collect();
         let preview = preview_lines.join("\n");
         let line_count = content.content.lines().count();
+        // Le contenu est déjà masqué : compter les marqueurs présents dans l'extrait
+        let redactions = preview.matches(REDACTION_MARKER).count();
 
         Self {
             path: content.path.clone(),
@@ -395,6 +2304,7 @@ impl FilePreview {
             size: content.size,
             extension: content.extension.clone(),
             line_count,
+            redactions,
         }
     }
 }
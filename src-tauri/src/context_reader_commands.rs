@@ -1,122 +1,1004 @@
-use tauri::{State, AppHandle, Wry};
-use std::sync::Mutex;
-use std::path::PathBuf;
-use crate::context_reader::{ContextReader, FileContent, ContextReaderConfig};
+use tauri::{State, AppHandle, Emitter, Manager, Window, Wry};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::{Path, PathBuf};
+use crate::context_reader::{confirmation_content_hash, ChunkedFile, ConfigChanges, ContextManifest, ContextReader, DeletedFile, DuplicateFilesResult, FileContent, ContextReaderConfig, ExtensionInfo, FileChunk, FileDiff, FileStats, FilePreview, LanguageStat, LoadedContextManifest, MultipleFilesResult, PendingConfirmationToken, ReadPreflight, RecentFile, ResolvedSelection, RepositorySummary, ScanRegistry, ScanResult, ScopeBreakdown, TokenBudgetedContent, TokenPurpose};
 use crate::permission_manager::{PermissionManager, Permission};
+use crate::permission_commands::ensure_permission;
+use crate::dependency_graph::{self, DependencyGraph, DependencyGraphCache};
+use crate::settings_profile::ConfigSource;
+use tauri_plugin_dialog::DialogExt;
 
-/// Helper pour vérifier la permission (sans auto-grant)
-/// En mode parano, la permission doit être explicitement accordée via l'UI
-async fn ensure_permission(
-    permission_state: &State<'_, Mutex<PermissionManager<Wry>>>,
-    permission: Permission,
-    context: &str,
-) -> Result<(), String> {
-    let mut manager = permission_state.lock().map_err(|e| e.to_string())?;
+#[tauri::command]
+pub async fn read_file(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    file_path: String,
+) -> Result<FileContent, String> {
+    // Vérifier la permission de lecture
+    ensure_permission(&permission_state, Permission::FileRead, &format!("Reading file: {}", file_path), "read_file").await?;
+
+    let path = PathBuf::from(file_path);
+    // Cloner le config avant le lock pour éviter de garder le MutexGuard pendant await
+    let (config, scope_generation) = read_config(&context_state, window.label())?;
     
-    // Vérifier si la permission existe
-    if !manager.has_permission(&permission) {
-        return Err(format!(
-            "Permission {:?} is required for: {}. Please grant it via the Permission Manager UI.",
-            permission, context
-        ));
+    // Créer un ContextReader temporaire avec la config clonée pour la validation
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+    
+    let content = temp_reader.read_file_with_permission(path).await?;
+    ensure_scope_unchanged(&context_state, scope_generation)?;
+    record_recent_file(&context_state, &content.path);
+    Ok(content)
+}
+
+/// Lit le contenu ajouté à un fichier depuis `byte_offset` (vue de logs en direct)
+/// Le frontend repasse `end_offset` au prochain appel pour n'obtenir que les nouvelles lignes
+#[tauri::command]
+pub async fn read_file_from_offset(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    file_path: String,
+    byte_offset: u64,
+) -> Result<FileChunk, String> {
+    ensure_permission(&permission_state, Permission::FileRead, &format!("Tailing file: {}", file_path), "read_file_from_offset").await?;
+
+    let (config, scope_generation) = read_config(&context_state, window.label())?;
+
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+
+    let chunk = temp_reader.read_file_from_offset(PathBuf::from(file_path), byte_offset)?;
+    ensure_scope_unchanged(&context_state, scope_generation)?;
+    Ok(chunk)
+}
+
+/// Lit le début d'un fichier tenant dans un budget de tokens estimé (ingestion par le modèle)
+/// `truncated` indique que le fichier a été coupé pour respecter `max_tokens`
+#[tauri::command]
+pub async fn read_file_by_token_budget(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    file_path: String,
+    max_tokens: usize,
+) -> Result<TokenBudgetedContent, String> {
+    ensure_permission(&permission_state, Permission::FileRead, &format!("Reading file: {}", file_path), "read_file_by_token_budget").await?;
+
+    let (config, scope_generation) = read_config(&context_state, window.label())?;
+
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+
+    let content = temp_reader.read_file_by_token_budget(PathBuf::from(file_path), max_tokens)?;
+    ensure_scope_unchanged(&context_state, scope_generation)?;
+    record_recent_file(&context_state, &content.path);
+    Ok(content)
+}
+
+/// Taille minimale d'un morceau pour chunk_file (octets)
+const MIN_CHUNK_BYTES: usize = 256;
+/// Recouvrement par défaut entre morceaux (lignes)
+const DEFAULT_CHUNK_OVERLAP: usize = 2;
+
+/// Découpe un fichier en morceaux ordonnés (frontières de ligne, de fonction pour le code)
+/// avec un léger recouvrement, pour alimenter un modèle à contexte limité
+#[tauri::command]
+pub async fn chunk_file(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    file_path: String,
+    max_chunk_bytes: usize,
+    overlap: Option<usize>,
+) -> Result<ChunkedFile, String> {
+    if max_chunk_bytes < MIN_CHUNK_BYTES {
+        return Err(format!("max_chunk_bytes must be at least {}", MIN_CHUNK_BYTES));
+    }
+
+    ensure_permission(&permission_state, Permission::FileRead, &format!("Chunking file: {}", file_path), "chunk_file").await?;
+
+    let (config, scope_generation) = read_config(&context_state, window.label())?;
+
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+
+    let chunked = temp_reader
+        .chunk_file(PathBuf::from(file_path), max_chunk_bytes, overlap.unwrap_or(DEFAULT_CHUNK_OVERLAP))
+        .await?;
+    ensure_scope_unchanged(&context_state, scope_generation)?;
+    record_recent_file(&context_state, &chunked.path);
+    Ok(chunked)
+}
+
+/// Compare deux fichiers du scope et retourne un diff unifié (sans renvoyer les contenus complets)
+#[tauri::command]
+pub async fn diff_files(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    path_a: String,
+    path_b: String,
+) -> Result<FileDiff, String> {
+    ensure_permission(
+        &permission_state,
+        Permission::FileRead,
+        &format!("Comparing files: {} and {}", path_a, path_b),
+        "diff_files",
+    ).await?;
+
+    // Vérification stricte du scope (chemins canonicalisés) avant toute lecture
+    let (config, scope_generation) = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.resolve_in_window_scope(window.label(), Path::new(&path_a))?;
+        context_reader.resolve_in_window_scope(window.label(), Path::new(&path_b))?;
+        (context_reader.config_for_window(window.label()), context_reader.scope_generation())
+    };
+
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+
+    let content_a = temp_reader.read_file_with_permission(PathBuf::from(&path_a)).await?;
+    let content_b = temp_reader.read_file_with_permission(PathBuf::from(&path_b)).await?;
+
+    ensure_scope_unchanged(&context_state, scope_generation)?;
+    Ok(FileDiff::between(&content_a, &content_b))
+}
+
+/// Config effective d'une fenêtre pour une lecture, avec la génération de scope correspondante
+fn read_config(
+    context_state: &State<'_, Mutex<ContextReader<Wry>>>,
+    label: &str,
+) -> Result<(ContextReaderConfig, u64), String> {
+    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    Ok((context_reader.config_for_window(label), context_reader.scope_generation()))
+}
+
+/// Rejette un contenu lu avec une config clonée si le scope a changé pendant la lecture
+/// (les règles appliquées ne sont plus celles en vigueur)
+fn ensure_scope_unchanged(
+    context_state: &State<'_, Mutex<ContextReader<Wry>>>,
+    generation: u64,
+) -> Result<(), String> {
+    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    if context_reader.scope_generation() != generation {
+        return Err("Scope changed while the file was being read. Please retry.".to_string());
+    }
+    Ok(())
+}
+
+/// Ajoute un fichier lu à la liste des récents du ContextReader partagé
+fn record_recent_file(context_state: &State<'_, Mutex<ContextReader<Wry>>>, path: &str) {
+    if let Ok(mut context_reader) = context_state.lock() {
+        context_reader.record_recent_file(path);
+    }
+}
+
+/// Nombre de fichiers récents retournés par défaut
+const DEFAULT_RECENT_FILES_LIMIT: usize = 20;
+
+/// Liste les fichiers lus récemment (les plus récents d'abord)
+#[tauri::command]
+pub async fn get_recent_files(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    limit: Option<usize>,
+) -> Result<Vec<RecentFile>, String> {
+    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    Ok(context_reader.recent_files(limit.unwrap_or(DEFAULT_RECENT_FILES_LIMIT)))
+}
+
+/// Exporte les fichiers lus récemment (chemin, taille, sha256, date d'accès) dans un manifeste JSON
+#[tauri::command]
+pub async fn export_context_manifest(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    path: String,
+) -> Result<ContextManifest, String> {
+    // Cloner la liste sous le verrou, hacher les fichiers hors verrou
+    let (recent_files, scope) = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        (
+            context_reader.recent_files(usize::MAX),
+            context_reader.current_scope().map(|p| p.to_string_lossy().into_owned()),
+        )
+    };
+    let manifest = tokio::task::spawn_blocking(move || ContextReader::<Wry>::context_manifest(&recent_files, scope))
+        .await
+        .map_err(|e| format!("Context manifest task failed: {}", e))?;
+
+    let json = serde_json::to_string_pretty(&manifest).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json)
+        .map_err(|e| format!("Failed to write context manifest {}: {}", path, e))?;
+    Ok(manifest)
+}
+
+/// Recharge les fichiers d'un manifeste de contexte (scope et permissions de lecture respectés)
+/// Les fichiers modifiés depuis l'export sont signalés dans `changed`
+#[tauri::command]
+pub async fn load_context_manifest(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    path: String,
+) -> Result<LoadedContextManifest, String> {
+    let data = std::fs::read(&path)
+        .map_err(|e| format!("Failed to read context manifest {}: {}", path, e))?;
+    let manifest: ContextManifest = serde_json::from_slice(&data)
+        .map_err(|e| format!("Invalid context manifest {}: {}", path, e))?;
+
+    ensure_permission(
+        &permission_state,
+        Permission::FileRead,
+        &format!("Loading context manifest: {} ({} files)", path, manifest.files.len()),
+        "load_context_manifest",
+    ).await?;
+
+    let (config, scope_generation) = read_config(&context_state, window.label())?;
+
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+
+    let loaded = temp_reader.load_context_manifest(&manifest).await?;
+    ensure_scope_unchanged(&context_state, scope_generation)?;
+    for content in &loaded.files {
+        record_recent_file(&context_state, &content.path);
     }
+    Ok(loaded)
+}
+
+#[tauri::command]
+pub async fn read_multiple_files(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    file_paths: Vec<String>,
+) -> Result<MultipleFilesResult, String> {
+    // Vérifier la permission de lecture
+    let paths_str = file_paths.join(", ");
+    ensure_permission(&permission_state, Permission::FileRead, &format!("Reading multiple files: {}", paths_str), "read_multiple_files").await?;
+
+    let paths: Vec<PathBuf> = file_paths.into_iter().map(PathBuf::from).collect();
+    let (config, scope_generation) = read_config(&context_state, window.label())?;
     
-    // En mode parano, consommer la permission (expire après usage)
-    manager.check_and_consume_permission(&permission, context)?;
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
     
-    Ok(())
+    let result = temp_reader.read_multiple_files(paths).await?;
+    ensure_scope_unchanged(&context_state, scope_generation)?;
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn scan_directory(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    scan_registry: State<'_, Mutex<ScanRegistry>>,
+    window: Window<Wry>,
+    directory_path: String,
+    recursive: bool,
+    request_id: Option<String>,
+) -> Result<ScanResult, String> {
+    // Vérifier la permission de lecture pour scanner le dossier
+    ensure_permission(&permission_state, Permission::FileRead, &format!("Scanning directory: {}", directory_path), "scan_directory").await?;
+
+    let path = PathBuf::from(directory_path);
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+
+    // Jeton d'annulation (annulable via cancel_scan uniquement si un request_id est fourni)
+    let cancel = match &request_id {
+        Some(id) => scan_registry.lock().map_err(|e| e.to_string())?.register(id)?,
+        None => Arc::new(AtomicBool::new(false)),
+    };
+
+    // Scanner hors du lock et hors du runtime async (les gros arbres peuvent prendre du temps)
+    let mut temp_reader = ContextReader::<Wry>::new(window.app_handle());
+    temp_reader.update_config(config);
+    let scan_cancel = cancel.clone();
+    let scanned = tokio::task::spawn_blocking(move || {
+        temp_reader.scan_directory_cancellable(&path, recursive, &scan_cancel)
+    })
+    .await
+    .map_err(|e| format!("Scan task failed: {}", e));
+
+    if let Some(id) = &request_id {
+        if let Ok(mut registry) = scan_registry.lock() {
+            registry.unregister(id);
+        }
+    }
+
+    let files = scanned??;
+
+    // Convert PathBuf to String for serialization
+    Ok(ScanResult {
+        files: files.into_iter().map(|p| p.to_string_lossy().into_owned()).collect(),
+        cancelled: cancel.load(Ordering::SeqCst),
+    })
+}
+
+/// Transforme une sélection mixte (fichiers et dossiers) en liste plate de fichiers lisibles,
+/// dédupliquée et avec leurs tailles (dossiers développés récursivement par défaut)
+#[tauri::command]
+pub async fn resolve_context_selection(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    window: Window<Wry>,
+    paths: Vec<String>,
+    recursive: Option<bool>,
+) -> Result<ResolvedSelection, String> {
+    ensure_permission(&permission_state, Permission::FileRead, &format!("Resolving selection of {} paths", paths.len()), "resolve_context_selection").await?;
+
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+
+    let mut temp_reader = ContextReader::<Wry>::new(window.app_handle());
+    temp_reader.update_config(config);
+    let recursive = recursive.unwrap_or(true);
+    tokio::task::spawn_blocking(move || temp_reader.resolve_selection(&paths, recursive))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Nombre de fichiers par événement "scan-entry"
+const SCAN_STREAM_BATCH: usize = 100;
+
+/// Variante récursive de scan_directory pour les gros arbres : les fichiers sont émis par lots
+/// ("scan-entry") au fur et à mesure, puis "scan-complete" donne le total.
+/// `truncated` est vrai si le scan a été annulé (cancel_scan) ou a atteint `max_files`.
+#[tauri::command]
+pub async fn scan_directory_stream(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    scan_registry: State<'_, Mutex<ScanRegistry>>,
+    window: Window<Wry>,
+    directory_path: String,
+    request_id: String,
+    max_files: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    ensure_permission(&permission_state, Permission::FileRead, &format!("Scanning directory: {}", directory_path), "scan_directory_stream").await?;
+
+    let path = PathBuf::from(directory_path);
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+
+    let cancel = scan_registry.lock().map_err(|e| e.to_string())?.register(&request_id)?;
+
+    let mut temp_reader = ContextReader::<Wry>::new(window.app_handle());
+    temp_reader.update_config(config);
+    let scan_cancel = cancel.clone();
+    let scan_id = request_id.clone();
+    let scan_window = window.clone();
+    let scanned = tokio::task::spawn_blocking(move || {
+        temp_reader.scan_directory_streaming(&path, true, max_files, SCAN_STREAM_BATCH, &scan_cancel, &mut |files| {
+            let _ = scan_window.emit("scan-entry", serde_json::json!({
+                "request_id": scan_id,
+                "files": files
+            }));
+        })
+    })
+    .await
+    .map_err(|e| format!("Scan task failed: {}", e));
+
+    if let Ok(mut registry) = scan_registry.lock() {
+        registry.unregister(&request_id);
+    }
+
+    let (total, truncated) = scanned??;
+    let summary = serde_json::json!({
+        "request_id": request_id,
+        "total": total,
+        "truncated": truncated,
+        "cancelled": cancel.load(Ordering::SeqCst)
+    });
+    let _ = window.emit("scan-complete", &summary);
+    Ok(summary)
+}
+
+/// Annule un scan en cours (le scan retourne rapidement un résultat partiel)
+#[tauri::command]
+pub async fn cancel_scan(
+    scan_registry: State<'_, Mutex<ScanRegistry>>,
+    request_id: String,
+) -> Result<bool, String> {
+    let registry = scan_registry.lock().map_err(|e| e.to_string())?;
+    Ok(registry.cancel(&request_id))
+}
+
+/// Calcule une vue d'ensemble du projet courant (nécessite RepoAnalyze + confirmation explicite)
+/// Annulable via cancel_scan si un request_id est fourni (résultat partiel)
+#[tauri::command]
+pub async fn analyze_repository(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    scan_registry: State<'_, Mutex<ScanRegistry>>,
+    window: Window<Wry>,
+    confirmed: bool,
+    request_id: Option<String>,
+) -> Result<RepositorySummary, String> {
+    if !confirmed {
+        return Err("Repository analysis requires explicit user confirmation.".to_string());
+    }
+
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+    let scope = config.current_scope.clone()
+        .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+    ensure_permission(
+        &permission_state,
+        Permission::RepoAnalyze,
+        &format!("Analyzing repository: {}", scope.display()),
+        "analyze_repository",
+    ).await?;
+
+    let cancel = match &request_id {
+        Some(id) => scan_registry.lock().map_err(|e| e.to_string())?.register(id)?,
+        None => Arc::new(AtomicBool::new(false)),
+    };
+
+    // Analyse hors Mutex et hors runtime async (peut être longue sur un gros projet)
+    let mut temp_reader = ContextReader::<Wry>::new(window.app_handle());
+    temp_reader.update_config(config);
+    let scan_cancel = cancel.clone();
+    let summary = tokio::task::spawn_blocking(move || temp_reader.analyze_repository(&scan_cancel))
+        .await
+        .map_err(|e| format!("Scan task failed: {}", e));
+
+    if let Some(id) = &request_id {
+        if let Ok(mut registry) = scan_registry.lock() {
+            registry.unregister(id);
+        }
+    }
+
+    summary?
+}
+
+/// Graphe des imports entre fichiers du scope (Python, JS/TS, Rust) pour donner au modèle
+/// la structure du projet. Mis en cache par scope tant qu'aucun fichier n'a changé.
+/// Annulable via cancel_scan si un request_id est fourni (résultat partiel, non mis en cache)
+#[tauri::command]
+pub async fn analyze_dependencies(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    graph_cache: State<'_, Mutex<DependencyGraphCache>>,
+    scan_registry: State<'_, Mutex<ScanRegistry>>,
+    window: Window<Wry>,
+    request_id: Option<String>,
+) -> Result<DependencyGraph, String> {
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+    let scope = config.current_scope.clone()
+        .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+    ensure_permission(
+        &permission_state,
+        Permission::RepoAnalyze,
+        &format!("Analyzing dependencies: {}", scope.display()),
+        "analyze_dependencies",
+    ).await?;
+
+    let cancel = match &request_id {
+        Some(id) => scan_registry.lock().map_err(|e| e.to_string())?.register(id)?,
+        None => Arc::new(AtomicBool::new(false)),
+    };
+
+    let graph = dependency_graph_for_scope(&graph_cache, window.app_handle(), config, scope, cancel).await;
+
+    if let Some(id) = &request_id {
+        if let Ok(mut registry) = scan_registry.lock() {
+            registry.unregister(id);
+        }
+    }
+
+    graph
+}
+
+/// Parcours et analyse hors runtime async ; le cache n'est consulté et rempli que pour un scan complet
+async fn dependency_graph_for_scope(
+    graph_cache: &Mutex<DependencyGraphCache>,
+    app: &AppHandle<Wry>,
+    config: ContextReaderConfig,
+    scope: PathBuf,
+    cancel: Arc<AtomicBool>,
+) -> Result<DependencyGraph, String> {
+    let max_file_size = config.max_file_size;
+    let mut temp_reader = ContextReader::<Wry>::new(app);
+    temp_reader.update_config(config);
+
+    // L'empreinte ne lit que les métadonnées : un scope inchangé est servi sans relire les fichiers
+    let scan_scope = scope.clone();
+    let scan_cancel = cancel.clone();
+    let (files, fingerprint) = tokio::task::spawn_blocking(move || {
+        let files = temp_reader.scan_directory_cancellable(&scan_scope, true, &scan_cancel)?;
+        let fingerprint = dependency_graph::scope_fingerprint(&scan_scope, &files);
+        Ok::<_, String>((files, fingerprint))
+    })
+    .await
+    .map_err(|e| format!("Scan task failed: {}", e))??;
+
+    if !cancel.load(Ordering::SeqCst) {
+        if let Some(graph) = graph_cache.lock().map_err(|e| e.to_string())?.get(&scope, &fingerprint) {
+            return Ok(graph);
+        }
+    }
+
+    let build_scope = scope.clone();
+    let build_cancel = cancel.clone();
+    let graph = tokio::task::spawn_blocking(move || {
+        dependency_graph::build_dependency_graph(&build_scope, &files, max_file_size, fingerprint, &build_cancel)
+    })
+    .await
+    .map_err(|e| format!("Scan task failed: {}", e))?;
+
+    if !graph.cancelled {
+        graph_cache.lock().map_err(|e| e.to_string())?.insert(scope, graph.clone());
+    }
+    Ok(graph)
+}
+
+/// Répartition du scope courant par extension (nombre de fichiers, octets), triée par volume
+/// Annulable via cancel_scan si un request_id est fourni (résultat partiel)
+#[tauri::command]
+pub async fn scope_extension_breakdown(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    scan_registry: State<'_, Mutex<ScanRegistry>>,
+    window: Window<Wry>,
+    request_id: Option<String>,
+) -> Result<ScopeBreakdown, String> {
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+    let scope = config.current_scope.clone()
+        .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+    ensure_permission(
+        &permission_state,
+        Permission::RepoAnalyze,
+        &format!("Computing extension breakdown: {}", scope.display()),
+        "scope_extension_breakdown",
+    ).await?;
+
+    let cancel = match &request_id {
+        Some(id) => scan_registry.lock().map_err(|e| e.to_string())?.register(id)?,
+        None => Arc::new(AtomicBool::new(false)),
+    };
+
+    let mut temp_reader = ContextReader::<Wry>::new(window.app_handle());
+    temp_reader.update_config(config);
+    let scan_cancel = cancel.clone();
+    let breakdown = tokio::task::spawn_blocking(move || temp_reader.extension_breakdown(&scan_cancel))
+        .await
+        .map_err(|e| format!("Scan task failed: {}", e));
+
+    if let Some(id) = &request_id {
+        if let Ok(mut registry) = scan_registry.lock() {
+            registry.unregister(id);
+        }
+    }
+
+    breakdown?
+}
+
+/// Recherche les fichiers identiques du scope courant (taille puis sha256)
+/// Annulable via cancel_scan si un request_id est fourni (résultat partiel)
+#[tauri::command]
+pub async fn find_duplicate_files(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    scan_registry: State<'_, Mutex<ScanRegistry>>,
+    window: Window<Wry>,
+    request_id: Option<String>,
+) -> Result<DuplicateFilesResult, String> {
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+    let scope = config.current_scope.clone()
+        .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+    ensure_permission(
+        &permission_state,
+        Permission::RepoAnalyze,
+        &format!("Finding duplicate files: {}", scope.display()),
+        "find_duplicate_files",
+    ).await?;
+
+    let cancel = match &request_id {
+        Some(id) => scan_registry.lock().map_err(|e| e.to_string())?.register(id)?,
+        None => Arc::new(AtomicBool::new(false)),
+    };
+
+    let mut temp_reader = ContextReader::<Wry>::new(window.app_handle());
+    temp_reader.update_config(config);
+    let scan_cancel = cancel.clone();
+    let duplicates = tokio::task::spawn_blocking(move || temp_reader.find_duplicate_files(&scan_cancel))
+        .await
+        .map_err(|e| format!("Scan task failed: {}", e));
+
+    if let Some(id) = &request_id {
+        if let Ok(mut registry) = scan_registry.lock() {
+            registry.unregister(id);
+        }
+    }
+
+    duplicates?
+}
+
+/// Détecte le(s) langage(s) principal(aux) du projet courant (nécessite RepoAnalyze)
+/// Annulable via cancel_scan si un request_id est fourni
+#[tauri::command]
+pub async fn detect_project_language(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    scan_registry: State<'_, Mutex<ScanRegistry>>,
+    window: Window<Wry>,
+    request_id: Option<String>,
+) -> Result<Vec<LanguageStat>, String> {
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+    let scope = config.current_scope.clone()
+        .ok_or_else(|| "No scope set. Please select a project folder first.".to_string())?;
+
+    ensure_permission(
+        &permission_state,
+        Permission::RepoAnalyze,
+        &format!("Detecting project language: {}", scope.display()),
+        "detect_project_language",
+    ).await?;
+
+    let cancel = match &request_id {
+        Some(id) => scan_registry.lock().map_err(|e| e.to_string())?.register(id)?,
+        None => Arc::new(AtomicBool::new(false)),
+    };
+
+    let mut temp_reader = ContextReader::<Wry>::new(window.app_handle());
+    temp_reader.update_config(config);
+    let scan_cancel = cancel.clone();
+    let languages = tokio::task::spawn_blocking(move || temp_reader.detect_project_language(&scan_cancel))
+        .await
+        .map_err(|e| format!("Scan task failed: {}", e));
+
+    if let Some(id) = &request_id {
+        if let Ok(mut registry) = scan_registry.lock() {
+            registry.unregister(id);
+        }
+    }
+
+    languages?
+}
+
+#[tauri::command]
+pub async fn get_context_config(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+) -> Result<ContextReaderConfig, String> {
+    // La lecture de la config ne nécessite pas de permission
+    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    Ok(context_reader.get_config())
+}
+
+/// Validité (minutes) des tokens de confirmation générés par les previews
+#[tauri::command]
+pub async fn get_confirmation_ttl(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+) -> Result<u32, String> {
+    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    Ok(context_reader.confirmation_ttl_minutes())
+}
+
+/// Modifie la validité des tokens de confirmation (1 à 60 minutes)
+/// Plus court = garantie preview-avant-lecture plus stricte, plus long = relecture confortable
+#[tauri::command]
+pub async fn set_confirmation_ttl(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    minutes: u32,
+) -> Result<(), String> {
+    // Configuration : pas de permission requise (comme update_context_config)
+    let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    context_reader.set_confirmation_ttl_minutes(minutes)
+}
+
+/// Liste les tokens de confirmation en attente (chemin et expiration, sans la valeur du token)
+/// Aide à diagnostiquer un "invalid token" de read_file_confirmed
+#[tauri::command]
+pub async fn get_pending_confirmation_tokens(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+) -> Result<Vec<PendingConfirmationToken>, String> {
+    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    Ok(context_reader.pending_confirmation_tokens())
+}
+
+/// Supprime tous les tokens de confirmation (les previews devront être refaites)
+#[tauri::command]
+pub async fn clear_confirmation_tokens(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+) -> Result<usize, String> {
+    let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    Ok(context_reader.clear_confirmation_tokens())
+}
+
+/// Retourne le scope effectif de la fenêtre appelante et s'il est défini (plus léger que get_context_config)
+/// `window_specific` indique si la fenêtre a son propre scope plutôt que le scope partagé
+#[tauri::command]
+pub async fn get_current_scope(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    window: Window<Wry>,
+) -> Result<serde_json::Value, String> {
+    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    let scope = context_reader
+        .window_scope(window.label())
+        .map(|p| p.to_string_lossy().into_owned());
+
+    Ok(serde_json::json!({
+        "is_set": scope.is_some(),
+        "scope": scope,
+        "window_specific": context_reader.has_window_scope(window.label())
+    }))
+}
+
+/// Indique si un chemin est dans le scope effectif de la fenêtre (après canonicalisation)
+/// Permet à l'UI de griser les fichiers hors scope sans tenter de lecture
+#[tauri::command]
+pub async fn is_path_in_scope(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    window: Window<Wry>,
+    path: String,
+) -> Result<serde_json::Value, String> {
+    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    let scope = context_reader
+        .window_scope(window.label())
+        .map(|p| p.to_string_lossy().into_owned());
+    let resolved = context_reader.resolve_in_window_scope(window.label(), Path::new(&path));
+
+    Ok(serde_json::json!({
+        "in_scope": resolved.is_ok(),
+        "scope": scope,
+        "reason": resolved.err()
+    }))
+}
+
+/// Compare deux scopes (chemins canonicalisés quand c'est possible)
+fn same_scope(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
 }
 
-#[tauri::command]
-pub async fn read_file(
-    context_state: State<'_, Mutex<ContextReader<Wry>>>,
-    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
-    app: AppHandle<Wry>,
-    file_path: String,
-) -> Result<FileContent, String> {
-    // Vérifier la permission de lecture
-    ensure_permission(&permission_state, Permission::FileRead, &format!("Reading file: {}", file_path)).await?;
+/// Notifie toutes les fenêtres d'un changement de scope
+/// Plusieurs fenêtres peuvent définir le scope partagé : le dernier appel l'emporte
+fn emit_scope_changed(
+    app: &AppHandle<Wry>,
+    label: &str,
+    scope: &Path,
+    previous: Option<&PathBuf>,
+    window_only: bool,
+) {
+    let _ = app.emit("scope-changed", serde_json::json!({
+        "scope": scope.to_string_lossy(),
+        "previous": previous.map(|p| p.to_string_lossy().into_owned()),
+        "window": label,
+        "window_only": window_only,
+        "semantics": "last_writer_wins",
+        "changed_at": chrono::Utc::now().to_rfc3339()
+    }));
+}
 
-    let path = PathBuf::from(file_path);
-    // Cloner le config avant le lock pour éviter de garder le MutexGuard pendant await
-    let config = {
-        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
-        context_reader.get_config()
+/// Applique un scope choisi : propre à la fenêtre si `window_only`, sinon partagé
+/// (la fenêtre qui définit le scope partagé abandonne alors son scope propre)
+/// Retourne false sans rien modifier si ce scope est déjà celui en vigueur
+fn apply_scope(
+    context_reader: &mut ContextReader<Wry>,
+    window: &Window<Wry>,
+    path: PathBuf,
+    window_only: bool,
+) -> Result<bool, String> {
+    let label = window.label();
+    let has_own_scope = context_reader.has_window_scope(label);
+    let previous = if window_only || has_own_scope {
+        context_reader.window_scope(label).cloned()
+    } else {
+        context_reader.current_scope().cloned()
     };
-    
-    // Créer un ContextReader temporaire avec la config clonée pour la validation
-    let mut temp_reader = ContextReader::<Wry>::new(&app);
-    temp_reader.update_config(config);
-    
-    temp_reader.read_file_with_permission(path).await
+
+    let unchanged = if window_only {
+        has_own_scope && previous.as_deref().is_some_and(|p| same_scope(p, &path))
+    } else {
+        !has_own_scope && previous.as_deref().is_some_and(|p| same_scope(p, &path))
+    };
+    if unchanged {
+        return Ok(false);
+    }
+
+    if window_only {
+        context_reader.set_window_scope(label, path.clone())?;
+    } else {
+        context_reader.set_scope(path.clone())?;
+        context_reader.clear_window_scope(label);
+    }
+    emit_scope_changed(window.app_handle(), label, &path, previous.as_ref(), window_only);
+    Ok(true)
 }
 
 #[tauri::command]
-pub async fn read_multiple_files(
+pub async fn set_context_scope(
     context_state: State<'_, Mutex<ContextReader<Wry>>>,
-    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
-    app: AppHandle<Wry>,
-    file_paths: Vec<String>,
-) -> Result<Vec<FileContent>, String> {
-    // Vérifier la permission de lecture
-    let paths_str = file_paths.join(", ");
-    ensure_permission(&permission_state, Permission::FileRead, &format!("Reading multiple files: {}", paths_str)).await?;
-
-    let paths: Vec<PathBuf> = file_paths.into_iter().map(PathBuf::from).collect();
-    let config = {
-        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
-        context_reader.get_config()
-    };
-    
-    let mut temp_reader = ContextReader::<Wry>::new(&app);
-    temp_reader.update_config(config);
-    
-    temp_reader.read_multiple_files(paths).await
+    window: Window<Wry>,
+    scope_path: String,
+    window_only: Option<bool>,
+) -> Result<bool, String> {
+    // Le changement de scope ne nécessite pas de permission (c'est une configuration)
+    // Retourne false si le scope demandé est déjà en vigueur (aucun événement émis)
+    let path = PathBuf::from(scope_path);
+    let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    apply_scope(&mut context_reader, &window, path, window_only.unwrap_or(false))
 }
 
+/// Supprime le scope propre à la fenêtre appelante (elle revient au scope partagé)
 #[tauri::command]
-pub async fn scan_directory(
+pub async fn clear_window_scope(
     context_state: State<'_, Mutex<ContextReader<Wry>>>,
-    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
-    directory_path: String,
-    recursive: bool,
-) -> Result<Vec<String>, String> {
-    // Vérifier la permission de lecture pour scanner le dossier
-    ensure_permission(&permission_state, Permission::FileRead, &format!("Scanning directory: {}", directory_path)).await?;
+    window: Window<Wry>,
+) -> Result<bool, String> {
+    let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    Ok(context_reader.clear_window_scope(window.label()))
+}
 
-    let path = PathBuf::from(directory_path);
+/// Retourne le dernier scope défini (persisté entre les lancements)
+#[tauri::command]
+pub async fn get_last_scope(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+) -> Result<serde_json::Value, String> {
     let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    let state = context_reader.scope_state();
 
-    let files = context_reader.scan_directory(&path, recursive)?;
+    Ok(serde_json::json!({
+        "last_scope": state.last_scope.as_ref().map(|p| p.to_string_lossy().into_owned()),
+        "exists": state.last_scope.as_ref().is_some_and(|p| p.is_dir()),
+        "auto_restore_scope": state.auto_restore_scope
+    }))
+}
 
-    // Convert PathBuf to String for serialization
-    Ok(files.into_iter().map(|p| p.to_string_lossy().into_owned()).collect())
+/// Réapplique le dernier scope défini
+#[tauri::command]
+pub async fn restore_last_scope(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    window: Window<Wry>,
+) -> Result<String, String> {
+    let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    let last_scope = context_reader
+        .scope_state()
+        .last_scope
+        .ok_or("No previous scope to restore")?;
+    apply_scope(&mut context_reader, &window, last_scope.clone(), false)?;
+    Ok(last_scope.to_string_lossy().into_owned())
 }
 
+/// Active/désactive la restauration automatique du dernier scope au démarrage
 #[tauri::command]
-pub async fn get_context_config(
+pub async fn set_auto_restore_scope(
     context_state: State<'_, Mutex<ContextReader<Wry>>>,
-) -> Result<ContextReaderConfig, String> {
-    // La lecture de la config ne nécessite pas de permission
-    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
-    Ok(context_reader.get_config())
+    enabled: bool,
+) -> Result<(), String> {
+    let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    context_reader.set_auto_restore_scope(enabled);
+    Ok(())
 }
 
+/// Affiche un fichier ou dossier du scope dans le gestionnaire de fichiers de l'OS
 #[tauri::command]
-pub async fn set_context_scope(
+pub async fn reveal_in_file_manager(
     context_state: State<'_, Mutex<ContextReader<Wry>>>,
-    scope_path: String,
+    window: Window<Wry>,
+    path: String,
 ) -> Result<(), String> {
-    // Le changement de scope ne nécessite pas de permission (c'est une configuration)
-    let path = PathBuf::from(scope_path);
+    let resolved = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.resolve_in_window_scope(window.label(), Path::new(&path))?
+    };
+
+    reveal_path(&resolved).map_err(|e| format!("Failed to open file manager: {}", e))
+}
+
+#[cfg(target_os = "windows")]
+fn reveal_path(path: &Path) -> std::io::Result<()> {
+    use std::os::windows::process::CommandExt;
+    // Explorer ne comprend pas le préfixe verbatim ajouté par canonicalize
+    let display = path.to_string_lossy();
+    let display = display.strip_prefix(r"\\?\").unwrap_or(&display);
+    // explorer retourne un code non nul même en cas de succès : on ne l'attend pas
+    std::process::Command::new("explorer")
+        .arg(format!("/select,{}", display))
+        .creation_flags(0x08000000) // CREATE_NO_WINDOW
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(target_os = "macos")]
+fn reveal_path(path: &Path) -> std::io::Result<()> {
+    std::process::Command::new("open")
+        .arg("-R")
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn reveal_path(path: &Path) -> std::io::Result<()> {
+    // xdg-open ne sait pas sélectionner un élément : ouvrir le dossier parent
+    std::process::Command::new("xdg-open")
+        .arg(path.parent().unwrap_or(path))
+        .spawn()
+        .map(|_| ())
+}
+
+/// Ouvre le sélecteur de dossier natif et définit le scope sur le dossier choisi
+/// Retourne None si l'utilisateur annule la sélection
+#[tauri::command]
+pub async fn pick_scope_directory(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    window_only: Option<bool>,
+) -> Result<Option<String>, String> {
+    let current_scope = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.window_scope(window.label()).cloned()
+    };
+
+    let mut dialog = app.dialog().file().set_title("Select project folder");
+    if let Some(scope) = current_scope {
+        dialog = dialog.set_directory(scope);
+    }
+
+    // Le dialogue est asynchrone côté plugin : attendre le résultat via un canal
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    dialog.pick_folder(move |folder| {
+        let _ = tx.send(folder);
+    });
+
+    let folder = match rx.await.map_err(|e| format!("Folder dialog failed: {}", e))? {
+        Some(folder) => folder,
+        None => return Ok(None),
+    };
+
+    let path = folder
+        .into_path()
+        .map_err(|e| format!("Invalid folder selection: {}", e))?;
+
+    // Valider immédiatement le chemin choisi via set_scope
     let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
-    context_reader.set_scope(path)
+    apply_scope(&mut context_reader, &window, path.clone(), window_only.unwrap_or(false))?;
+
+    Ok(Some(path.to_string_lossy().into_owned()))
 }
 
 #[tauri::command]
 pub async fn get_file_preview(
     context_state: State<'_, Mutex<ContextReader<Wry>>>,
     app: AppHandle<Wry>,
+    window: Window<Wry>,
     file_path: String,
     max_lines: Option<usize>,
 ) -> Result<serde_json::Value, String> {
@@ -126,19 +1008,22 @@ pub async fn get_file_preview(
     let path = PathBuf::from(file_path.clone());
     let max = max_lines.unwrap_or(50);
     
-    let (config, confirmation_token) = {
-        let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
-        let config = context_reader.get_config();
-        // Générer un token de confirmation
-        let token = context_reader.generate_confirmation_token(&path);
-        (config, token)
-    };
+    let (config, scope_generation) = read_config(&context_state, window.label())?;
+    let max_file_size = config.max_file_size;
     
     let mut temp_reader = ContextReader::<Wry>::new(&app);
     temp_reader.update_config(config);
     
     // Utiliser la nouvelle méthode qui lit seulement les premières lignes
-    let preview = temp_reader.get_file_preview(path, max)?;
+    let preview = temp_reader.get_file_preview(path.clone(), max)?;
+    ensure_scope_unchanged(&context_state, scope_generation)?;
+    
+    // Générer un token de confirmation une fois le fichier validé (empreinte calculée hors Mutex)
+    let content_hash = confirmation_content_hash(&path, max_file_size);
+    let confirmation_token = {
+        let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.generate_confirmation_token(&path, TokenPurpose::Read, content_hash)
+    };
     
     // Retourner preview + token
     Ok(serde_json::json!({
@@ -147,12 +1032,133 @@ pub async fn get_file_preview(
     }))
 }
 
+/// Taille, nombre de lignes, type et date de modification d'un fichier, sans transférer son contenu
+#[tauri::command]
+pub async fn get_file_stats(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    file_path: String,
+) -> Result<FileStats, String> {
+    // Comme le preview, aucune permission ni token requis (aucun contenu n'est retourné)
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+
+    temp_reader.get_file_stats(PathBuf::from(file_path))
+}
+
+/// Pré-vérification "puis-je lire ce fichier ?" : exécute les contrôles de read_file et indique
+/// si FileRead est accordée, sans lire le contenu ni consommer la permission (ni audit)
+#[tauri::command]
+pub async fn can_read_file(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    file_path: String,
+) -> Result<ReadPreflight, String> {
+    let (config, _) = read_config(&context_state, window.label())?;
+    let (permission_held, sandboxed) = {
+        let manager = permission_state.lock().map_err(|e| e.to_string())?;
+        (manager.has_permission(&Permission::FileRead), manager.is_sandbox_mode())
+    };
+
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+
+    let checks = temp_reader.read_checks(Path::new(&file_path));
+    let readable = permission_held && !sandboxed && checks.iter().all(|check| check.passed);
+    Ok(ReadPreflight {
+        path: file_path,
+        readable,
+        checks,
+        permission_held,
+        sandboxed,
+    })
+}
+
+/// Nombre maximal de fichiers par appel à get_file_previews
+/// (reste sous le plafond de tokens de confirmation conservés)
+const MAX_PREVIEW_BATCH: usize = 100;
+
+/// Preview de plusieurs fichiers en un seul appel
+/// Les erreurs sont rapportées par fichier ; un token est généré pour chaque preview réussi
+#[tauri::command]
+pub async fn get_file_previews(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    app: AppHandle<Wry>,
+    window: Window<Wry>,
+    paths: Vec<String>,
+    max_lines: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    // Comme get_file_preview, aucune permission requise (lecture partielle et limitée)
+    if paths.len() > MAX_PREVIEW_BATCH {
+        return Err(format!(
+            "Too many files to preview ({}). Max allowed: {}",
+            paths.len(),
+            MAX_PREVIEW_BATCH
+        ));
+    }
+    let max = max_lines.unwrap_or(50);
+
+    let config = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+
+    let max_file_size = config.max_file_size;
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+
+    let results: Vec<(String, Result<FilePreview, String>)> = paths
+        .into_iter()
+        .map(|path| {
+            let preview = temp_reader.get_file_preview(PathBuf::from(&path), max);
+            (path, preview)
+        })
+        .collect();
+
+    // Empreintes calculées hors Mutex, puis tokens générés en une seule prise du lock
+    let content_hashes: Vec<(&String, Option<String>)> = results
+        .iter()
+        .filter(|(_, preview)| preview.is_ok())
+        .map(|(path, _)| (path, confirmation_content_hash(Path::new(path), max_file_size)))
+        .collect();
+    let mut confirmation_tokens = serde_json::Map::new();
+    {
+        let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        for (path, content_hash) in content_hashes {
+            let token = context_reader.generate_confirmation_token(Path::new(path), TokenPurpose::Read, content_hash);
+            confirmation_tokens.insert(path.clone(), serde_json::Value::String(token));
+        }
+    }
+
+    let previews: Vec<serde_json::Value> = results
+        .into_iter()
+        .map(|(path, preview)| match preview {
+            Ok(preview) => serde_json::json!({ "path": path, "preview": preview }),
+            Err(error) => serde_json::json!({ "path": path, "error": error }),
+        })
+        .collect();
+
+    Ok(serde_json::json!({
+        "previews": previews,
+        "confirmation_tokens": confirmation_tokens
+    }))
+}
+
 /// Lit un fichier complet après confirmation (nécessite permission + token)
 #[tauri::command]
 pub async fn read_file_confirmed(
     context_state: State<'_, Mutex<ContextReader<Wry>>>,
     permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
     app: AppHandle<Wry>,
+    window: Window<Wry>,
     file_path: String,
     confirmation_token: String,
 ) -> Result<FileContent, String> {
@@ -161,39 +1167,207 @@ pub async fn read_file_confirmed(
         &permission_state,
         Permission::FileRead,
         &format!("Reading file: {}", file_path),
+        "read_file_confirmed",
     ).await?;
 
     let path = PathBuf::from(file_path.clone());
     
     // 2. Valider le token de confirmation
-    {
+    let validated = {
         let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
-        if !context_reader.validate_confirmation_token(&path, &confirmation_token) {
-            return Err("Invalid or expired confirmation token. Please preview the file first.".to_string());
+        match context_reader.validate_confirmation_token(&path, &confirmation_token, TokenPurpose::Read) {
+            Some(validated) => validated,
+            None => return Err("Invalid or expired confirmation token. Please preview the file first.".to_string()),
         }
-    }
+    };
     
     // 3. Lire le fichier complet
+    let (config, scope_generation) = read_config(&context_state, window.label())?;
+    
+    let mut temp_reader = ContextReader::<Wry>::new(&app);
+    temp_reader.update_config(config);
+    
+    let content = temp_reader.read_file_with_permission(path).await?;
+    ensure_scope_unchanged(&context_state, scope_generation)?;
+
+    // 4. Vérifier que le fichier n'a pas changé depuis le preview
+    if let Some(expected) = validated.content_hash {
+        if expected != content.sha256 {
+            return Err("File changed since preview. Please preview the file again.".to_string());
+        }
+    }
+
+    record_recent_file(&context_state, &content.path);
+    Ok(content)
+}
+
+/// Prépare la suppression d'un fichier : valide le chemin et génère un token de confirmation
+#[tauri::command]
+pub async fn prepare_delete_file(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    window: Window<Wry>,
+    file_path: String,
+) -> Result<serde_json::Value, String> {
+    let path = PathBuf::from(&file_path);
     let config = {
         let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.config_for_window(window.label())
+    };
+    let max_file_size = config.max_file_size;
+
+    let mut validator = ContextReader::<Wry>::new(window.app_handle());
+    validator.update_config(config);
+    validator.validate_deletable(&path)?;
+
+    let content_hash = confirmation_content_hash(&path, max_file_size);
+    let confirmation_token = {
+        let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.generate_confirmation_token(&path, TokenPurpose::Delete, content_hash)
+    };
+    Ok(serde_json::json!({
+        "path": file_path,
+        "confirmation_token": confirmation_token
+    }))
+}
+
+/// Supprime un fichier du scope (nécessite FileWrite + token de prepare_delete_file)
+/// Le fichier est déplacé dans la corbeille de l'app et la suppression est tracée dans l'audit
+#[tauri::command]
+pub async fn delete_file(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    window: Window<Wry>,
+    file_path: String,
+    confirmation_token: String,
+) -> Result<DeletedFile, String> {
+    ensure_permission(
+        &permission_state,
+        Permission::FileWrite,
+        &format!("Deleting file: {}", file_path),
+        "delete_file",
+    ).await?;
+
+    let path = PathBuf::from(&file_path);
+    let (validated, config) = {
+        let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        let validated = context_reader
+            .validate_confirmation_token(&path, &confirmation_token, TokenPurpose::Delete)
+            .ok_or("Invalid or expired confirmation token. Please confirm the deletion again.")?;
+        (validated, context_reader.config_for_window(window.label()))
+    };
+
+    let mut temp_reader = ContextReader::<Wry>::new(window.app_handle());
+    temp_reader.update_config(config);
+    temp_reader.validate_deletable(&path)?;
+
+    // Ne pas supprimer un fichier modifié depuis la confirmation
+    if let Some(expected) = validated.content_hash {
+        let current = std::fs::read(&path)
+            .map(|bytes| crate::context_reader::content_sha256(&bytes))
+            .map_err(|e| format!("Failed to read file {}: {}", file_path, e))?;
+        if current != expected {
+            return Err("File changed since confirmation. Please confirm the deletion again.".to_string());
+        }
+    }
+
+    let deleted = temp_reader.move_to_trash(&path)?;
+
+    let (log, handle) = {
+        let mut manager = permission_state.lock().map_err(|e| e.to_string())?;
+        let log = manager.record_event(
+            Permission::FileWrite,
+            &format!("Deleting file: {}", deleted.path),
+            format!("File moved to trash: {}", deleted.trash_path),
+            "delete_file",
+        );
+        (log, manager.async_handle())
+    };
+    handle.write_log(log).await?;
+
+    Ok(deleted)
+}
+
+/// Prépare la lecture d'un fichier distant : valide l'URL et génère un token de confirmation
+#[tauri::command]
+pub async fn prepare_remote_read(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    url: String,
+) -> Result<serde_json::Value, String> {
+    // Comme le preview, la préparation ne nécessite pas de permission (aucun accès réseau)
+    let confirmation_token = {
+        let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.validate_remote_url(&url)?;
+        context_reader.generate_confirmation_token(Path::new(&url), TokenPurpose::Remote, None)
+    };
+
+    Ok(serde_json::json!({
+        "url": url,
+        "confirmation_token": confirmation_token
+    }))
+}
+
+/// Lit un fichier distant (HTTPS) après confirmation (nécessite RemoteAccess + token)
+#[tauri::command]
+pub async fn read_remote_file(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    url: String,
+    confirmation_token: String,
+) -> Result<FileContent, String> {
+    // 1. Vérifier la permission d'accès distant
+    ensure_permission(
+        &permission_state,
+        Permission::RemoteAccess,
+        &format!("Reading remote file: {}", url),
+        "read_remote_file",
+    ).await?;
+
+    // 2. Respecter le budget réseau quotidien
+    crate::egress_budget::check_egress(&app, "read_remote_file")?;
+
+    // 3. Valider le token de confirmation
+    let config = {
+        let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        if context_reader.validate_confirmation_token(Path::new(&url), &confirmation_token, TokenPurpose::Remote).is_none() {
+            return Err("Invalid or expired confirmation token. Please prepare the remote read first.".to_string());
+        }
         context_reader.get_config()
     };
-    
+
+    // 4. Télécharger le contenu
     let mut temp_reader = ContextReader::<Wry>::new(&app);
     temp_reader.update_config(config);
-    
-    temp_reader.read_file_with_permission(path).await
+
+    temp_reader.read_remote_file(&url).await
+}
+
+/// Applique une configuration au ContextReader partagé et prévient le frontend si elle change
+pub(crate) fn apply_context_config(
+    app: &AppHandle<Wry>,
+    new_config: ContextReaderConfig,
+    source: ConfigSource,
+) -> Result<ConfigChanges, String> {
+    let context_state = app.state::<Mutex<ContextReader<Wry>>>();
+    let changes = {
+        let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.apply_config(new_config, source)
+    };
+
+    // Prévenir le frontend pour qu'il rafraîchisse ce qui dépend du scope/des extensions
+    if changes.scope_changed || changes.extensions_changed || changes.limits_changed || changes.ignore_changed {
+        let _ = app.emit("context-config-changed", &changes);
+    }
+    Ok(changes)
 }
 
 #[tauri::command]
 pub async fn update_context_config(
-    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    app: AppHandle<Wry>,
     new_config: ContextReaderConfig,
-) -> Result<(), String> {
+) -> Result<ConfigChanges, String> {
     // La mise à jour de la config ne nécessite pas de permission (c'est une configuration)
-    let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
-    context_reader.update_config(new_config);
-    Ok(())
+    apply_context_config(&app, new_config, ConfigSource::Runtime)
 }
 
 #[tauri::command]
@@ -216,4 +1390,13 @@ pub async fn remove_allowed_extension(
     let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
     context_reader.remove_allowed_extension(&extension);
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Liste les extensions autorisées avec description et catégorie (code/data/docs)
+#[tauri::command]
+pub async fn list_allowed_extensions_detailed(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+) -> Result<Vec<ExtensionInfo>, String> {
+    let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+    Ok(context_reader.allowed_extensions_detailed())
+}
@@ -0,0 +1,467 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::UNIX_EPOCH;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Nombre de graphes gardés en cache (un par scope, les plus anciens sont évincés)
+const MAX_CACHED_GRAPHS: usize = 8;
+
+/// Extensions essayées pour un import JS/TS sans extension
+const JS_EXTENSIONS: [&str; 6] = ["ts", "tsx", "js", "jsx", "mjs", "cjs"];
+
+lazy_static! {
+    /// Python : `import a.b as c, d`
+    static ref PY_IMPORT: Regex = Regex::new(r"(?m)^[ \t]*import[ \t]+([^#\n]+)").expect("invalid python import pattern");
+    /// Python : `from ..a.b import c, d`
+    static ref PY_FROM: Regex = Regex::new(r"(?m)^[ \t]*from[ \t]+(\.*)([\w.]*)[ \t]+import[ \t]+\(?([^#\n)]+)").expect("invalid python from pattern");
+    /// JS/TS : `import x from '...'`, `export * from '...'`, `import '...'`, `import('...')`, `require('...')`
+    static ref JS_SPECIFIER: Regex = Regex::new(r#"(?:\bfrom|\bimport|\bimport\s*\(|\brequire\s*\()\s*['"]([^'"\n]+)['"]"#).expect("invalid js import pattern");
+    /// Rust : `mod x;` (les modules en ligne `mod x { ... }` n'ont pas de fichier)
+    static ref RS_MOD: Regex = Regex::new(r"(?m)^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?mod[ \t]+(\w+)[ \t]*;").expect("invalid rust mod pattern");
+    /// Rust : `use a::b::{c, d};` (éventuellement sur plusieurs lignes)
+    static ref RS_USE: Regex = Regex::new(r"(?m)^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?use[ \t]+([^;]+);").expect("invalid rust use pattern");
+}
+
+/// Graphe des dépendances entre fichiers du scope (chemins relatifs au scope, séparateur `/`)
+/// Un graphe annulé est partiel et n'est jamais mis en cache
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyGraph {
+    pub root: String,
+    pub fingerprint: String,  // Empreinte du scope (chemins, tailles, dates de modification)
+    pub computed_at: DateTime<Utc>,
+    pub file_count: usize,  // Fichiers analysés
+    pub edge_count: usize,
+    pub dependencies: BTreeMap<String, Vec<String>>,  // Fichier -> fichiers du scope qu'il importe
+    pub external: BTreeMap<String, Vec<String>>,  // Fichier -> modules hors scope (paquets, std...)
+    pub skipped: Vec<String>,  // Fichiers trop gros ou illisibles
+    pub cached: bool,
+    pub cancelled: bool,
+}
+
+/// Cache des graphes par scope. L'empreinte change dès qu'un fichier est ajouté, supprimé ou modifié,
+/// ce qui invalide le graphe sans surveiller le disque.
+pub struct DependencyGraphCache {
+    graphs: HashMap<PathBuf, DependencyGraph>,
+}
+
+impl DependencyGraphCache {
+    pub fn new() -> Self {
+        Self { graphs: HashMap::new() }
+    }
+
+    /// Graphe en cache pour ce scope, s'il correspond encore à l'empreinte
+    pub fn get(&self, root: &Path, fingerprint: &str) -> Option<DependencyGraph> {
+        self.graphs
+            .get(root)
+            .filter(|graph| graph.fingerprint == fingerprint)
+            .map(|graph| DependencyGraph { cached: true, ..graph.clone() })
+    }
+
+    pub fn insert(&mut self, root: PathBuf, graph: DependencyGraph) {
+        if !self.graphs.contains_key(&root) && self.graphs.len() >= MAX_CACHED_GRAPHS {
+            let oldest = self.graphs
+                .iter()
+                .min_by_key(|(_, graph)| graph.computed_at)
+                .map(|(path, _)| path.clone());
+            if let Some(path) = oldest {
+                self.graphs.remove(&path);
+            }
+        }
+        self.graphs.insert(root, graph);
+    }
+}
+
+/// Empreinte d'un ensemble de fichiers (sans les lire) : chemin, taille et date de modification
+pub fn scope_fingerprint(root: &Path, files: &[PathBuf]) -> String {
+    let mut entries: Vec<(String, u64, u128)> = files
+        .iter()
+        .map(|path| {
+            let metadata = fs::metadata(path).ok();
+            let size = metadata.as_ref().map_or(0, |m| m.len());
+            let modified = metadata
+                .and_then(|m| m.modified().ok())
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_nanos());
+            (relative_path(root, path), size, modified)
+        })
+        .collect();
+    entries.sort();
+
+    let mut hasher = Sha256::new();
+    hasher.update(root.to_string_lossy().as_bytes());
+    for (path, size, modified) in entries {
+        hasher.update(format!("\n{}\t{}\t{}", path, size, modified).as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Analyse les imports des fichiers Python, JS/TS et Rust et les résout vers des fichiers du scope
+/// Les fichiers au-delà de `max_file_size` ne sont pas lus ; `cancel` interrompt l'analyse (graphe partiel)
+pub fn build_dependency_graph(
+    root: &Path,
+    files: &[PathBuf],
+    max_file_size: usize,
+    fingerprint: String,
+    cancel: &AtomicBool,
+) -> DependencyGraph {
+    let root = &normalize(root);
+    let known: HashSet<PathBuf> = files.iter().map(|path| normalize(path)).collect();
+    let mut dependencies = BTreeMap::new();
+    let mut external = BTreeMap::new();
+    let mut skipped = Vec::new();
+    let mut file_count = 0;
+
+    for path in files {
+        if cancel.load(Ordering::SeqCst) {
+            break;
+        }
+        let extension = path.extension().and_then(|ext| ext.to_str()).unwrap_or("").to_lowercase();
+        let language = match extension.as_str() {
+            "py" => Language::Python,
+            "js" | "jsx" | "ts" | "tsx" | "mjs" | "cjs" => Language::JavaScript,
+            "rs" => Language::Rust,
+            _ => continue,
+        };
+
+        let relative = relative_path(root, path);
+        // Taille vérifiée avant lecture : un fichier trop gros n'est jamais chargé en mémoire
+        let too_large = fs::metadata(path).map_or(true, |m| m.len() > max_file_size as u64);
+        if too_large {
+            skipped.push(relative);
+            continue;
+        }
+        let source = match fs::read_to_string(path) {
+            Ok(source) => source,
+            Err(_) => {
+                skipped.push(relative);
+                continue;
+            }
+        };
+        file_count += 1;
+
+        let path = normalize(path);
+        let resolver = Resolver { root, known: &known, file: &path };
+        let (internal, unresolved) = match language {
+            Language::Python => resolver.python_imports(&source),
+            Language::JavaScript => resolver.js_imports(&source),
+            Language::Rust => resolver.rust_imports(&source),
+        };
+
+        let internal: BTreeSet<String> = internal
+            .into_iter()
+            .filter(|target| *target != path)
+            .map(|target| relative_path(root, &target))
+            .collect();
+        if !internal.is_empty() {
+            dependencies.insert(relative.clone(), internal.into_iter().collect());
+        }
+        if !unresolved.is_empty() {
+            external.insert(relative, unresolved.into_iter().collect());
+        }
+    }
+
+    DependencyGraph {
+        root: root.to_string_lossy().into_owned(),
+        fingerprint,
+        computed_at: Utc::now(),
+        file_count,
+        edge_count: dependencies.values().map(Vec::len).sum(),
+        dependencies,
+        external,
+        skipped,
+        cached: false,
+        cancelled: cancel.load(Ordering::SeqCst),
+    }
+}
+
+enum Language {
+    Python,
+    JavaScript,
+    Rust,
+}
+
+/// Résolution des imports d'un fichier vers les fichiers connus du scope
+struct Resolver<'a> {
+    root: &'a Path,
+    known: &'a HashSet<PathBuf>,
+    file: &'a Path,
+}
+
+impl Resolver<'_> {
+    fn dir(&self) -> &Path {
+        self.file.parent().unwrap_or(self.root)
+    }
+
+    /// Premier candidat présent dans le scope
+    fn first_known(&self, candidates: impl IntoIterator<Item = PathBuf>) -> Option<PathBuf> {
+        candidates.into_iter().map(|path| normalize(&path)).find(|path| self.known.contains(path))
+    }
+
+    /// Module Python `a.b.c` depuis `base` : a/b/c.py ou a/b/c/__init__.py
+    fn python_module(&self, base: &Path, module: &str) -> Option<PathBuf> {
+        let module_path: PathBuf = module.split('.').filter(|part| !part.is_empty()).collect();
+        self.first_known([
+            base.join(&module_path).with_extension("py"),
+            base.join(&module_path).join("__init__.py"),
+        ])
+    }
+
+    /// Module exact, cherché depuis le dossier du fichier puis depuis la racine du scope
+    fn python_exact(&self, module: &str) -> Option<PathBuf> {
+        self.python_module(self.dir(), module)
+            .or_else(|| self.python_module(self.root, module))
+    }
+
+    /// Module absolu, cherché depuis le dossier du fichier puis depuis la racine du scope.
+    /// Le préfixe le plus long présent dans le scope l'emporte (`a.b.func` -> a/b.py).
+    fn python_absolute(&self, module: &str) -> Option<PathBuf> {
+        let parts: Vec<&str> = module.split('.').collect();
+        (1..=parts.len()).rev().find_map(|len| {
+            self.python_exact(&parts[..len].join("."))
+        })
+    }
+
+    fn python_imports(&self, source: &str) -> (Vec<PathBuf>, BTreeSet<String>) {
+        let mut internal = Vec::new();
+        let mut unresolved = BTreeSet::new();
+
+        for captures in PY_IMPORT.captures_iter(source) {
+            for module in captures[1].split(',').filter_map(|item| item.split_whitespace().next()) {
+                match self.python_absolute(module) {
+                    Some(path) => internal.push(path),
+                    None => {
+                        unresolved.insert(module.to_string());
+                    }
+                }
+            }
+        }
+
+        for captures in PY_FROM.captures_iter(source) {
+            let dots = captures[1].len();
+            let module = &captures[2];
+            let names: Vec<&str> = captures[3]
+                .split(',')
+                .filter_map(|item| item.split_whitespace().next())
+                .filter(|name| *name != "*")
+                .collect();
+
+            if dots == 0 {
+                // `from a.b import c` : c peut être un sous-module (a/b/c.py) ou un symbole de a/b.py
+                let submodules: Vec<PathBuf> = names
+                    .iter()
+                    .filter_map(|name| self.python_exact(&format!("{}.{}", module, name)))
+                    .collect();
+                if !submodules.is_empty() {
+                    internal.extend(submodules);
+                } else if let Some(path) = self.python_absolute(module) {
+                    internal.push(path);
+                } else {
+                    unresolved.insert(module.to_string());
+                }
+                continue;
+            }
+
+            // Import relatif : un point = dossier du fichier, chaque point de plus remonte d'un niveau
+            let mut base = self.dir().to_path_buf();
+            for _ in 1..dots {
+                base.pop();
+            }
+            let mut found = false;
+            for name in &names {
+                let target = if module.is_empty() { name.to_string() } else { format!("{}.{}", module, name) };
+                if let Some(path) = self.python_module(&base, &target) {
+                    internal.push(path);
+                    found = true;
+                }
+            }
+            if !found && !module.is_empty() {
+                if let Some(path) = self.python_module(&base, module) {
+                    internal.push(path);
+                }
+            }
+        }
+
+        (internal, unresolved)
+    }
+
+    fn js_imports(&self, source: &str) -> (Vec<PathBuf>, BTreeSet<String>) {
+        let mut internal = Vec::new();
+        let mut unresolved = BTreeSet::new();
+
+        for captures in JS_SPECIFIER.captures_iter(source) {
+            let specifier = &captures[1];
+            // Seuls les imports relatifs désignent un fichier, les autres sont des paquets
+            if !specifier.starts_with("./") && !specifier.starts_with("../") {
+                unresolved.insert(specifier.to_string());
+                continue;
+            }
+
+            let target = self.dir().join(specifier);
+            let mut candidates = vec![target.clone()];
+            candidates.extend(JS_EXTENSIONS.iter().map(|ext| PathBuf::from(format!("{}.{}", target.display(), ext))));
+            candidates.extend(JS_EXTENSIONS.iter().map(|ext| target.join(format!("index.{}", ext))));
+            if let Some(path) = self.first_known(candidates) {
+                internal.push(path);
+            }
+        }
+
+        (internal, unresolved)
+    }
+
+    /// Dossier des sous-modules du fichier courant (`foo.rs` -> foo/, `mod.rs`/`lib.rs`/`main.rs` -> son dossier)
+    fn rust_module_dir(&self) -> PathBuf {
+        match self.file.file_name().and_then(|name| name.to_str()) {
+            Some("mod.rs" | "lib.rs" | "main.rs") => self.dir().to_path_buf(),
+            _ => self.dir().join(self.file.file_stem().unwrap_or_default()),
+        }
+    }
+
+    /// Racine `src/` du crate contenant le fichier (dossier `src` voisin d'un Cargo.toml)
+    fn rust_crate_src(&self) -> Option<PathBuf> {
+        self.file
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(self.root))
+            .find(|dir| {
+                dir.file_name().is_some_and(|name| name == "src")
+                    && dir.parent().is_some_and(|parent| parent.join("Cargo.toml").is_file())
+            })
+            .map(Path::to_path_buf)
+    }
+
+    /// Module Rust `a::b::c` depuis `base` : le plus long préfixe présent (a/b/c.rs, a/b/c/mod.rs, a/b.rs...)
+    fn rust_module(&self, base: &Path, segments: &[&str]) -> Option<PathBuf> {
+        (1..=segments.len()).rev().find_map(|len| {
+            let module_path: PathBuf = segments[..len].iter().collect();
+            self.first_known([
+                base.join(&module_path).with_extension("rs"),
+                base.join(&module_path).join("mod.rs"),
+            ])
+        })
+    }
+
+    fn rust_imports(&self, source: &str) -> (Vec<PathBuf>, BTreeSet<String>) {
+        let mut internal = Vec::new();
+        let mut unresolved = BTreeSet::new();
+        let module_dir = self.rust_module_dir();
+
+        for captures in RS_MOD.captures_iter(source) {
+            let name = &captures[1];
+            if let Some(path) = self.first_known([
+                module_dir.join(format!("{}.rs", name)),
+                module_dir.join(name).join("mod.rs"),
+            ]) {
+                internal.push(path);
+            }
+        }
+
+        for captures in RS_USE.captures_iter(source) {
+            for use_path in expand_use_tree(&captures[1]) {
+                let segments: Vec<&str> = use_path
+                    .split("::")
+                    .map(str::trim)
+                    .filter(|segment| !segment.is_empty() && *segment != "self" && *segment != "*")
+                    .collect();
+                let Some((first, rest)) = segments.split_first() else {
+                    continue;
+                };
+
+                let resolved = match *first {
+                    "crate" => self.rust_crate_src().and_then(|src| self.rust_module(&src, rest)),
+                    "super" => {
+                        // `super` désigne le module parent : ses sous-modules sont dans le dossier du fichier
+                        let mut base = module_dir.parent().map(Path::to_path_buf).unwrap_or_default();
+                        let mut rest = rest;
+                        while let Some((&"super", tail)) = rest.split_first() {
+                            base.pop();
+                            rest = tail;
+                        }
+                        self.rust_module(&base, rest)
+                    }
+                    // Chemin relatif au module courant (sous-module) ou crate externe
+                    _ => self.rust_module(&module_dir, &segments),
+                };
+
+                match resolved {
+                    Some(path) => internal.push(path),
+                    None if !matches!(*first, "crate" | "super") => {
+                        unresolved.insert(first.to_string());
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        (internal, unresolved)
+    }
+}
+
+/// Développe un arbre `use` (`a::{b, c::{d, e}}`) en chemins simples (`a::b`, `a::c::d`, `a::c::e`)
+fn expand_use_tree(tree: &str) -> Vec<String> {
+    let tree = tree.trim();
+    let Some(open) = tree.find('{') else {
+        // `a::b as c` -> `a::b`
+        return vec![tree.split_whitespace().next().unwrap_or("").to_string()];
+    };
+    let Some(close) = tree.rfind('}') else {
+        return Vec::new();
+    };
+    if close < open {
+        return Vec::new();
+    }
+
+    let prefix = &tree[..open];
+    let mut items = Vec::new();
+    let mut depth = 0;
+    let mut start = open + 1;
+    for (index, c) in tree[open + 1..close].char_indices() {
+        let index = index + open + 1;
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                items.push(&tree[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&tree[start..close]);
+
+    items
+        .into_iter()
+        .filter(|item| !item.trim().is_empty())
+        .flat_map(|item| expand_use_tree(&format!("{}{}", prefix, item.trim())))
+        .collect()
+}
+
+/// Supprime les `.` et `..` d'un chemin sans toucher au disque
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Chemin relatif au scope avec des `/` (identique sur toutes les plateformes)
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,313 @@
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, State, Wry};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::command_executor::CommandExecutor;
+use crate::context_reader::ContextReader;
+use crate::licensing::store::LicenseStore;
+use crate::ollama_installer;
+use crate::permission_manager::{self, PermissionManager};
+use crate::python_bridge::{self, PythonBridge};
+use crate::settings_profile::ConfigSource;
+
+/// Valeur de remplacement pour les secrets exclus du bundle
+const REDACTED: &str = "[REDACTED]";
+
+/// Remplace un identifiant d'appareil par une empreinte courte : deux bundles du même appareil
+/// restent rapprochables sans exposer l'identifiant
+fn anonymize_identifier(identifier: &str) -> String {
+    let digest = hex::encode(Sha256::digest(identifier.as_bytes()));
+    format!("sha256:{}", &digest[..16])
+}
+
+/// Exporte un bundle de diagnostic (zip) pour les rapports de bug :
+/// journal d'audit des permissions, log du worker, licence (expurgée, empreinte d'appareil hachée),
+/// version de l'app, OS/arch et état d'Ollama.
+#[tauri::command]
+pub async fn export_diagnostics(
+    app: AppHandle<Wry>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    license_store: State<'_, LicenseStore>,
+    bridge: State<'_, PythonBridge<Wry>>,
+    path: String,
+) -> Result<serde_json::Value, String> {
+    let audit_log_path = {
+        let manager = permission_state.lock().map_err(|e| e.to_string())?;
+        manager.log_path()
+    };
+
+    // Licence : ne jamais exporter le JWS (contient la clé) ni l'identifiant de l'appareil
+    let mut license = license_store.snapshot();
+    if license.entitlement_jws.is_some() {
+        license.entitlement_jws = Some(REDACTED.to_string());
+    }
+    license.device_fingerprint = license.device_fingerprint.as_deref().map(anonymize_identifier);
+
+    // Sondes bloquantes (processus, réseau local) : hors du runtime async
+    let (ollama_installed, ollama_running) = tauri::async_runtime::spawn_blocking(|| {
+        (ollama_installer::is_ollama_installed(), ollama_installer::is_ollama_running())
+    })
+    .await
+    .map_err(|e| format!("Ollama probe failed: {}", e))?;
+
+    let worker_log_path = bridge.worker_log_file().filter(|p| p.is_file());
+
+    let mut included: Vec<String> = Vec::new();
+    let mut missing: Vec<String> = Vec::new();
+
+    let file = File::create(&path)
+        .map_err(|e| format!("Failed to create diagnostics bundle {}: {}", path, e))?;
+    let mut zip = ZipWriter::new(file);
+    let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+
+    let mut add_entry = |zip: &mut ZipWriter<File>, name: &str, bytes: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options)
+            .map_err(|e| format!("Failed to add {} to diagnostics bundle: {}", name, e))?;
+        zip.write_all(bytes)
+            .map_err(|e| format!("Failed to write {} to diagnostics bundle: {}", name, e))?;
+        included.push(name.to_string());
+        Ok(())
+    };
+
+    match audit_log_path.as_ref().and_then(|p| std::fs::read(p).ok()) {
+        Some(bytes) => add_entry(&mut zip, "permission_audit.log", &bytes)?,
+        None => missing.push("permission_audit.log".to_string()),
+    }
+
+    match worker_log_path.as_ref().and_then(|p| std::fs::read(p).ok()) {
+        Some(bytes) => add_entry(&mut zip, "worker.log", &bytes)?,
+        None => missing.push("worker.log".to_string()),
+    }
+
+    let license_json = serde_json::to_vec_pretty(&license).map_err(|e| e.to_string())?;
+    add_entry(&mut zip, "license.json", &license_json)?;
+
+    let summary = serde_json::json!({
+        "generated_at": chrono::Utc::now().to_rfc3339(),
+        "app_version": app.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "ollama": {
+            "installed": ollama_installed,
+            "running": ollama_running
+        },
+        "worker_ready": bridge.is_ready(),
+        "missing_files": missing
+    });
+    let summary_json = serde_json::to_vec_pretty(&summary).map_err(|e| e.to_string())?;
+    add_entry(&mut zip, "diagnostics.json", &summary_json)?;
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize diagnostics bundle: {}", e))?;
+
+    Ok(serde_json::json!({
+        "success": true,
+        "path": path,
+        "files": included,
+        "missing_files": missing
+    }))
+}
+
+/// Résultat d'une vérification de démarrage, avec une piste de résolution en cas d'échec
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthCheck {
+    pub passed: bool,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+}
+
+impl HealthCheck {
+    fn pass(detail: Option<String>) -> Self {
+        Self { passed: true, detail, hint: None }
+    }
+
+    fn fail(detail: Option<String>, hint: &str) -> Self {
+        Self { passed: false, detail, hint: Some(hint.to_string()) }
+    }
+}
+
+/// Rapport de santé de l'environnement (onboarding, "ça ne marche pas")
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupDiagnostics {
+    pub healthy: bool,
+    pub worker: HealthCheck,
+    pub ollama_installed: HealthCheck,
+    pub ollama_running: HealthCheck,
+    pub ollama_version: Option<String>,
+    pub log_dir_writable: HealthCheck,
+    pub license: HealthCheck,
+    pub scope: HealthCheck,
+}
+
+/// Vérifie qu'on peut créer un fichier dans le dossier du journal d'audit
+fn check_log_dir(log_path: Option<PathBuf>, warning: Option<String>) -> HealthCheck {
+    let hint = "Check that the application data folder exists and is writable.";
+    let Some(dir) = log_path.as_ref().and_then(|p| p.parent()) else {
+        return HealthCheck::fail(warning.or(Some("Audit log file is not available".to_string())), hint);
+    };
+
+    let probe = dir.join(".write_probe");
+    match File::create(&probe) {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            HealthCheck::pass(Some(dir.to_string_lossy().into_owned()))
+        }
+        Err(e) => HealthCheck::fail(Some(format!("{}: {}", dir.display(), e)), hint),
+    }
+}
+
+/// Vérifie l'état de l'environnement en un seul appel : worker, Ollama, journal d'audit, licence et scope
+#[tauri::command]
+pub async fn run_startup_diagnostics(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    license_store: State<'_, LicenseStore>,
+    bridge: State<'_, PythonBridge<Wry>>,
+) -> Result<StartupDiagnostics, String> {
+    let (log_path, audit_warning) = {
+        let manager = permission_state.lock().map_err(|e| e.to_string())?;
+        (manager.log_path(), manager.audit_file_warning())
+    };
+    let scope = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.current_scope().cloned()
+    };
+
+    let worker = match bridge.unavailable_reason() {
+        Some(reason) => HealthCheck::fail(Some(reason.to_string()), "Reinstall Horizon AI or check that Python is available."),
+        None if bridge.is_ready() => HealthCheck::pass(None),
+        None => HealthCheck::fail(
+            Some("Python worker is not ready".to_string()),
+            "Wait a few seconds, then restart Horizon AI if the worker stays unavailable.",
+        ),
+    };
+
+    let ollama_installed = if ollama_installer::is_ollama_installed() {
+        HealthCheck::pass(None)
+    } else {
+        HealthCheck::fail(None, "Install Ollama from the setup screen.")
+    };
+
+    let probe = ollama_installer::probe_local_ollama().await?;
+    let ollama_running = if probe.reachable {
+        HealthCheck::pass(probe.latency_ms.map(|ms| format!("{} ms", ms)))
+    } else {
+        HealthCheck::fail(probe.error, "Start Ollama (ollama serve) or restart Horizon AI.")
+    };
+
+    let license_record = license_store.snapshot();
+    let license = match license_record.state.as_str() {
+        "expired" | "error" => HealthCheck::fail(
+            Some(license_record.error.unwrap_or(license_record.state)),
+            "Refresh or re-activate your license.",
+        ),
+        state => HealthCheck::pass(Some(format!("{} ({})", license_record.plan, state))),
+    };
+
+    let scope = match scope {
+        Some(dir) => HealthCheck::pass(Some(dir.to_string_lossy().into_owned())),
+        None => HealthCheck::fail(None, "Select a project folder to give the assistant file context."),
+    };
+
+    let log_dir_writable = check_log_dir(log_path, audit_warning);
+
+    let healthy = [&worker, &ollama_installed, &ollama_running, &log_dir_writable, &license, &scope]
+        .iter()
+        .all(|check| check.passed);
+
+    Ok(StartupDiagnostics {
+        healthy,
+        worker,
+        ollama_installed,
+        ollama_running,
+        ollama_version: probe.version,
+        log_dir_writable,
+        license,
+        scope,
+    })
+}
+
+/// Valeur de configuration annotée de sa provenance, enregistrée quand la valeur a été fixée
+fn config_entry<T: Serialize>(value: T, source: ConfigSource) -> serde_json::Value {
+    serde_json::json!({ "value": value, "source": source })
+}
+
+/// Valeur fixée à la compilation (non modifiable à l'exécution)
+fn builtin_entry<T: Serialize>(value: T) -> serde_json::Value {
+    config_entry(value, ConfigSource::Default)
+}
+
+/// Retourne la configuration effective (contexte, permissions, exécution de commandes, worker, Ollama),
+/// chaque valeur annotée de sa provenance, pour comprendre "pourquoi cette limite vaut X".
+#[tauri::command]
+pub async fn get_effective_config(
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    executor_state: State<'_, Mutex<CommandExecutor>>,
+    bridge: State<'_, PythonBridge<Wry>>,
+) -> Result<serde_json::Value, String> {
+    let (context, auto_restore_scope, context_sources) = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        (
+            context_reader.get_config(),
+            context_reader.scope_state().auto_restore_scope,
+            context_reader.config_sources(),
+        )
+    };
+    let (parano_mode, sandbox_mode, permission_sources) = {
+        let manager = permission_state.lock().map_err(|e| e.to_string())?;
+        (manager.is_parano_mode(), manager.is_sandbox_mode(), manager.config_sources())
+    };
+    let (executor, executor_sources) = {
+        let executor = executor_state.lock().map_err(|e| e.to_string())?;
+        (executor.get_config(), executor.config_sources())
+    };
+    // Les délais par commande ne sont fixés que par set_command_timeout, pendant la session
+    let command_timeouts = bridge.command_timeouts();
+    let command_timeouts_source = if command_timeouts.is_empty() {
+        ConfigSource::Default
+    } else {
+        ConfigSource::Runtime
+    };
+
+    Ok(serde_json::json!({
+        "context": {
+            "allowed_extensions": config_entry(context.allowed_extensions, context_sources.get("allowed_extensions")),
+            "max_file_size": config_entry(context.max_file_size, context_sources.get("max_file_size")),
+            "max_total_size": config_entry(context.max_total_size, context_sources.get("max_total_size")),
+            "current_scope": config_entry(context.current_scope, context_sources.get("current_scope")),
+            "redact_secrets": config_entry(context.redact_secrets, context_sources.get("redact_secrets")),
+            "ignore_patterns": config_entry(context.ignore_patterns, context_sources.get("ignore_patterns")),
+            "lossy_decode": config_entry(context.lossy_decode, context_sources.get("lossy_decode")),
+            "confirmation_ttl_minutes": config_entry(context.confirmation_ttl_minutes, context_sources.get("confirmation_ttl_minutes")),
+            "chars_per_token": config_entry(context.chars_per_token, context_sources.get("chars_per_token")),
+            "auto_restore_scope": config_entry(auto_restore_scope, context_sources.get("auto_restore_scope"))
+        },
+        "permissions": {
+            "parano_mode": config_entry(parano_mode, permission_sources.get("parano_mode")),
+            "sandbox_mode": config_entry(sandbox_mode, permission_sources.get("sandbox_mode")),
+            "rate_limit_max_requests": builtin_entry(permission_manager::RATE_LIMIT_MAX_REQUESTS),
+            "rate_limit_window_secs": builtin_entry(permission_manager::RATE_LIMIT_WINDOW_SECS)
+        },
+        "command_executor": {
+            "allowed_programs": config_entry(executor.allowed_programs, executor_sources.get("allowed_programs")),
+            "timeout_secs": config_entry(executor.timeout_secs, executor_sources.get("timeout_secs")),
+            "max_output_bytes": config_entry(executor.max_output_bytes, executor_sources.get("max_output_bytes"))
+        },
+        "worker": {
+            "ready_timeout_secs": builtin_entry(python_bridge::READY_TIMEOUT_SECS),
+            "response_timeout_secs": builtin_entry(python_bridge::RESPONSE_TIMEOUT_SECS),
+            "command_timeouts": config_entry(command_timeouts, command_timeouts_source)
+        },
+        "ollama": {
+            "endpoint": builtin_entry(ollama_installer::OLLAMA_ENDPOINT)
+        }
+    }))
+}
@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use chrono::{Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, State, Wry};
+
+use crate::settings_profile::{read_settings_section, write_settings_section};
+
+/// Clé du fichier de réglages contenant le budget quotidien (octets, null = illimité)
+const EGRESS_BUDGET_SETTING: &str = "egress_budget_bytes";
+/// Compteur du jour, persisté dans le dossier de données (survit à un redémarrage)
+const EGRESS_USAGE_FILE: &str = "egress_usage.json";
+/// Consommation supplémentaire à partir de laquelle le compteur est re-persisté
+const PERSIST_EVERY_BYTES: u64 = 1024 * 1024;
+
+/// Compteur persisté
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedUsage {
+    day: NaiveDate,
+    used_bytes: u64,
+}
+
+/// Consommation réseau du jour (heure locale)
+#[derive(Debug, Clone, Serialize)]
+pub struct EgressUsage {
+    pub day: String,
+    pub used_bytes: u64,
+    pub budget_bytes: Option<u64>,  // None = illimité
+    pub remaining_bytes: Option<u64>,
+    pub exceeded: bool,
+    pub resets_at: String,  // Minuit (heure locale)
+}
+
+/// Budget quotidien de trafic réseau des commandes réseau (lecture distante, installeur Ollama,
+/// téléchargement de modèles, chat direct et test d'un endpoint Ollama).
+/// Une fois le budget atteint, ces commandes échouent avec "EGRESS_LIMIT" jusqu'au lendemain.
+/// Les téléchargements de modèles (faits par le processus Ollama) sont comptés d'après les octets
+/// rapportés par les événements de progression du worker ; un `pull` en cours n'est pas interrompu.
+pub struct EgressBudget {
+    budget_bytes: Option<u64>,
+    day: NaiveDate,
+    used_bytes: u64,
+    persisted_bytes: u64,
+    usage_path: Option<PathBuf>,
+}
+
+impl EgressBudget {
+    /// Charge le budget (réglages) et le compteur du jour (dossier de données)
+    pub fn load<R: Runtime>(app: &AppHandle<R>) -> Self {
+        let budget_bytes = read_settings_section(app, EGRESS_BUDGET_SETTING).and_then(|v| v.as_u64());
+        let usage_path = app.path().app_data_dir().ok().map(|dir| dir.join(EGRESS_USAGE_FILE));
+        let today = Local::now().date_naive();
+        let used_bytes = usage_path
+            .as_ref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|data| serde_json::from_slice::<PersistedUsage>(&data).ok())
+            .filter(|usage| usage.day == today)
+            .map_or(0, |usage| usage.used_bytes);
+
+        Self {
+            budget_bytes,
+            day: today,
+            used_bytes,
+            persisted_bytes: used_bytes,
+            usage_path,
+        }
+    }
+
+    /// Remet le compteur à zéro au changement de jour
+    fn roll_day(&mut self) {
+        let today = Local::now().date_naive();
+        if today != self.day {
+            self.day = today;
+            self.used_bytes = 0;
+            self.persist();
+        }
+    }
+
+    fn persist(&mut self) {
+        let Some(path) = &self.usage_path else {
+            return;
+        };
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let usage = PersistedUsage { day: self.day, used_bytes: self.used_bytes };
+        if let Ok(data) = serde_json::to_vec_pretty(&usage) {
+            if let Err(e) = fs::write(path, data) {
+                eprintln!("⚠️ Failed to persist {}: {}", EGRESS_USAGE_FILE, e);
+            }
+        }
+        self.persisted_bytes = self.used_bytes;
+    }
+
+    /// Refuse une commande réseau si le budget du jour est épuisé
+    pub fn check(&mut self, command: &str) -> Result<(), String> {
+        self.roll_day();
+        match self.budget_bytes {
+            Some(budget) if self.used_bytes >= budget => Err(format!(
+                "EGRESS_LIMIT: Daily network budget of {} bytes reached ({} bytes used), {} is blocked until midnight",
+                budget, self.used_bytes, command
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Comptabilise des octets échangés
+    pub fn record(&mut self, bytes: u64) {
+        self.roll_day();
+        self.used_bytes = self.used_bytes.saturating_add(bytes);
+        let budget_reached = self.budget_bytes.is_some_and(|budget| self.used_bytes >= budget);
+        if budget_reached || self.used_bytes - self.persisted_bytes >= PERSIST_EVERY_BYTES {
+            self.persist();
+        }
+    }
+
+    pub fn usage(&mut self) -> EgressUsage {
+        self.roll_day();
+        let resets_at = (self.day + Duration::days(1))
+            .and_hms_opt(0, 0, 0)
+            .and_then(|midnight| midnight.and_local_timezone(Local).earliest())
+            .map(|midnight| midnight.to_rfc3339())
+            .unwrap_or_default();
+
+        EgressUsage {
+            day: self.day.to_string(),
+            used_bytes: self.used_bytes,
+            budget_bytes: self.budget_bytes,
+            remaining_bytes: self.budget_bytes.map(|budget| budget.saturating_sub(self.used_bytes)),
+            exceeded: self.budget_bytes.is_some_and(|budget| self.used_bytes >= budget),
+            resets_at,
+        }
+    }
+}
+
+/// Vérifie le budget avant une commande réseau (sans budget géré : toujours autorisé)
+pub(crate) fn check_egress<R: Runtime>(app: &AppHandle<R>, command: &str) -> Result<(), String> {
+    match app.try_state::<Mutex<EgressBudget>>() {
+        Some(state) => state.lock().map_err(|e| e.to_string())?.check(command),
+        None => Ok(()),
+    }
+}
+
+/// Comptabilise le trafic d'une commande réseau
+pub(crate) fn record_egress<R: Runtime>(app: &AppHandle<R>, bytes: u64) {
+    if let Some(state) = app.try_state::<Mutex<EgressBudget>>() {
+        if let Ok(mut budget) = state.lock() {
+            budget.record(bytes);
+        }
+    }
+}
+
+/// Consommation réseau du jour et budget restant
+#[tauri::command]
+pub async fn get_egress_usage(
+    state: State<'_, Mutex<EgressBudget>>,
+) -> Result<EgressUsage, String> {
+    let mut budget = state.lock().map_err(|e| e.to_string())?;
+    Ok(budget.usage())
+}
+
+/// Définit le budget réseau quotidien en octets (None = illimité). Le choix est persisté.
+#[tauri::command]
+pub async fn set_egress_budget(
+    app: AppHandle<Wry>,
+    state: State<'_, Mutex<EgressBudget>>,
+    budget_bytes: Option<u64>,
+) -> Result<EgressUsage, String> {
+    write_settings_section(&app, EGRESS_BUDGET_SETTING, serde_json::json!(budget_bytes))?;
+    let mut budget = state.lock().map_err(|e| e.to_string())?;
+    budget.budget_bytes = budget_bytes;
+    Ok(budget.usage())
+}
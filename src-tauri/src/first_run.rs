@@ -0,0 +1,116 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State, Wry};
+
+use crate::command_executor::CommandExecutor;
+use crate::context_reader::ContextReader;
+use crate::licensing::store::{LicenseRecord, LicenseStore};
+use crate::permission_manager::PermissionManager;
+use crate::settings_profile::{current_profile, SettingsProfile, SETTINGS_FILE};
+
+/// Marqueur de premier lancement (dossier de données)
+const FIRST_RUN_FILE: &str = "first_run.json";
+
+/// Contenu du marqueur de premier lancement
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirstRunMarker {
+    pub completed_at: String,
+    pub app_version: String,
+}
+
+/// État initial retourné à l'onboarding
+#[derive(Debug, Clone, Serialize)]
+pub struct FirstRunState {
+    pub first_run: bool,  // false si l'initialisation avait déjà été faite
+    pub completed_at: String,
+    pub config_dir: String,
+    pub log_dir: String,
+    pub data_dir: String,
+    pub settings: SettingsProfile,
+    pub license: LicenseRecord,
+    pub scope: Option<String>,
+}
+
+fn create_dir(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))
+}
+
+fn read_marker(path: &Path) -> Option<FirstRunMarker> {
+    fs::read(path)
+        .ok()
+        .and_then(|data| serde_json::from_slice(&data).ok())
+}
+
+/// Initialise une nouvelle installation en un seul appel : dossiers de configuration, de logs
+/// et de données, réglages par défaut, puis marqueur de premier lancement.
+/// Aux lancements suivants, rien n'est réécrit et l'état courant est simplement retourné.
+#[tauri::command]
+pub async fn initialize_first_run(
+    app: AppHandle<Wry>,
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    executor_state: State<'_, Mutex<CommandExecutor>>,
+    license_store: State<'_, LicenseStore>,
+) -> Result<FirstRunState, String> {
+    let paths = app.path();
+    let config_dir: PathBuf = paths.app_config_dir().map_err(|e| e.to_string())?;
+    let log_dir: PathBuf = paths.app_log_dir().map_err(|e| e.to_string())?;
+    let data_dir: PathBuf = paths.app_data_dir().map_err(|e| e.to_string())?;
+
+    let settings = current_profile(&app, &context_state, &permission_state, &executor_state)?;
+    let marker_path = data_dir.join(FIRST_RUN_FILE);
+
+    let (first_run, marker) = match read_marker(&marker_path) {
+        Some(marker) => (false, marker),
+        None => {
+            for dir in [&config_dir, &log_dir, &data_dir] {
+                create_dir(dir)?;
+            }
+
+            // Ne jamais écraser des réglages existants
+            let settings_path = config_dir.join(SETTINGS_FILE);
+            if !settings_path.exists() {
+                let data = serde_json::to_vec_pretty(&settings).map_err(|e| e.to_string())?;
+                fs::write(&settings_path, data)
+                    .map_err(|e| format!("Failed to write {}: {}", settings_path.display(), e))?;
+            }
+
+            let marker = FirstRunMarker {
+                completed_at: chrono::Utc::now().to_rfc3339(),
+                app_version: app.package_info().version.to_string(),
+            };
+            // create_new : si deux appels se croisent, un seul réalise l'initialisation
+            match OpenOptions::new().write(true).create_new(true).open(&marker_path) {
+                Ok(mut file) => {
+                    let data = serde_json::to_vec_pretty(&marker).map_err(|e| e.to_string())?;
+                    file.write_all(&data)
+                        .map_err(|e| format!("Failed to write {}: {}", marker_path.display(), e))?;
+                    (true, marker)
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    (false, read_marker(&marker_path).unwrap_or(marker))
+                }
+                Err(e) => return Err(format!("Failed to write {}: {}", marker_path.display(), e)),
+            }
+        }
+    };
+
+    let scope = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.current_scope().map(|p| p.to_string_lossy().into_owned())
+    };
+
+    Ok(FirstRunState {
+        first_run,
+        completed_at: marker.completed_at,
+        config_dir: config_dir.to_string_lossy().into_owned(),
+        log_dir: log_dir.to_string_lossy().into_owned(),
+        data_dir: data_dir.to_string_lossy().into_owned(),
+        settings,
+        license: license_store.snapshot(),
+        scope,
+    })
+}
@@ -4,17 +4,29 @@ mod permission_manager;
 mod permission_commands;
 mod context_reader;
 mod context_reader_commands;
+mod context_config_file;
 mod window_manager;
 mod licensing;
+mod diagnostics;
+mod command_executor;
+mod settings_profile;
+mod first_run;
+mod storage;
+mod redaction;
+mod egress_budget;
+mod dependency_graph;
 
-use python_bridge::PythonBridge;
-use tauri::{Manager, Wry, AppHandle, RunEvent};
+use python_bridge::{BatchItemResult, DispatchedResponse, PythonBridge};
+use tauri::{Manager, Wry, AppHandle, RunEvent, WindowEvent};
 use serde_json::Value;
 use std::process::Command;
 use std::sync::Mutex;
 use permission_manager::PermissionManager;
-use context_reader::ContextReader;
+use context_reader::{ContextReader, ScanRegistry};
+use context_config_file::ConfigFileWatch;
 use licensing::store::LicenseStore;
+use command_executor::CommandExecutor;
+use egress_budget::EgressBudget;
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
@@ -27,9 +39,171 @@ const CREATE_NO_WINDOW: u32 = 0x08000000;
 async fn call_python(
     state: tauri::State<'_, PythonBridge<Wry>>, 
     cmd: String,
-    payload: Value
+    payload: Value,
+    request_id: Option<String>,
+    retry_on_crash: Option<bool>,
+) -> Result<DispatchedResponse, String> {
+    state.send(cmd, payload, request_id, retry_on_crash.unwrap_or(false)).await
+}
+
+/// Élément d'un batch de commandes Python
+#[derive(serde::Deserialize)]
+struct PythonBatchItem {
+    cmd: String,
+    #[serde(default)]
+    payload: Value,
+}
+
+/// Envoie plusieurs commandes au worker en une seule trame IPC
+#[tauri::command]
+async fn call_python_batch(
+    state: tauri::State<'_, PythonBridge<Wry>>,
+    items: Vec<PythonBatchItem>,
+) -> Result<Vec<BatchItemResult>, String> {
+    state
+        .send_batch(items.into_iter().map(|item| (item.cmd, item.payload)).collect())
+        .await
+}
+
+/// Charge un modèle dans le worker et attend sa confirmation (délai MODEL_LOAD_TIMEOUT_SECS)
+/// Retourne le nom du modèle chargé et le temps de chargement : signal "modèle prêt" pour l'UI
+#[tauri::command]
+async fn set_active_model(
+    state: tauri::State<'_, PythonBridge<Wry>>,
+    model: String,
 ) -> Result<Value, String> {
-    state.send(cmd, payload).await
+    if model.trim().is_empty() {
+        return Err("Model name cannot be empty".to_string());
+    }
+    let response = state
+        .send("set_model".to_string(), serde_json::json!({ "model": model }), None, false)
+        .await?;
+    Ok(response.data)
+}
+
+/// Délais de réponse du worker : défaut et délais spécifiques par commande
+#[tauri::command]
+fn get_command_timeouts(state: tauri::State<'_, PythonBridge<Wry>>) -> Value {
+    serde_json::json!({
+        "default_secs": python_bridge::RESPONSE_TIMEOUT_SECS,
+        "overrides": state.command_timeouts()
+    })
+}
+
+/// Définit le délai de réponse d'une commande Python (None = délai par défaut)
+#[tauri::command]
+fn set_command_timeout(
+    state: tauri::State<'_, PythonBridge<Wry>>,
+    cmd: String,
+    secs: Option<u64>,
+) -> Result<(), String> {
+    state.set_command_timeout(cmd, secs)
+}
+
+/// Profondeur de la file des requêtes en attente de réponse du worker
+#[tauri::command]
+async fn get_pending_request_count(state: tauri::State<'_, PythonBridge<Wry>>) -> Result<Value, String> {
+    Ok(serde_json::json!({
+        "pending": state.pending_count().await,
+        "max_pending": state.max_pending(),
+        "default_max_pending": python_bridge::DEFAULT_MAX_PENDING
+    }))
+}
+
+/// Ajuste le nombre maximal de requêtes en attente (au-delà : refus + "worker-backpressure")
+#[tauri::command]
+fn set_max_pending(state: tauri::State<'_, PythonBridge<Wry>>, max: usize) -> Result<(), String> {
+    state.set_max_pending(max)
+}
+
+/// Indique si le backend Python a pu être lancé (sinon, la raison et comment y remédier)
+#[tauri::command]
+fn get_backend_status(state: tauri::State<'_, PythonBridge<Wry>>) -> Value {
+    let reason = state.unavailable_reason();
+    serde_json::json!({
+        "available": reason.is_none(),
+        "reason": reason
+    })
+}
+
+/// Vérifie l'empreinte du sidecar backend installé par rapport à celle embarquée au build
+#[tauri::command]
+fn verify_backend_integrity() -> python_bridge::BackendIntegrity {
+    python_bridge::verify_backend_integrity()
+}
+
+/// Suspend le relais "python-stream" d'une requête (ex: fenêtre minimisée) sans l'annuler
+#[tauri::command]
+fn pause_stream(state: tauri::State<'_, PythonBridge<Wry>>, request_id: String) -> Result<(), String> {
+    state.pause_stream(&request_id)
+}
+
+/// Reprend le relais d'un stream en pause et relaie les événements mis en attente
+#[tauri::command]
+fn resume_stream(
+    state: tauri::State<'_, PythonBridge<Wry>>,
+    request_id: String,
+) -> Result<python_bridge::ResumedStream, String> {
+    state.resume_stream(&request_id)
+}
+
+/// Réémet à la fenêtre appelante les événements récents d'un stream (fenêtre ouverte en cours de génération)
+#[tauri::command]
+fn replay_stream(
+    state: tauri::State<'_, PythonBridge<Wry>>,
+    window: tauri::Window<Wry>,
+    request_id: String,
+) -> Result<python_bridge::ReplayedStream, String> {
+    state.replay_stream(&request_id, window.label())
+}
+
+/// Distribution (p50/p90/p99) de la latence aller-retour d'une commande Python
+#[tauri::command]
+fn get_latency_percentiles(
+    state: tauri::State<'_, PythonBridge<Wry>>,
+    cmd: String,
+) -> python_bridge::LatencyPercentiles {
+    state.latency_percentiles(&cmd)
+}
+
+/// Durée de fonctionnement du worker, nombre d'arrêts et de redémarrages automatiques, dernier arrêt
+#[tauri::command]
+fn get_worker_status(state: tauri::State<'_, PythonBridge<Wry>>) -> python_bridge::WorkerStatus {
+    state.status()
+}
+
+/// Versions de l'app, du worker (handshake), du protocole IPC et d'Ollama (fenêtre "À propos")
+#[tauri::command]
+async fn get_versions(
+    app: tauri::AppHandle<Wry>,
+    state: tauri::State<'_, PythonBridge<Wry>>,
+) -> Result<Value, String> {
+    let ollama_version = tauri::async_runtime::spawn_blocking(ollama_installer::ollama_cli_version)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    Ok(serde_json::json!({
+        "app_version": app.package_info().version.to_string(),
+        "worker_version": state.worker_info_field("version"),
+        "protocol_version": python_bridge::IPC_PROTOCOL_VERSION,
+        "worker_protocol_version": state.worker_info_field("protocol_version"),
+        "ollama_version": ollama_version
+    }))
+}
+
+/// Indique si le worker Python a terminé son initialisation
+#[tauri::command]
+fn is_worker_ready(state: tauri::State<'_, PythonBridge<Wry>>) -> bool {
+    state.is_ready()
+}
+
+/// Restreint les événements "python-stream" relayés au frontend (None = tous)
+#[tauri::command]
+fn set_stream_filter(
+    state: tauri::State<'_, PythonBridge<Wry>>,
+    events: Option<Vec<String>>,
+) -> Result<(), String> {
+    state.set_stream_filter(events)
 }
 
 /// Vérifie si Ollama est installé
@@ -41,9 +215,66 @@ fn check_ollama_installed() -> bool {
 /// Installe Ollama automatiquement
 #[tauri::command]
 async fn install_ollama(app: AppHandle<Wry>) -> Result<(), String> {
+    egress_budget::check_egress(&app, "install_ollama")?;
     ollama_installer::download_and_install_ollama(&app).await
 }
 
+/// Teste la joignabilité d'un endpoint Ollama (avant de l'utiliser)
+/// Seul un endpoint hors de la machine est soumis au budget réseau
+#[tauri::command]
+async fn test_ollama_endpoint(
+    app: AppHandle<Wry>,
+    host: String,
+    port: u16,
+) -> Result<ollama_installer::EndpointProbe, String> {
+    if !ollama_installer::is_loopback_host(&host) {
+        egress_budget::check_egress(&app, "test_ollama_endpoint")?;
+    }
+    ollama_installer::probe_ollama_endpoint(&host, port).await
+}
+
+/// Détecte le GPU et indique si Ollama l'utilisera (avant de télécharger un gros modèle)
+#[tauri::command]
+async fn detect_gpu() -> Result<ollama_installer::GpuProbe, String> {
+    tauri::async_runtime::spawn_blocking(ollama_installer::detect_gpu)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Chat en streaming directement avec Ollama (événements "ollama-chat-token"), quand le worker
+/// Python est indisponible. Soumis à la permission NetworkAccess ; seul l'Ollama local est contacté.
+#[tauri::command]
+async fn ollama_chat_stream(
+    app: AppHandle<Wry>,
+    permission_state: tauri::State<'_, Mutex<PermissionManager<Wry>>>,
+    model: String,
+    messages: Vec<ollama_installer::OllamaChatMessage>,
+    request_id: Option<String>,
+) -> Result<ollama_installer::OllamaChatResult, String> {
+    permission_commands::ensure_permission(
+        &permission_state,
+        permission_manager::Permission::NetworkAccess,
+        &format!("Direct Ollama chat with {}", model),
+        "ollama_chat_stream",
+    )
+    .await?;
+
+    let request_id = request_id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    ollama_installer::stream_ollama_chat(&app, request_id, model, messages).await
+}
+
+/// Liste les processus Ollama en cours (PID et mémoire)
+#[tauri::command]
+fn list_ollama_processes() -> Result<Vec<ollama_installer::OllamaProcess>, String> {
+    ollama_installer::list_ollama_processes()
+}
+
+/// Tue un processus Ollama orphelin
+#[tauri::command]
+fn kill_ollama_process(pid: u32) -> Result<(), String> {
+    ollama_installer::kill_ollama_process(pid)
+}
+
 /// Démarre le service Ollama
 #[tauri::command]
 fn start_ollama() -> Result<(), String> {
@@ -121,8 +352,7 @@ pub fn run() {
         .plugin(tauri_plugin_log::Builder::new().build())
         .setup(|app| {
             // --- 1. INITIALISATION DU PERMISSION MANAGER ---
-            let mut permission_manager = PermissionManager::<Wry>::new(&app.handle())
-                .expect("Failed to initialize PermissionManager");
+            let mut permission_manager = PermissionManager::<Wry>::new(&app.handle());
 
             // V2.1 Phase 3 : Nettoyer les permissions expirées au démarrage
             let cleaned = permission_manager.cleanup_expired_permissions();
@@ -132,7 +362,9 @@ pub fn run() {
             }
 
             // --- 2. INITIALISATION DU CONTEXT READER ---
-            let context_reader = ContextReader::<Wry>::new(&app.handle());
+            let mut context_reader = ContextReader::<Wry>::new(&app.handle());
+            context_reader.restore_recent_files();
+            context_reader.restore_scope_state();
 
             // --- 3. INITIALISATION DU BRIDGE ---
             // Cette étape lance le Python Worker et connecte les canaux
@@ -142,7 +374,14 @@ pub fn run() {
             app.manage(bridge);
             app.manage(Mutex::new(permission_manager));
             app.manage(Mutex::new(context_reader));
-            app.manage(LicenseStore::new());
+            app.manage(Mutex::new(ScanRegistry::new()));
+            app.manage(Mutex::new(dependency_graph::DependencyGraphCache::new()));
+            app.manage(Mutex::new(ConfigFileWatch::new()));
+            let license_store = LicenseStore::new();
+            license_store.attach_app_handle(app.handle().clone());
+            app.manage(license_store);
+            app.manage(Mutex::new(CommandExecutor::new()));
+            app.manage(Mutex::new(EgressBudget::load(app.handle())));
 
             // ✅ DÉMARRER OLLAMA AU LANCEMENT (si installé)
             if ollama_installer::is_ollama_installed() {
@@ -157,11 +396,44 @@ pub fn run() {
             
             Ok(())
         })
+        .on_window_event(|window, event| {
+            // Une fenêtre fermée ne garde pas de scope propre
+            if let WindowEvent::Destroyed = event {
+                if let Some(state) = window.try_state::<Mutex<ContextReader<Wry>>>() {
+                    if let Ok(mut context_reader) = state.lock() {
+                        context_reader.clear_window_scope(window.label());
+                    }
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             call_python,
+            call_python_batch,
+            is_worker_ready,
+            get_worker_status,
+            get_latency_percentiles,
+            get_versions,
+            get_backend_status,
+            verify_backend_integrity,
+            set_active_model,
+            get_command_timeouts,
+            set_command_timeout,
+            get_pending_request_count,
+            set_max_pending,
+            set_stream_filter,
+            pause_stream,
+            resume_stream,
+            replay_stream,
             check_ollama_installed,
             install_ollama,
             start_ollama,
+            test_ollama_endpoint,
+            detect_gpu,
+            ollama_chat_stream,
+            egress_budget::get_egress_usage,
+            egress_budget::set_egress_budget,
+            list_ollama_processes,
+            kill_ollama_process,
             minimize_window,
             toggle_maximize,
             close_window,
@@ -169,31 +441,104 @@ pub fn run() {
             permission_commands::request_permission,
             permission_commands::request_permission_with_scope,  // V2.1 Phase 3 : Nouvelle commande avec scope
             permission_commands::has_permission,
+            permission_commands::describe_permission,
+            permission_commands::register_custom_permission,
+            permission_commands::list_permissions,
             permission_commands::has_permission_with_context,  // V2.1 Phase 3 : Vérification avec contexte (projectId)
+            permission_commands::revoke_project_permissions,
+            permission_commands::revoke_all_permissions,
+            permission_commands::export_permission_state,
+            permission_commands::import_permission_state,
             permission_commands::get_permission_logs,
+            permission_commands::read_audit_log_file,
+            permission_commands::get_project_permission_history,
             permission_commands::clear_permission_logs,
             permission_commands::export_permission_logs,
             permission_commands::get_parano_mode,
             permission_commands::set_parano_mode,
+            permission_commands::get_sandbox_mode,
+            permission_commands::set_sandbox_mode,
+            permission_commands::set_audit_log_retention,
+            permission_commands::set_audit_log_path,
             context_reader_commands::read_file,
             context_reader_commands::read_multiple_files,
             context_reader_commands::read_file_confirmed,
+            context_reader_commands::read_file_from_offset,
+            context_reader_commands::read_file_by_token_budget,
+            context_reader_commands::chunk_file,
+            context_reader_commands::get_recent_files,
+            context_reader_commands::export_context_manifest,
+            context_reader_commands::load_context_manifest,
+            context_reader_commands::diff_files,
             context_reader_commands::scan_directory,
+            context_reader_commands::scan_directory_stream,
+            context_reader_commands::resolve_context_selection,
+            context_reader_commands::cancel_scan,
+            context_reader_commands::analyze_repository,
+            context_reader_commands::scope_extension_breakdown,
+            context_reader_commands::find_duplicate_files,
+            context_reader_commands::detect_project_language,
+            context_reader_commands::analyze_dependencies,
             context_reader_commands::get_context_config,
+            context_reader_commands::get_confirmation_ttl,
+            context_reader_commands::set_confirmation_ttl,
+            context_reader_commands::get_pending_confirmation_tokens,
+            context_reader_commands::clear_confirmation_tokens,
+            context_reader_commands::get_current_scope,
+            context_reader_commands::is_path_in_scope,
+            context_reader_commands::get_last_scope,
+            context_reader_commands::restore_last_scope,
+            context_reader_commands::set_auto_restore_scope,
             context_reader_commands::set_context_scope,
+            context_reader_commands::clear_window_scope,
+            context_reader_commands::pick_scope_directory,
+            context_reader_commands::reveal_in_file_manager,
             context_reader_commands::get_file_preview,
+            context_reader_commands::get_file_previews,
+            context_reader_commands::get_file_stats,
+            context_reader_commands::can_read_file,
             context_reader_commands::update_context_config,
+            context_config_file::load_context_config_from_file,
+            context_config_file::stop_context_config_watch,
+            context_config_file::get_context_config_watch,
             context_reader_commands::add_allowed_extension,
             context_reader_commands::remove_allowed_extension,
+            context_reader_commands::list_allowed_extensions_detailed,
+            context_reader_commands::prepare_remote_read,
+            context_reader_commands::prepare_delete_file,
+            context_reader_commands::delete_file,
+            context_reader_commands::read_remote_file,
             window_manager::create_chat_window,
             window_manager::list_chat_windows,
             window_manager::close_chat_window,
             window_manager::update_chat_window_title,
+            window_manager::set_chat_window_opacity,
+            window_manager::reload_chat_window,
+            window_manager::navigate_chat_window,
             window_manager::move_window_to_screen,
             window_manager::get_available_screens,
+            window_manager::snap_chat_window,
             licensing::license_status,
             licensing::license_activate,
-            licensing::license_refresh
+            licensing::license_import_jws,
+            licensing::license_refresh,
+            licensing::check_license_expiry_warning,
+            licensing::license_start_trial,
+            licensing::verify_license_file,
+            licensing::repair_license,
+            diagnostics::export_diagnostics,
+            diagnostics::get_effective_config,
+            diagnostics::run_startup_diagnostics,
+            settings_profile::export_settings,
+            settings_profile::import_settings,
+            first_run::initialize_first_run,
+            storage::get_app_storage_usage,
+            storage::clear_app_cache,
+            command_executor::run_command,
+            command_executor::list_changed_files,
+            command_executor::get_command_allowlist,
+            command_executor::set_command_allowlist,
+            command_executor::set_command_limits
         ])
         .build(tauri::generate_context!())
         .expect("Erreur lors du lancement de l'application Horizon AI");
@@ -1,37 +1,115 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use tauri::State;
 
 use super::{
     device,
-    store::{LicenseRecord, LicenseStore},
-    verify::{build_license_record, Entitlement},
+    store::{LicenseFileDiagnostic, LicenseRecord, LicenseStore, MOCK_JWS_PREFIX},
+    verify::{build_license_record, decode_signed_entitlement, verify_entitlement, Entitlement},
 };
 
+/// Durée de la période d'essai (jours)
+const TRIAL_DAYS: i64 = 14;
+/// Fenêtre d'avertissement par défaut avant l'expiration (jours)
+const DEFAULT_EXPIRY_WARNING_DAYS: i64 = 3;
+/// Fenêtre d'avertissement maximale acceptée (jours)
+const MAX_EXPIRY_WARNING_DAYS: i64 = 30;
+
 #[derive(Debug, Serialize)]
 pub struct LicenseStatusDto {
     pub status: LicenseRecord,
+    pub days_remaining: Option<i64>,  // None si pas d'expiration (free, lifetime)
+    pub in_grace: bool,
 }
 
-/// Retourne l'état courant (in-memory pour l'instant).
-#[tauri::command]
-pub async fn license_status(store: State<'_, LicenseStore>) -> Result<LicenseStatusDto, String> {
-    Ok(LicenseStatusDto {
-        status: store.snapshot(),
-    })
+impl LicenseStatusDto {
+    /// Calcule les champs dérivés (jours restants, période de grâce) au moment du snapshot.
+    fn from_record(status: LicenseRecord, now: DateTime<Utc>) -> Self {
+        let (days_remaining, in_grace) = match status.expires_at {
+            Some(exp) => {
+                let grace_end = exp + chrono::Duration::days(status.grace_days.unwrap_or(0));
+                let in_grace = exp < now && now <= grace_end;
+                // En grâce, le décompte porte sur la fin de la période de grâce
+                let end = if in_grace { grace_end } else { exp };
+                let secs = (end - now).num_seconds().max(0);
+                (Some((secs + 86_399) / 86_400), in_grace)
+            }
+            None => (None, false),
+        };
+
+        LicenseStatusDto {
+            status,
+            days_remaining,
+            in_grace,
+        }
+    }
 }
 
-/// Active une licence (maquette sans appel réseau).
+/// Avertissement d'expiration prochaine (essai, abonnement mensuel ou période de grâce)
+#[derive(Debug, Clone, Serialize)]
+pub struct LicenseExpiryWarning {
+    pub expiring_soon: bool,
+    pub days_remaining: Option<i64>,  // Jusqu'à l'expiration, ou jusqu'à la fin de la grâce
+    pub expires_at: Option<DateTime<Utc>>,
+    pub in_grace: bool,
+    pub plan: String,
+    pub state: String,
+    pub warning_days: i64,
+}
+
+/// Évalue l'avertissement et émet "license-expiring-soon" si l'expiration tombe dans la fenêtre
+fn check_expiry(store: &LicenseStore, status: &LicenseStatusDto, warning_days: i64) -> LicenseExpiryWarning {
+    let expiring_soon = matches!(status.status.state.as_str(), "trial" | "active" | "grace")
+        && status.days_remaining.is_some_and(|days| days <= warning_days);
+    let warning = LicenseExpiryWarning {
+        expiring_soon,
+        days_remaining: status.days_remaining,
+        expires_at: status.status.expires_at,
+        in_grace: status.in_grace,
+        plan: status.status.plan.clone(),
+        state: status.status.state.clone(),
+        warning_days,
+    };
+    if expiring_soon {
+        store.notify("license-expiring-soon", warning.clone());
+    }
+    warning
+}
+
+/// Vérifie si la licence expire dans les `warning_days` prochains jours (3 par défaut)
+/// et émet alors "license-expiring-soon" pour que l'UI invite à renouveler.
 #[tauri::command]
-pub async fn license_activate(
-    key: String,
+pub async fn check_license_expiry_warning(
     store: State<'_, LicenseStore>,
-) -> Result<LicenseStatusDto, String> {
-    let now = Utc::now();
-    if key.trim().is_empty() {
-        return Err("missing_license_key".into());
+    warning_days: Option<i64>,
+) -> Result<LicenseExpiryWarning, String> {
+    let warning_days = warning_days.unwrap_or(DEFAULT_EXPIRY_WARNING_DAYS);
+    if !(1..=MAX_EXPIRY_WARNING_DAYS).contains(&warning_days) {
+        return Err("invalid_warning_days".into());
     }
+    let status = LicenseStatusDto::from_record(store.snapshot(), Utc::now());
+    Ok(check_expiry(&store, &status, warning_days))
+}
+
+/// Retourne l'état courant (in-memory pour l'instant).
+#[tauri::command]
+pub async fn license_status(store: State<'_, LicenseStore>) -> Result<LicenseStatusDto, String> {
+    Ok(LicenseStatusDto::from_record(store.snapshot(), Utc::now()))
+}
 
+/// Résultat d'une tentative de réparation du fichier licence
+#[derive(Debug, Serialize)]
+pub struct LicenseRepairResult {
+    pub repaired: bool,
+    pub method: Option<String>,  // signed_entitlement | license_key
+    pub diagnostic: LicenseFileDiagnostic,  // État du fichier avant réparation
+    pub backup_path: Option<String>,
+    pub status: LicenseStatusDto,
+    pub next_step: Option<String>,  // Action attendue de l'utilisateur si la réparation a échoué
+}
+
+/// Construit le record d'une activation par clé (maquette sans appel réseau).
+fn activation_record(key: &str, now: DateTime<Utc>) -> LicenseRecord {
     // TODO: remplacer par appel HTTP /license/activate + vérif JWS
     let local_fp = device::fingerprint();
     let is_lifetime = key.to_uppercase().contains("LIFE");
@@ -43,16 +121,62 @@ pub async fn license_activate(
         iat: Some(now),
         device_fingerprint: local_fp.clone(),
         grace_days: Some(10),
-        raw_jws: Some(format!("mock-jws-{}", key)),
+        raw_jws: Some(format!("{}{}", MOCK_JWS_PREFIX, key)),
     };
 
     let mut record = build_license_record(ent, now);
-    record.entitlement_jws = Some(format!("mock-jws-{}", key));
+    record.entitlement_jws = Some(format!("{}{}", MOCK_JWS_PREFIX, key));
+    record
+}
+
+/// Active une licence (maquette sans appel réseau).
+#[tauri::command]
+pub async fn license_activate(
+    key: String,
+    store: State<'_, LicenseStore>,
+) -> Result<LicenseStatusDto, String> {
+    let now = Utc::now();
+    if key.trim().is_empty() {
+        return Err("missing_license_key".into());
+    }
+
+    let mut record = activation_record(&key, now);
+    record.trial_started_at = store.snapshot().trial_started_at;
     store
         .save(record.clone())
         .map_err(|e| format!("persist_error: {e}"))?;
 
-    Ok(LicenseStatusDto { status: record })
+    Ok(LicenseStatusDto::from_record(record, now))
+}
+
+/// Importe un entitlement signé (JWS) pour une activation hors ligne (machines air-gapped).
+/// La signature est vérifiée avec la clé publique embarquée et l'empreinte machine doit correspondre.
+#[tauri::command]
+pub async fn license_import_jws(
+    jws: String,
+    store: State<'_, LicenseStore>,
+) -> Result<LicenseStatusDto, String> {
+    let now = Utc::now();
+    if jws.trim().is_empty() {
+        return Err("missing_jws".into());
+    }
+
+    let ent = decode_signed_entitlement(&jws).map_err(|e| e.code().to_string())?;
+
+    // Un entitlement hors ligne doit être lié à cette machine
+    let local_fp = device::fingerprint();
+    if ent.device_fingerprint.is_none() || local_fp.is_none() {
+        return Err("device_fingerprint_missing".into());
+    }
+    let ent = verify_entitlement(ent, now, local_fp).map_err(|e| e.code().to_string())?;
+
+    let mut record = build_license_record(ent, now);
+    record.trial_started_at = store.snapshot().trial_started_at;
+    store
+        .save(record.clone())
+        .map_err(|e| format!("persist_error: {e}"))?;
+
+    Ok(LicenseStatusDto::from_record(record, now))
 }
 
 /// Rafraîchit une licence (maquette).
@@ -61,8 +185,15 @@ pub async fn license_refresh(store: State<'_, LicenseStore>) -> Result<LicenseSt
     let now = Utc::now();
     let mut snapshot = store.snapshot();
 
+    // Trial : expire à la fin de la fenêtre d'essai, jamais prolongé automatiquement
+    if snapshot.state == "trial" {
+        if let Some(exp) = snapshot.expires_at {
+            if exp < now {
+                snapshot.state = "expired".to_string();
+            }
+        }
     // Monthly : si expiré -> state expired, sinon on rafraîchit iat/last_verified
-    if snapshot.plan == "pro_monthly" {
+    } else if snapshot.plan == "pro_monthly" {
         if let Some(exp) = snapshot.expires_at {
             if exp < now {
                 snapshot.state = "expired".to_string();
@@ -78,5 +209,128 @@ pub async fn license_refresh(store: State<'_, LicenseStore>) -> Result<LicenseSt
         .save(snapshot.clone())
         .map_err(|e| format!("persist_error: {e}"))?;
 
-    Ok(LicenseStatusDto { status: snapshot })
+    // Le refresh périodique sert aussi de veille d'expiration
+    let status = LicenseStatusDto::from_record(snapshot, now);
+    check_expiry(&store, &status, DEFAULT_EXPIRY_WARNING_DAYS);
+    Ok(status)
+}
+
+/// Démarre une période d'essai (une seule fois par installation).
+/// Le plan est "pro_monthly" pour le feature gating, avec l'état "trial".
+#[tauri::command]
+pub async fn license_start_trial(store: State<'_, LicenseStore>) -> Result<LicenseStatusDto, String> {
+    let now = Utc::now();
+    let mut snapshot = store.snapshot();
+
+    if snapshot.trial_started_at.is_some() {
+        return Err("trial_already_used".into());
+    }
+    if snapshot.state == "active" || snapshot.state == "grace" {
+        return Err("license_already_active".into());
+    }
+
+    snapshot.plan = "pro_monthly".to_string();
+    snapshot.state = "trial".to_string();
+    snapshot.trial_started_at = Some(now);
+    snapshot.expires_at = Some(now + chrono::Duration::days(TRIAL_DAYS));
+    snapshot.last_verified_at = Some(now);
+    snapshot.device_fingerprint = device::fingerprint();
+    snapshot.error = None;
+    store
+        .save(snapshot.clone())
+        .map_err(|e| format!("persist_error: {e}"))?;
+
+    Ok(LicenseStatusDto::from_record(snapshot, now))
+}
+
+/// Diagnostique le fichier licence : absent, illisible, corrompu, altéré ou valide.
+#[tauri::command]
+pub async fn verify_license_file(store: State<'_, LicenseStore>) -> Result<LicenseFileDiagnostic, String> {
+    Ok(store.check_file())
+}
+
+/// Tente de réparer un fichier licence corrompu ou altéré en réactivant l'entitlement stocké
+/// (JWS signé revérifié, ou clé de la maquette). Le fichier d'origine est sauvegardé avant
+/// réécriture. Sans entitlement récupérable, indique comment réactiver.
+#[tauri::command]
+pub async fn repair_license(store: State<'_, LicenseStore>) -> Result<LicenseRepairResult, String> {
+    let now = Utc::now();
+    let diagnostic = store.check_file();
+    if diagnostic.status == "valid" || diagnostic.status == "unverified" {
+        return Ok(LicenseRepairResult {
+            repaired: false,
+            method: None,
+            diagnostic,
+            backup_path: None,
+            status: LicenseStatusDto::from_record(store.snapshot(), now),
+            next_step: None,
+        });
+    }
+
+    // Récupérer ce qui peut l'être du fichier, même hors format
+    let raw = store.read_raw();
+    let field = |name: &str| raw.as_ref().and_then(|r| r.get(name)).and_then(|v| v.as_str()).map(String::from);
+    let stored_jws = field("entitlement_jws");
+    let trial_started_at = field("trial_started_at")
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|d| d.with_timezone(&Utc))
+        .or(store.snapshot().trial_started_at);
+
+    let mut failure: Option<String> = None;
+    let recovered = match stored_jws.as_deref() {
+        Some(jws) if jws.starts_with(MOCK_JWS_PREFIX) => {
+            let key = &jws[MOCK_JWS_PREFIX.len()..];
+            (!key.trim().is_empty()).then(|| ("license_key", activation_record(key, now)))
+        }
+        Some(jws) => match decode_signed_entitlement(jws)
+            .and_then(|ent| verify_entitlement(ent, now, device::fingerprint()))
+        {
+            Ok(ent) => Some(("signed_entitlement", build_license_record(ent, now))),
+            Err(e) => {
+                failure = Some(e.code().to_string());
+                None
+            }
+        },
+        None => None,
+    };
+
+    let Some((method, mut record)) = recovered else {
+        return Ok(LicenseRepairResult {
+            repaired: false,
+            method: None,
+            diagnostic,
+            backup_path: None,
+            status: LicenseStatusDto::from_record(store.snapshot(), now),
+            next_step: Some(match failure {
+                Some(code) => format!("reactivate_license ({})", code),
+                None => "reactivate_license".to_string(),
+            }),
+        });
+    };
+
+    let backup_path = if diagnostic.status == "missing" {
+        None
+    } else {
+        Some(
+            store
+                .backup_file()
+                .map_err(|e| format!("backup_error: {e}"))?
+                .to_string_lossy()
+                .into_owned(),
+        )
+    };
+
+    record.trial_started_at = trial_started_at;
+    store
+        .save(record.clone())
+        .map_err(|e| format!("persist_error: {e}"))?;
+
+    Ok(LicenseRepairResult {
+        repaired: true,
+        method: Some(method.to_string()),
+        diagnostic,
+        backup_path,
+        status: LicenseStatusDto::from_record(record, now),
+        next_step: None,
+    })
 }
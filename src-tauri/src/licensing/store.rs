@@ -2,19 +2,22 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
-use std::sync::Mutex;
+use std::sync::{Mutex, OnceLock};
+use tauri::{AppHandle, Emitter, Wry};
 
 /// Snapshot sérialisable de l'état licence. Reste volontairement simple pour ne pas casser l'existant.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LicenseRecord {
     pub plan: String,                     // free | pro_monthly | pro_lifetime
-    pub state: String,                    // free | active | grace | expired | error
+    pub state: String,                    // free | trial | active | grace | expired | error
     pub entitlement_jws: Option<String>,  // token signé côté serveur
     pub last_verified_at: Option<DateTime<Utc>>,
     pub expires_at: Option<DateTime<Utc>>,
     pub grace_days: Option<i64>,
     pub device_fingerprint: Option<String>,
     pub error: Option<String>,
+    #[serde(default)]
+    pub trial_started_at: Option<DateTime<Utc>>,  // Empêche de relancer un essai déjà consommé
 }
 
 impl Default for LicenseRecord {
@@ -28,15 +31,31 @@ impl Default for LicenseRecord {
             grace_days: None,
             device_fingerprint: None,
             error: None,
+            trial_started_at: None,
         }
     }
 }
 
+/// Préfixe des entitlements de la maquette d'activation (pas de signature à vérifier)
+pub(crate) const MOCK_JWS_PREFIX: &str = "mock-jws-";
+
+/// Diagnostic du fichier d'état licence
+#[derive(Debug, Clone, Serialize)]
+pub struct LicenseFileDiagnostic {
+    pub path: String,
+    pub status: String,  // missing | unreadable | corrupt | tampered | unverified | valid
+    pub error: Option<String>,
+    pub plan: Option<String>,
+    pub state: Option<String>,
+}
+
 /// Stockage simple sur disque (fichier JSON) + mutex in-memory.
 /// Remplacer par un store chiffré/credential vault pour la prod.
 pub struct LicenseStore {
     inner: Mutex<LicenseRecord>,
     path: PathBuf,
+    // Pour notifier toutes les fenêtres ("license-changed") à chaque sauvegarde
+    app_handle: OnceLock<AppHandle<Wry>>,
 }
 
 impl Default for LicenseStore {
@@ -52,9 +71,27 @@ impl LicenseStore {
         LicenseStore {
             inner: Mutex::new(initial),
             path,
+            app_handle: OnceLock::new(),
         }
     }
 
+    /// Branche l'AppHandle utilisé pour émettre "license-changed" (appelé au setup)
+    pub fn attach_app_handle(&self, app_handle: AppHandle<Wry>) {
+        let _ = self.app_handle.set(app_handle);
+    }
+
+    /// Émet un événement licence vers toutes les fenêtres (sans effet avant le setup)
+    pub fn notify<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        if let Some(app_handle) = self.app_handle.get() {
+            let _ = app_handle.emit(event, payload);
+        }
+    }
+
+    /// Emplacement du fichier d'état licence
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
     pub fn snapshot(&self) -> LicenseRecord {
         self.inner.lock().unwrap().clone()
     }
@@ -65,9 +102,71 @@ impl LicenseStore {
         }
         let data = serde_json::to_vec_pretty(&record)?;
         fs::write(&self.path, data)?;
+
+        // Toutes les fenêtres (y compris les chats détachés) mettent à jour leur plan
+        if let Some(app_handle) = self.app_handle.get() {
+            let _ = app_handle.emit("license-changed", &record);
+        }
         Ok(())
     }
 
+    /// Distingue un fichier absent d'un fichier présent mais illisible, corrompu ou altéré
+    /// (au chargement, ces cas retombent silencieusement sur le plan free)
+    pub fn check_file(&self) -> LicenseFileDiagnostic {
+        let diagnostic = |status: &str, error: Option<String>, record: Option<&LicenseRecord>| {
+            LicenseFileDiagnostic {
+                path: self.path.to_string_lossy().into_owned(),
+                status: status.to_string(),
+                error,
+                plan: record.map(|r| r.plan.clone()),
+                state: record.map(|r| r.state.clone()),
+            }
+        };
+
+        let data = match fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return diagnostic("missing", None, None),
+            Err(e) => return diagnostic("unreadable", Some(e.to_string()), None),
+        };
+        let record = match serde_json::from_slice::<LicenseRecord>(&data) {
+            Ok(record) => record,
+            Err(e) => return diagnostic("corrupt", Some(e.to_string()), None),
+        };
+
+        match record.entitlement_jws.as_deref() {
+            // Entitlement signé : la signature et le plan doivent concorder avec le fichier
+            Some(jws) if !jws.starts_with(MOCK_JWS_PREFIX) => {
+                match super::verify::decode_signed_entitlement(jws) {
+                    Ok(ent) if ent.plan == record.plan => diagnostic("valid", None, Some(&record)),
+                    Ok(ent) => diagnostic(
+                        "tampered",
+                        Some(format!("plan {} does not match signed plan {}", record.plan, ent.plan)),
+                        Some(&record),
+                    ),
+                    Err(e) => diagnostic("tampered", Some(e.code().to_string()), Some(&record)),
+                }
+            }
+            Some(_) => diagnostic("unverified", None, Some(&record)),
+            None => diagnostic("valid", None, Some(&record)),
+        }
+    }
+
+    /// Relit le fichier brut, même s'il ne respecte plus le format attendu
+    pub fn read_raw(&self) -> Option<serde_json::Value> {
+        let data = fs::read(&self.path).ok()?;
+        serde_json::from_slice(&data).ok()
+    }
+
+    /// Copie le fichier courant à côté de l'original avant une réparation
+    pub fn backup_file(&self) -> std::io::Result<PathBuf> {
+        let backup = self.path.with_extension(format!(
+            "json.corrupt-{}",
+            Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        fs::copy(&self.path, &backup)?;
+        Ok(backup)
+    }
+
     fn load_from_disk(path: &PathBuf) -> Option<LicenseRecord> {
         let data = fs::read(path).ok()?;
         serde_json::from_slice::<LicenseRecord>(&data).ok()
@@ -1,4 +1,7 @@
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
 use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::{Signature, VerifyingKey};
 use serde::{Deserialize, Serialize};
 
 use super::store::LicenseRecord;
@@ -13,12 +16,103 @@ pub struct Entitlement {
     pub raw_jws: Option<String>,
 }
 
+/// Clé publique Ed25519 du serveur de licences (hex), injectée au build via HORIZON_LICENSE_PUBLIC_KEY
+/// (build.rs refuse un build release sans clé). None en développement : l'activation hors ligne échoue.
+const LICENSE_PUBLIC_KEY_HEX: Option<&str> = option_env!("HORIZON_LICENSE_PUBLIC_KEY");
+
+/// Clé publique servant à vérifier les entitlements signés (JWS EdDSA)
+fn license_public_key() -> Result<VerifyingKey, VerifyError> {
+    let bytes: [u8; 32] = LICENSE_PUBLIC_KEY_HEX
+        .and_then(|key| hex::decode(key).ok())
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or(VerifyError::MissingPublicKey)?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| VerifyError::MissingPublicKey)
+}
+
 #[derive(Debug)]
 pub enum VerifyError {
     ClockSkew,
     Expired,
     DeviceMismatch,
     InvalidFormat,
+    InvalidSignature,
+    MissingPublicKey,  // Build sans clé publique de production
+}
+
+impl VerifyError {
+    /// Code d'erreur renvoyé au frontend
+    pub fn code(&self) -> &'static str {
+        match self {
+            VerifyError::ClockSkew => "clock_skew",
+            VerifyError::Expired => "license_expired",
+            VerifyError::DeviceMismatch => "device_mismatch",
+            VerifyError::InvalidFormat => "invalid_jws_format",
+            VerifyError::InvalidSignature => "invalid_jws_signature",
+            VerifyError::MissingPublicKey => "license_key_unavailable",
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct JwsHeader {
+    alg: String,
+}
+
+/// Claims d'un entitlement signé (dates en secondes Unix)
+#[derive(Debug, Deserialize)]
+struct EntitlementClaims {
+    plan: String,
+    exp: Option<i64>,
+    iat: Option<i64>,
+    device_fingerprint: Option<String>,
+    grace_days: Option<i64>,
+}
+
+fn decode_segment(segment: &str) -> Result<Vec<u8>, VerifyError> {
+    URL_SAFE_NO_PAD
+        .decode(segment.trim_end_matches('='))
+        .map_err(|_| VerifyError::InvalidFormat)
+}
+
+fn timestamp(secs: Option<i64>) -> Result<Option<DateTime<Utc>>, VerifyError> {
+    secs.map(|s| DateTime::from_timestamp(s, 0).ok_or(VerifyError::InvalidFormat))
+        .transpose()
+}
+
+/// Vérifie hors ligne un JWS compact (header.payload.signature) signé en EdDSA
+/// avec la clé publique embarquée, et en extrait l'entitlement. Aucun appel réseau.
+pub fn decode_signed_entitlement(jws: &str) -> Result<Entitlement, VerifyError> {
+    let jws = jws.trim();
+    let mut parts = jws.split('.');
+    let (header_b64, payload_b64, signature_b64) = match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(h), Some(p), Some(s), None) => (h, p, s),
+        _ => return Err(VerifyError::InvalidFormat),
+    };
+
+    let header: JwsHeader = serde_json::from_slice(&decode_segment(header_b64)?)
+        .map_err(|_| VerifyError::InvalidFormat)?;
+    if header.alg != "EdDSA" {
+        return Err(VerifyError::InvalidFormat);
+    }
+
+    let signature = Signature::from_slice(&decode_segment(signature_b64)?)
+        .map_err(|_| VerifyError::InvalidFormat)?;
+    let key = license_public_key()?;
+    let signing_input = &jws[..header_b64.len() + 1 + payload_b64.len()];
+    key.verify_strict(signing_input.as_bytes(), &signature)
+        .map_err(|_| VerifyError::InvalidSignature)?;
+
+    let claims: EntitlementClaims = serde_json::from_slice(&decode_segment(payload_b64)?)
+        .map_err(|_| VerifyError::InvalidFormat)?;
+
+    Ok(Entitlement {
+        plan: claims.plan,
+        exp: timestamp(claims.exp)?,
+        iat: timestamp(claims.iat)?,
+        device_fingerprint: claims.device_fingerprint,
+        grace_days: claims.grace_days,
+        raw_jws: Some(jws.to_string()),
+    })
 }
 
 /// Vérification simplifiée : parse l'entitlement et applique des gardes basiques.
@@ -74,5 +168,6 @@ pub fn build_license_record(ent: Entitlement, now: DateTime<Utc>) -> LicenseReco
         grace_days: ent.grace_days,
         device_fingerprint: ent.device_fingerprint,
         error: None,
+        trial_started_at: None,
     }
 }
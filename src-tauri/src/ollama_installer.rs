@@ -1,7 +1,12 @@
 use std::process::Command;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
+use std::io::{Read, Write};
+use sha2::{Digest, Sha256};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Runtime, Emitter};
+use crate::egress_budget::{check_egress, record_egress};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
@@ -10,6 +15,75 @@ use std::os::windows::process::CommandExt;
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// Noms des processus Ollama (serveur et runners de modèles)
+#[cfg(windows)]
+const OLLAMA_PROCESS_NAMES: [&str; 2] = ["ollama.exe", "ollama_llama_server.exe"];
+#[cfg(not(windows))]
+const OLLAMA_PROCESS_NAMES: [&str; 2] = ["ollama", "ollama_llama_server"];
+
+/// Endpoint de l'API Ollama locale
+pub(crate) const OLLAMA_ENDPOINT: &str = "http://localhost:11434";
+
+/// Sommes de contrôle publiées avec chaque release Ollama ("<sha256>  ./<fichier>")
+const OLLAMA_CHECKSUMS_URL: &str = "https://github.com/ollama/ollama/releases/latest/download/sha256sum.txt";
+
+/// Délai maximal pour tester un endpoint Ollama
+const ENDPOINT_PROBE_TIMEOUT_SECS: u64 = 5;
+
+/// Délai de connexion à Ollama pour le chat direct (la réponse elle-même peut être longue)
+const CHAT_CONNECT_TIMEOUT_SECS: u64 = 5;
+/// Rôles acceptés dans les messages du chat direct
+const CHAT_ROLES: [&str; 3] = ["system", "user", "assistant"];
+
+/// Première version d'Ollama prenant en charge les GPU AMD (ROCm)
+const OLLAMA_MIN_AMD_VERSION: (u32, u32, u32) = (0, 1, 29);
+/// Identifiant PCI des GPU AMD (sysfs Linux)
+const AMD_PCI_VENDOR_ID: &str = "0x1002";
+
+/// Processus Ollama en cours d'exécution
+#[derive(Debug, Clone, Serialize)]
+pub struct OllamaProcess {
+    pub pid: u32,
+    pub name: String,
+    pub memory_bytes: u64,
+}
+
+/// Résultat du test d'un endpoint Ollama
+#[derive(Debug, Clone, Serialize)]
+pub struct EndpointProbe {
+    pub reachable: bool,
+    pub latency_ms: Option<u64>,
+    pub version: Option<String>,
+    pub error: Option<String>,
+}
+
+/// Message du chat direct avec Ollama (format /api/chat)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OllamaChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+/// Réponse complète d'un chat direct (les tokens ont été émis au fil de l'eau)
+#[derive(Debug, Clone, Serialize)]
+pub struct OllamaChatResult {
+    pub request_id: String,
+    pub model: String,
+    pub content: String,
+    pub token_count: usize,  // Fragments "ollama-chat-token" émis
+}
+
+/// GPU détecté et capacité de la version d'Ollama installée à l'utiliser
+#[derive(Debug, Clone, Serialize)]
+pub struct GpuProbe {
+    pub vendor: Option<String>,  // "nvidia", "apple" ou "amd" (None = aucun GPU pris en charge)
+    pub name: Option<String>,
+    pub vram_mb: Option<u64>,  // Mémoire unifiée pour Apple Silicon
+    pub ollama_version: Option<String>,
+    pub ollama_will_use_gpu: bool,
+    pub warning: Option<String>,
+}
+
 /// Vérifie si Ollama est installé sur le système
 pub fn is_ollama_installed() -> bool {
     // Méthode 1: Vérifier si la commande ollama existe
@@ -51,6 +125,139 @@ pub fn is_ollama_installed() -> bool {
     false
 }
 
+/// Version de la CLI Ollama (`ollama --version`), None si indisponible
+pub fn ollama_cli_version() -> Option<String> {
+    let mut command = Command::new("ollama");
+    command.arg("--version");
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    let output = command.output().ok()?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    // Sortie du type "ollama version is 0.5.7" (avertissements éventuels sur d'autres lignes)
+    text.lines()
+        .find(|line| line.contains("version"))
+        .and_then(|line| line.split_whitespace().last())
+        .map(String::from)
+}
+
+/// Version "x.y.z" en tuple comparable (suffixes type "-rc1" ignorés)
+fn parse_version(version: &str) -> Option<(u32, u32, u32)> {
+    let mut parts = version
+        .trim_start_matches('v')
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.parse::<u32>().ok());
+    Some((parts.next()??, parts.next().flatten().unwrap_or(0), parts.next().flatten().unwrap_or(0)))
+}
+
+/// Sortie standard d'une commande, None si elle est absente ou échoue
+fn command_stdout(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    #[cfg(windows)]
+    command.creation_flags(CREATE_NO_WINDOW);
+
+    let output = command.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// GPU NVIDIA via `nvidia-smi` (premier GPU) : (nom, VRAM en Mo)
+fn probe_nvidia() -> Option<(String, Option<u64>)> {
+    let output = command_stdout(
+        "nvidia-smi",
+        &["--query-gpu=name,memory.total", "--format=csv,noheader,nounits"],
+    )?;
+    let line = output.lines().next()?;
+    let mut fields = line.split(',').map(str::trim);
+    let name = fields.next().filter(|name| !name.is_empty())?.to_string();
+    let vram_mb = fields.next().and_then(|vram| vram.parse().ok());
+    Some((name, vram_mb))
+}
+
+/// Apple Silicon (GPU Metal, mémoire unifiée) : (nom de la puce, mémoire en Mo)
+fn probe_apple_silicon() -> Option<(String, Option<u64>)> {
+    if !(cfg!(target_os = "macos") && cfg!(target_arch = "aarch64")) {
+        return None;
+    }
+    let name = command_stdout("sysctl", &["-n", "machdep.cpu.brand_string"])
+        .unwrap_or_else(|| "Apple Silicon".to_string());
+    let memory_mb = command_stdout("sysctl", &["-n", "hw.memsize"])
+        .and_then(|bytes| bytes.parse::<u64>().ok())
+        .map(|bytes| bytes / (1024 * 1024));
+    Some((name, memory_mb))
+}
+
+/// GPU AMD via sysfs (Linux uniquement) : (nom, VRAM en Mo)
+fn probe_amd() -> Option<(String, Option<u64>)> {
+    let cards = fs::read_dir("/sys/class/drm").ok()?;
+    cards
+        .flatten()
+        .map(|card| card.path().join("device"))
+        .find(|device| {
+            fs::read_to_string(device.join("vendor"))
+                .map(|vendor| vendor.trim() == AMD_PCI_VENDOR_ID)
+                .unwrap_or(false)
+        })
+        .map(|device| {
+            let vram_mb = fs::read_to_string(device.join("mem_info_vram_total"))
+                .ok()
+                .and_then(|bytes| bytes.trim().parse::<u64>().ok())
+                .map(|bytes| bytes / (1024 * 1024));
+            let name = fs::read_to_string(device.join("product_name"))
+                .ok()
+                .map(|name| name.trim().to_string())
+                .filter(|name| !name.is_empty())
+                .unwrap_or_else(|| "AMD GPU".to_string());
+            (name, vram_mb)
+        })
+}
+
+/// Détecte le GPU (NVIDIA, Apple Silicon, AMD) et indique si Ollama pourra l'utiliser
+/// Appels bloquants (sous-processus) : à exécuter hors du runtime async
+pub fn detect_gpu() -> GpuProbe {
+    let ollama_version = ollama_cli_version();
+    let detected = probe_nvidia()
+        .map(|gpu| ("nvidia", gpu))
+        .or_else(|| probe_apple_silicon().map(|gpu| ("apple", gpu)))
+        .or_else(|| probe_amd().map(|gpu| ("amd", gpu)));
+
+    let Some((vendor, (name, vram_mb))) = detected else {
+        return GpuProbe {
+            vendor: None,
+            name: None,
+            vram_mb: None,
+            ollama_version,
+            ollama_will_use_gpu: false,
+            warning: Some("No supported GPU detected, models will run on CPU".to_string()),
+        };
+    };
+
+    let (ollama_will_use_gpu, warning) = match ollama_version.as_deref() {
+        None => (false, Some("Ollama is not installed, GPU support could not be verified".to_string())),
+        Some(version) if vendor == "amd" && parse_version(version).is_some_and(|v| v < OLLAMA_MIN_AMD_VERSION) => (
+            false,
+            Some(format!(
+                "Ollama {} predates AMD GPU support ({}.{}.{}), update Ollama to use this GPU",
+                version, OLLAMA_MIN_AMD_VERSION.0, OLLAMA_MIN_AMD_VERSION.1, OLLAMA_MIN_AMD_VERSION.2
+            )),
+        ),
+        Some(_) => (true, None),
+    };
+
+    GpuProbe {
+        vendor: Some(vendor.to_string()),
+        name: Some(name),
+        vram_mb,
+        ollama_version,
+        ollama_will_use_gpu,
+        warning,
+    }
+}
+
 /// Télécharge et installe Ollama
 pub async fn download_and_install_ollama<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
     let download_url = "https://ollama.com/download/OllamaSetup.exe";
@@ -63,8 +270,14 @@ pub async fn download_and_install_ollama<R: Runtime>(app: &AppHandle<R>) -> Resu
         "message": "Downloading Ollama..."
     }));
     
-    // Télécharger l'installeur
-    match download_file(download_url, &installer_path).await {
+    // Checksum attendu (best-effort : si indisponible, le téléchargement n'est pas vérifié)
+    let expected_sha256 = fetch_expected_sha256(app, "OllamaSetup.exe").await;
+    if expected_sha256.is_none() {
+        eprintln!("[OLLAMA] Checksum unavailable, installer download will not be verified");
+    }
+
+    // Télécharger l'installeur (reprend un téléchargement partiel s'il existe)
+    match download_file(app, download_url, &installer_path, expected_sha256.as_deref()).await {
         Ok(_) => {
             let _ = app.emit("ollama-install-status", serde_json::json!({
                 "status": "installing",
@@ -134,24 +347,134 @@ pub async fn download_and_install_ollama<R: Runtime>(app: &AppHandle<R>) -> Resu
     }
 }
 
-/// Télécharge un fichier depuis une URL
-async fn download_file(url: &str, destination: &PathBuf) -> Result<(), String> {
-    // Utiliser reqwest pour le téléchargement
-    let response = reqwest::get(url)
-        .await
-        .map_err(|e| format!("HTTP request failed: {}", e))?;
-    
+/// Récupère le SHA-256 publié pour un fichier de la dernière release Ollama
+async fn fetch_expected_sha256<R: Runtime>(app: &AppHandle<R>, file_name: &str) -> Option<String> {
+    let response = reqwest::get(OLLAMA_CHECKSUMS_URL).await.ok()?;
     if !response.status().is_success() {
-        return Err(format!("HTTP error: {}", response.status()));
+        return None;
     }
-    
-    let bytes = response.bytes()
-        .await
-        .map_err(|e| format!("Failed to read response: {}", e))?;
-    
-    fs::write(destination, bytes)
-        .map_err(|e| format!("Failed to write file: {}", e))?;
-    
+    let body = response.text().await.ok()?;
+    record_egress(app, body.len() as u64);
+    body.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        let hash = parts.next()?;
+        let name = parts.next()?;
+        (name.trim_start_matches("./") == file_name).then(|| hash.to_lowercase())
+    })
+}
+
+/// SHA-256 d'un fichier (lecture par blocs)
+pub(crate) fn file_sha256(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buffer)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buffer[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Télécharge un fichier depuis une URL
+/// Le contenu est écrit dans `<destination>.part` ; si ce fichier existe déjà, le téléchargement
+/// reprend à sa taille actuelle (en-tête Range). Le checksum est vérifié avant de finaliser.
+/// Le trafic est compté dans le budget réseau quotidien ; s'il est atteint, le téléchargement
+/// s'arrête en gardant le `.part` pour une reprise ultérieure.
+async fn download_file<R: Runtime>(
+    app: &AppHandle<R>,
+    url: &str,
+    destination: &Path,
+    expected_sha256: Option<&str>,
+) -> Result<(), String> {
+    let mut part_name = destination.as_os_str().to_owned();
+    part_name.push(".part");
+    let part_path = PathBuf::from(part_name);
+
+    let mut existing = fs::metadata(&part_path).map(|m| m.len()).unwrap_or(0);
+
+    let client = reqwest::Client::new();
+    let (mut response, status) = loop {
+        let mut request = client.get(url);
+        if existing > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={}-", existing));
+        }
+        let response = request
+            .send()
+            .await
+            .map_err(|e| format!("HTTP request failed: {}", e))?;
+
+        let status = response.status();
+        // Fichier partiel "complet" mais sans empreinte pour le vérifier : repartir de zéro
+        if existing > 0 && status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE && expected_sha256.is_none() {
+            fs::remove_file(&part_path)
+                .map_err(|e| format!("Failed to remove partial file: {}", e))?;
+            existing = 0;
+            continue;
+        }
+        break (response, status);
+    };
+
+    let mut file = if existing > 0 && status == reqwest::StatusCode::PARTIAL_CONTENT {
+        let _ = app.emit("ollama-install-status", serde_json::json!({
+            "status": "resuming",
+            "message": format!("Resuming download from {} bytes...", existing)
+        }));
+        Some(
+            fs::OpenOptions::new()
+                .append(true)
+                .open(&part_path)
+                .map_err(|e| format!("Failed to open partial file: {}", e))?,
+        )
+    } else if existing > 0 && status == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
+        // Le fichier partiel est déjà complet : il ne reste qu'à vérifier son empreinte
+        None
+    } else if status.is_success() {
+        // Pas de reprise possible (ou premier téléchargement) : repartir de zéro
+        Some(
+            fs::File::create(&part_path)
+                .map_err(|e| format!("Failed to write file: {}", e))?,
+        )
+    } else {
+        return Err(format!("HTTP error: {}", status));
+    };
+
+    if let Some(file) = file.as_mut() {
+        // Écriture au fil de l'eau : une interruption laisse un .part réutilisable
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| format!("Failed to read response: {}", e))?
+        {
+            file.write_all(&chunk)
+                .map_err(|e| format!("Failed to write file: {}", e))?;
+            record_egress(app, chunk.len() as u64);
+            check_egress(app, "install_ollama")?;
+        }
+        file.flush().map_err(|e| format!("Failed to write file: {}", e))?;
+    }
+    drop(file);
+
+    if let Some(expected) = expected_sha256 {
+        let actual = file_sha256(&part_path)?;
+        if !actual.eq_ignore_ascii_case(expected) {
+            // Fichier corrompu : ne pas le réutiliser pour une prochaine reprise
+            let _ = fs::remove_file(&part_path);
+            return Err(format!(
+                "Checksum mismatch (expected {}, got {})",
+                expected, actual
+            ));
+        }
+    }
+
+    fs::rename(&part_path, destination)
+        .map_err(|e| format!("Failed to finalize download: {}", e))?;
+
     Ok(())
 }
 
@@ -159,15 +482,17 @@ async fn download_file(url: &str, destination: &PathBuf) -> Result<(), String> {
 pub fn is_ollama_running() -> bool {
     // Essayer de se connecter à l'API Ollama via reqwest (plus simple que curl)
     // On utilise une version synchrone simple
+    let tags_url = format!("{}/api/tags", OLLAMA_ENDPOINT);
+
     #[cfg(windows)]
     let result = Command::new("curl")
-        .args(["-s", "-o", "nul", "-w", "%{http_code}", "http://localhost:11434/api/tags"])
+        .args(["-s", "-o", "nul", "-w", "%{http_code}", &tags_url])
         .creation_flags(CREATE_NO_WINDOW)
         .output();
     
     #[cfg(not(windows))]
     let result = Command::new("curl")
-        .args(["-s", "-o", "/dev/null", "-w", "%{http_code}", "http://localhost:11434/api/tags"])
+        .args(["-s", "-o", "/dev/null", "-w", "%{http_code}", &tags_url])
         .output();
     
     if let Ok(output) = result {
@@ -201,3 +526,284 @@ pub fn start_ollama_service() -> Result<(), String> {
     
     Ok(())
 }
+
+/// Indique si un host désigne la machine locale (trafic non soumis au budget réseau)
+pub fn is_loopback_host(host: &str) -> bool {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host.parse::<std::net::IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Teste un endpoint Ollama arbitraire (LAN/distant) via /api/tags
+/// La version est récupérée en best-effort via /api/version
+pub async fn probe_ollama_endpoint(host: &str, port: u16) -> Result<EndpointProbe, String> {
+    let host = host.trim();
+    if host.is_empty() {
+        return Err("Host cannot be empty".to_string());
+    }
+
+    let tags_url = reqwest::Url::parse(&format!("http://{}:{}/api/tags", host, port))
+        .map_err(|e| format!("Invalid endpoint {}:{}: {}", host, port, e))?;
+    // Le host ne doit pas pouvoir injecter un chemin, des identifiants ou une requête
+    if tags_url.path() != "/api/tags"
+        || tags_url.query().is_some()
+        || !tags_url.username().is_empty()
+        || tags_url.port() != Some(port)
+    {
+        return Err(format!("Invalid host: {}", host));
+    }
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(ENDPOINT_PROBE_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let started = Instant::now();
+    let response = match client.get(tags_url.clone()).send().await {
+        Ok(response) => response,
+        Err(e) => {
+            return Ok(EndpointProbe {
+                reachable: false,
+                latency_ms: None,
+                version: None,
+                error: Some(e.to_string()),
+            });
+        }
+    };
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    if !response.status().is_success() {
+        return Ok(EndpointProbe {
+            reachable: false,
+            latency_ms: Some(latency_ms),
+            version: None,
+            error: Some(format!("HTTP error: {}", response.status())),
+        });
+    }
+
+    let mut version_url = tags_url;
+    version_url.set_path("/api/version");
+    let version = match client.get(version_url).send().await {
+        Ok(response) if response.status().is_success() => response
+            .bytes()
+            .await
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<serde_json::Value>(&bytes).ok())
+            .and_then(|v| v.get("version").and_then(|v| v.as_str()).map(String::from)),
+        _ => None,
+    };
+
+    Ok(EndpointProbe {
+        reachable: true,
+        latency_ms: Some(latency_ms),
+        version,
+        error: None,
+    })
+}
+
+/// Chat en streaming directement avec l'instance Ollama locale (`/api/chat`), sans le worker
+/// Python : chemin dégradé quand le worker est arrêté. Chaque fragment est émis dans un
+/// événement "ollama-chat-token" ; un dernier événement `done: true` (avec `error` en cas
+/// d'échec) est toujours émis. Seul OLLAMA_ENDPOINT est contacté (aucune donnée ne sort de la machine).
+pub async fn stream_ollama_chat<R: Runtime>(
+    app: &AppHandle<R>,
+    request_id: String,
+    model: String,
+    messages: Vec<OllamaChatMessage>,
+) -> Result<OllamaChatResult, String> {
+    let result = run_ollama_chat(app, &request_id, &model, messages).await;
+    let _ = app.emit("ollama-chat-token", serde_json::json!({
+        "request_id": request_id,
+        "token": "",
+        "done": true,
+        "error": result.as_ref().err()
+    }));
+    let (content, token_count) = result?;
+
+    Ok(OllamaChatResult {
+        request_id,
+        model,
+        content,
+        token_count,
+    })
+}
+
+async fn run_ollama_chat<R: Runtime>(
+    app: &AppHandle<R>,
+    request_id: &str,
+    model: &str,
+    messages: Vec<OllamaChatMessage>,
+) -> Result<(String, usize), String> {
+    if model.trim().is_empty() {
+        return Err("Model name cannot be empty".to_string());
+    }
+    if messages.is_empty() {
+        return Err("At least one message is required".to_string());
+    }
+    if let Some(message) = messages.iter().find(|m| !CHAT_ROLES.contains(&m.role.as_str())) {
+        return Err(format!("Invalid message role: {}", message.role));
+    }
+
+    let client = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(CHAT_CONNECT_TIMEOUT_SECS))
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let body = serde_json::to_vec(&serde_json::json!({
+        "model": model,
+        "messages": messages,
+        "stream": true
+    }))
+    .map_err(|e| e.to_string())?;
+
+    let mut response = client
+        .post(format!("{}/api/chat", OLLAMA_ENDPOINT))
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(body)
+        .send()
+        .await
+        .map_err(|e| format!("Ollama is not reachable at {}: {}", OLLAMA_ENDPOINT, e))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        return Err(format!("Ollama chat failed ({}): {}", status, body.trim()));
+    }
+
+    // Réponse NDJSON : une ligne JSON par fragment, les lignes peuvent être coupées entre deux chunks
+    let mut pending = Vec::new();
+    let mut content = String::new();
+    let mut token_count = 0;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Ollama stream interrupted: {}", e))?
+    {
+        pending.extend_from_slice(&chunk);
+        while let Some(newline) = pending.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = pending.drain(..=newline).collect();
+            let Ok(event) = serde_json::from_slice::<serde_json::Value>(&line) else {
+                continue;
+            };
+            if let Some(error) = event.get("error").and_then(|e| e.as_str()) {
+                return Err(format!("Ollama error: {}", error));
+            }
+            let token = event
+                .pointer("/message/content")
+                .and_then(|c| c.as_str())
+                .unwrap_or_default();
+            if !token.is_empty() {
+                content.push_str(token);
+                token_count += 1;
+                let _ = app.emit("ollama-chat-token", serde_json::json!({
+                    "request_id": request_id,
+                    "token": token,
+                    "done": false
+                }));
+            }
+        }
+    }
+
+    Ok((content, token_count))
+}
+
+/// Teste l'instance Ollama locale (OLLAMA_ENDPOINT)
+pub async fn probe_local_ollama() -> Result<EndpointProbe, String> {
+    let endpoint = reqwest::Url::parse(OLLAMA_ENDPOINT).map_err(|e| e.to_string())?;
+    let host = endpoint.host_str().unwrap_or("localhost");
+    let port = endpoint.port_or_known_default().unwrap_or(11434);
+    probe_ollama_endpoint(host, port).await
+}
+
+/// Liste les processus Ollama en cours (utile après des crashs qui laissent des runners en VRAM)
+#[cfg(windows)]
+pub fn list_ollama_processes() -> Result<Vec<OllamaProcess>, String> {
+    let mut processes = Vec::new();
+
+    for name in OLLAMA_PROCESS_NAMES {
+        let output = Command::new("tasklist")
+            .args(["/FO", "CSV", "/NH", "/FI", &format!("IMAGENAME eq {}", name)])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| format!("Failed to list processes: {}", e))?;
+
+        // Format : "ollama.exe","1234","Console","1","123 456 K"
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            let fields: Vec<&str> = line.split("\",\"").map(|f| f.trim_matches('"')).collect();
+            if fields.len() < 5 || !fields[0].eq_ignore_ascii_case(name) {
+                continue;
+            }
+            let Ok(pid) = fields[1].parse::<u32>() else { continue };
+            // Le séparateur de milliers dépend de la locale : ne garder que les chiffres
+            let memory_kb: u64 = fields[4]
+                .chars()
+                .filter(|c| c.is_ascii_digit())
+                .collect::<String>()
+                .parse()
+                .unwrap_or(0);
+            processes.push(OllamaProcess {
+                pid,
+                name: fields[0].to_string(),
+                memory_bytes: memory_kb * 1024,
+            });
+        }
+    }
+
+    Ok(processes)
+}
+
+/// Liste les processus Ollama en cours (utile après des crashs qui laissent des runners en VRAM)
+#[cfg(not(windows))]
+pub fn list_ollama_processes() -> Result<Vec<OllamaProcess>, String> {
+    let output = Command::new("ps")
+        // comm est tronqué à 15 caractères (ollama_llama_server) : utiliser l'exécutable de args
+        .args(["-eo", "pid=,rss=,args="])
+        .output()
+        .map_err(|e| format!("Failed to list processes: {}", e))?;
+
+    let processes = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let pid = fields.next()?.parse::<u32>().ok()?;
+            let rss_kb = fields.next()?.parse::<u64>().ok()?;
+            let executable = fields.next()?;
+            let name = executable.rsplit('/').next().unwrap_or(executable).to_string();
+            OLLAMA_PROCESS_NAMES.contains(&name.as_str()).then_some(OllamaProcess {
+                pid,
+                name,
+                memory_bytes: rss_kb * 1024,
+            })
+        })
+        .collect();
+
+    Ok(processes)
+}
+
+/// Tue un processus Ollama par PID (refuse tout PID qui n'est pas un processus Ollama)
+pub fn kill_ollama_process(pid: u32) -> Result<(), String> {
+    if !list_ollama_processes()?.iter().any(|p| p.pid == pid) {
+        return Err(format!("Process {} is not a running Ollama process", pid));
+    }
+
+    #[cfg(windows)]
+    let result = Command::new("taskkill")
+        .args(["/F", "/PID", &pid.to_string()])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+
+    #[cfg(not(windows))]
+    let result = Command::new("kill")
+        .arg(pid.to_string())
+        .output();
+
+    match result {
+        Ok(output) if output.status.success() => Ok(()),
+        Ok(output) => Err(format!(
+            "Failed to kill process {}: {}",
+            pid,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(e) => Err(format!("Failed to kill process {}: {}", pid, e)),
+    }
+}
@@ -1,8 +1,10 @@
-use tauri::{State, Wry};
+use tauri::{AppHandle, Emitter, State, Wry};
 use std::sync::Mutex;
 use std::path::PathBuf;
 use serde_json;
-use crate::permission_manager::{PermissionManager, Permission, PermissionScope};
+use crate::permission_manager::{AuditLogFilter, PermissionManager, Permission, PermissionScope, PermissionStateSnapshot, RateLimitDecision, AUDIT_LOG_PATH_SETTING};
+use crate::settings_profile::{write_settings_section, ConfigSource};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
 
 fn parse_permission(permission: &str) -> Result<Permission, String> {
     match permission {
@@ -13,8 +15,50 @@ fn parse_permission(permission: &str) -> Result<Permission, String> {
         "RemoteAccess" => Ok(Permission::RemoteAccess),
         "MemoryAccess" => Ok(Permission::MemoryAccess),
         "RepoAnalyze" => Ok(Permission::RepoAnalyze),  // V2.1 Phase 3 : Nouvelle permission
-        _ => Err(format!("Unknown permission: {}", permission)),
+        // Nom inconnu : permission personnalisée (doit être déclarée pour être accordée)
+        _ => {
+            Permission::validate_custom_name(permission)?;
+            Ok(Permission::Custom(permission.to_string()))
+        }
+    }
+}
+
+/// Helper pour vérifier la permission (sans auto-grant)
+/// En mode parano, la permission doit être explicitement accordée via l'UI
+/// `command` identifie la commande Tauri appelante dans le journal d'audit
+pub(crate) async fn ensure_permission(
+    permission_state: &State<'_, Mutex<PermissionManager<Wry>>>,
+    permission: Permission,
+    context: &str,
+    command: &str,
+) -> Result<(), String> {
+    // 🔒 lock court
+    let consumed = {
+        let mut manager = permission_state.lock().map_err(|e| e.to_string())?;
+
+        // Mode sandbox : refuser quelle que soit la permission accordée
+        manager.ensure_not_sandboxed(&permission, context)?;
+
+        // Vérifier si la permission existe
+        if !manager.has_permission(&permission) {
+            return Err(format!(
+                "Permission {:?} is required for: {}. Please grant it via the Permission Manager UI.",
+                permission, context
+            ));
+        }
+
+        // En mode parano, consommer la permission (expire après usage)
+        manager
+            .check_and_consume_permission(&permission, context, command)?
+            .map(|log| (log, manager.async_handle()))
+    };
+
+    // 🔓 mutex libéré ici : persister la consommation dans le fichier d'audit
+    if let Some((log, handle)) = consumed {
+        handle.write_log(log).await?;
     }
+
+    Ok(())
 }
 
 /// Commande legacy : request_permission sans scope (utilise Global)
@@ -83,6 +127,9 @@ pub async fn request_permission_with_scope(
         "global" | _ => PermissionScope::Global,
     };
 
+    // Octroi identique déjà accordé juste avant : renvoyé tel quel une fois l'événement audité
+    let mut duplicate_of = None;
+
     // 🔒 lock court
     let result = {
         let mut manager = match state.lock() {
@@ -97,33 +144,88 @@ pub async fn request_permission_with_scope(
             }
         };
 
+        if !manager.is_known_permission(&permission_enum) {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "INVALID_PERMISSION",
+                "message": format!("Custom permission {} is not registered (register_custom_permission)", permission),
+                "permission": permission,
+                "context": context
+            }));
+        }
+
         // Nettoyer les permissions expirées avant d'ajouter une nouvelle
         manager.cleanup_expired_permissions();
 
-        // En mode parano, toujours demander explicitement (pas d'auto-grant)
-        // L'utilisateur doit accorder via l'UI (déjà fait avant l'appel de cette commande)
-        let granted = true;  // Si cette commande est appelée, c'est que l'utilisateur a confirmé via UI
+        // Second octroi identique juste après le premier (double-clic, rejeu) : renvoyer le premier résultat
+        if let Some(first) = manager.recent_duplicate_grant(&permission_enum, &context) {
+            let log = manager.record_event(
+                permission_enum.clone(),
+                &context,
+                format!(
+                    "Duplicate grant ignored (cooldown {} ms)",
+                    crate::permission_manager::GRANT_COOLDOWN_MS
+                ),
+                "request_permission_with_scope",
+            );
+            duplicate_of = Some(first);
+            Ok((log, manager.async_handle()))
+        } else if let RateLimitDecision::Limited(log) = manager.check_rate_limit(&permission_enum, &context) {
+            // Anti-flood : rejeter les demandes au-delà de la limite (loggé une seule fois par rafale)
+            Err((log, manager.async_handle()))
+        } else {
+            // En mode parano, toujours demander explicitement (pas d'auto-grant)
+            // L'utilisateur doit accorder via l'UI (déjà fait avant l'appel de cette commande)
+            let granted = true;  // Si cette commande est appelée, c'est que l'utilisateur a confirmé via UI
 
-        let log = manager.prepare_permission_with_scope(
-            permission_enum.clone(),
-            &context,
-            granted,
-            permission_scope,
-            project_id.clone(),  // Cloner ici aussi pour le log
-        );
+            let log = manager.prepare_permission_with_scope(
+                permission_enum.clone(),
+                &context,
+                granted,
+                permission_scope,
+                project_id.clone(),  // Cloner ici aussi pour le log
+            );
+            manager.remember_grant(&permission_enum, &context, &scope, project_id.clone());
 
-        (log, manager.async_handle())
+            Ok((log, manager.async_handle()))
+        }
     };
 
     // 🔓 mutex libéré ici
-    match result.1.write_log(result.0).await {
-        Ok(_) => Ok(serde_json::json!({
-            "success": true,
-            "permission": permission,
-            "scope": scope,
-            "project_id": project_id,
-            "context": context
-        })),
+    let (log, handle) = match result {
+        Ok(prepared) => prepared,
+        Err((rate_limit_log, handle)) => {
+            if let Some(log) = rate_limit_log {
+                let _ = handle.write_log(log).await;
+            }
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "RATE_LIMITED",
+                "message": format!("Too many {} permission requests, please retry later", permission),
+                "permission": permission,
+                "context": context
+            }));
+        }
+    };
+
+    match handle.write_log(log).await {
+        Ok(_) => match duplicate_of {
+            Some(first) => Ok(serde_json::json!({
+                "success": true,
+                "permission": permission,
+                "scope": first.scope,
+                "project_id": first.project_id,
+                "context": context,
+                "duplicate": true
+            })),
+            None => Ok(serde_json::json!({
+                "success": true,
+                "permission": permission,
+                "scope": scope,
+                "project_id": project_id,
+                "context": context
+            })),
+        },
         Err(err) => Ok(serde_json::json!({
             "error": true,
             "code": "LOG_WRITE_ERROR",
@@ -173,6 +275,84 @@ pub async fn has_permission(
     }))
 }
 
+/// Décrit une permission et liste les commandes qu'elle débloque (UI de permissions transparente)
+#[tauri::command]
+pub async fn describe_permission(permission: String) -> Result<serde_json::Value, String> {
+    let permission_enum = match parse_permission(&permission) {
+        Ok(perm) => perm,
+        Err(err) => {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "INVALID_PERMISSION",
+                "message": err,
+                "permission": permission
+            }));
+        }
+    };
+
+    Ok(serde_json::json!({
+        "success": true,
+        "permission": permission,
+        "description": permission_enum.description(),
+        "commands": permission_enum.gated_commands()
+    }))
+}
+
+/// Déclare une permission personnalisée (plugin) pour qu'elle puisse être demandée, accordée et auditée
+/// comme une permission intégrée. La déclaration vaut pour la session courante.
+#[tauri::command]
+pub async fn register_custom_permission(
+    app: AppHandle<Wry>,
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+    name: String,
+) -> Result<serde_json::Value, String> {
+    let (registered, prepared) = {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        let registered = manager.register_custom_permission(&name)?;
+        let prepared = registered.then(|| {
+            let log = manager.record_event(
+                Permission::Custom(name.clone()),
+                &name,
+                "Custom permission registered".into(),
+                "register_custom_permission",
+            );
+            (log, manager.async_handle())
+        });
+        (registered, prepared)
+    };
+
+    if let Some((log, handle)) = prepared {
+        handle.write_log(log).await?;
+        let _ = app.emit("custom-permission-registered", serde_json::json!({ "permission": name }));
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "permission": name,
+        "registered": registered  // false si déjà déclarée
+    }))
+}
+
+/// Liste les permissions intégrées et personnalisées, avec leur description et leur état
+#[tauri::command]
+pub async fn list_permissions(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let mut manager = state.lock().map_err(|e| e.to_string())?;
+    manager.cleanup_expired_permissions();
+    Ok(manager
+        .list_permissions()
+        .into_iter()
+        .map(|permission| serde_json::json!({
+            "permission": permission.name(),
+            "custom": matches!(permission, Permission::Custom(_)),
+            "description": permission.description(),
+            "commands": permission.gated_commands(),
+            "granted": manager.has_permission(&permission)
+        }))
+        .collect())
+}
+
 /// V2.1 Phase 3 : Vérifie une permission avec contexte (projectId pour isolation par projet)
 #[tauri::command]
 pub async fn has_permission_with_context(
@@ -222,6 +402,170 @@ pub async fn has_permission_with_context(
     }))
 }
 
+/// Révoque immédiatement toutes les permissions, globales et de projet (incident),
+/// et active le mode parano si demandé
+/// Émet "permissions-cleared" pour que toutes les fenêtres rafraîchissent leur état
+#[tauri::command]
+pub async fn revoke_all_permissions(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    enable_parano: Option<bool>,
+) -> Result<serde_json::Value, String> {
+    let enable_parano = enable_parano.unwrap_or(false);
+
+    // 🔒 lock court
+    let (revoked_count, parano_mode, pending_log) = {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        let (count, log) = manager.revoke_all_permissions(enable_parano);
+        (count, manager.is_parano_mode(), log.map(|log| (log, manager.async_handle())))
+    };
+
+    // 🔓 mutex libéré ici
+    let _ = app.emit("permissions-cleared", serde_json::json!({
+        "revoked_count": revoked_count,
+        "parano_mode": parano_mode
+    }));
+
+    if let Some((log, handle)) = pending_log {
+        if let Err(err) = handle.write_log(log).await {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "LOG_WRITE_ERROR",
+                "message": format!("Failed to write permission log: {}", err),
+                "revoked_count": revoked_count
+            }));
+        }
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "revoked_count": revoked_count,
+        "parano_mode": parano_mode
+    }))
+}
+
+/// Exporte l'état complet des permissions (accordées, parano, sandbox) pour reproduire un bug
+#[tauri::command]
+pub async fn export_permission_state(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+) -> Result<PermissionStateSnapshot, String> {
+    let manager = state.lock().map_err(|e| e.to_string())?;
+    Ok(manager.snapshot_state())
+}
+
+/// Remplace l'état des permissions par un instantané (export_permission_state).
+/// Contourne le consentement de l'utilisateur : l'import doit être confirmé dans un dialogue natif
+/// (hors de portée de la webview), il est audité et "permissions-imported" est émis pour que l'UI
+/// se rafraîchisse. Un import ne peut qu'activer les modes parano et sandbox, jamais les désactiver.
+#[tauri::command]
+pub async fn import_permission_state(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+    app: AppHandle<Wry>,
+    snapshot: PermissionStateSnapshot,
+) -> Result<serde_json::Value, String> {
+    let entry_count: usize = snapshot.granted_permissions.values().map(|entries| entries.len()).sum();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(format!(
+            "An import will replace the current permissions with {} permission entries exported on {}, without asking for consent again.\n\nOnly import a snapshot you created yourself.",
+            entry_count,
+            snapshot.exported_at.to_rfc3339()
+        ))
+        .title("Import permission state?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom("Import".into(), "Cancel".into()))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+    if !rx.await.unwrap_or(false) {
+        return Ok(serde_json::json!({
+            "error": true,
+            "code": "CONFIRMATION_DECLINED",
+            "message": "Permission state import was not confirmed by the user"
+        }));
+    }
+
+    // 🔒 lock court
+    let (imported_count, parano_mode, sandbox_mode, pending_log) = {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        let (count, log) = manager.restore_state(snapshot);
+        (
+            count,
+            manager.is_parano_mode(),
+            manager.is_sandbox_mode(),
+            log.map(|log| (log, manager.async_handle())),
+        )
+    };
+
+    let _ = app.emit("permissions-imported", serde_json::json!({
+        "imported_count": imported_count,
+        "parano_mode": parano_mode,
+        "sandbox_mode": sandbox_mode
+    }));
+
+    if let Some((log, handle)) = pending_log {
+        if let Err(err) = handle.write_log(log).await {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "LOG_WRITE_ERROR",
+                "message": format!("Failed to write permission log: {}", err),
+                "imported_count": imported_count
+            }));
+        }
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "imported_count": imported_count,
+        "parano_mode": parano_mode,
+        "sandbox_mode": sandbox_mode
+    }))
+}
+
+/// Révoque toutes les permissions scopées à un projet (ex: fermeture du projet)
+/// Les permissions globales ne sont pas touchées
+#[tauri::command]
+pub async fn revoke_project_permissions(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+    project_id: String,
+) -> Result<serde_json::Value, String> {
+    // 🔒 lock court
+    let (revoked_count, pending_log) = {
+        let mut manager = match state.lock() {
+            Ok(guard) => guard,
+            Err(e) => {
+                return Ok(serde_json::json!({
+                    "error": true,
+                    "code": "MUTEX_LOCK_ERROR",
+                    "message": format!("Failed to acquire lock: {}", e),
+                    "project_id": project_id
+                }));
+            }
+        };
+
+        let (count, log) = manager.revoke_project_permissions(&project_id);
+        (count, log.map(|log| (log, manager.async_handle())))
+    };
+
+    // 🔓 mutex libéré ici
+    if let Some((log, handle)) = pending_log {
+        if let Err(err) = handle.write_log(log).await {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "LOG_WRITE_ERROR",
+                "message": format!("Failed to write permission log: {}", err),
+                "project_id": project_id
+            }));
+        }
+    }
+
+    Ok(serde_json::json!({
+        "success": true,
+        "revoked_count": revoked_count,
+        "project_id": project_id
+    }))
+}
+
 #[tauri::command]
 pub async fn get_permission_logs(
     state: State<'_, Mutex<PermissionManager<Wry>>>,
@@ -239,7 +583,136 @@ pub async fn get_permission_logs(
 
     Ok(serde_json::json!({
         "success": true,
-        "logs": manager.get_audit_logs()
+        "logs": manager.get_audit_logs(),
+        "audit_log_retention": manager.audit_log_retention(),
+        "audit_file_warning": manager.audit_file_warning()
+    }))
+}
+
+/// Déplace le journal d'audit dans un dossier choisi (ex: dossier surveillé et sauvegardé)
+/// `path` = None pour revenir au dossier de logs de l'application. Le choix est persisté.
+#[tauri::command]
+pub async fn set_audit_log_path(
+    app: AppHandle<Wry>,
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+    path: Option<String>,
+) -> Result<serde_json::Value, String> {
+    let (log, handle, new_path) = {
+        let mut manager = state.lock().map_err(|e| e.to_string())?;
+        let previous = manager.log_path();
+        let new_path = manager.set_audit_log_path(path.as_ref().map(PathBuf::from))?;
+        let log = manager.record_event(
+            Permission::FileWrite,
+            &new_path.to_string_lossy(),
+            format!(
+                "Audit log moved from {} to {}",
+                previous.map_or_else(|| "memory".to_string(), |p| p.display().to_string()),
+                new_path.display()
+            ),
+            "set_audit_log_path",
+        );
+        (log, manager.async_handle(), new_path)
+    };
+
+    // Persister le dossier choisi (null = dossier par défaut)
+    let setting = match &path {
+        Some(_) => serde_json::Value::String(
+            new_path.parent().map(|dir| dir.to_string_lossy().into_owned()).unwrap_or_default(),
+        ),
+        None => serde_json::Value::Null,
+    };
+    write_settings_section(&app, AUDIT_LOG_PATH_SETTING, setting)?;
+
+    handle.write_log(log).await?;
+    Ok(serde_json::json!({
+        "success": true,
+        "path": new_path.to_string_lossy()
+    }))
+}
+
+/// Modifie le nombre de logs d'audit conservés en mémoire (le fichier reste complet)
+#[tauri::command]
+pub async fn set_audit_log_retention(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+    max_entries: usize,
+) -> Result<serde_json::Value, String> {
+    let mut manager = match state.lock() {
+        Ok(guard) => guard,
+        Err(e) => {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "MUTEX_LOCK_ERROR",
+                "message": format!("Failed to acquire lock: {}", e),
+            }));
+        }
+    };
+
+    manager.set_audit_log_retention(max_entries)?;
+    Ok(serde_json::json!({
+        "success": true,
+        "audit_log_retention": max_entries
+    }))
+}
+
+/// Lit l'historique d'audit depuis le fichier (toutes sessions confondues)
+#[tauri::command]
+pub async fn read_audit_log_file(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+) -> Result<serde_json::Value, String> {
+    let manager = match state.lock() {
+        Ok(guard) => guard,
+        Err(e) => {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "MUTEX_LOCK_ERROR",
+                "message": format!("Failed to acquire lock: {}", e),
+            }));
+        }
+    };
+
+    match manager.read_audit_log_file() {
+        Ok((logs, skipped_lines)) => Ok(serde_json::json!({
+            "success": true,
+            "logs": logs,
+            "skipped_lines": skipped_lines
+        })),
+        Err(err) => Ok(serde_json::json!({
+            "error": true,
+            "code": "READ_LOG_FILE_ERROR",
+            "message": err,
+        })),
+    }
+}
+
+/// Historique des permissions d'un projet (toutes sessions) et ses permissions actives
+#[tauri::command]
+pub async fn get_project_permission_history(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+    project_id: String,
+) -> Result<serde_json::Value, String> {
+    if project_id.trim().is_empty() {
+        return Err("Project id cannot be empty".to_string());
+    }
+
+    let manager = match state.lock() {
+        Ok(guard) => guard,
+        Err(e) => {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "MUTEX_LOCK_ERROR",
+                "message": format!("Failed to acquire lock: {}", e),
+            }));
+        }
+    };
+
+    let (logs, skipped_lines) = manager.project_permission_history(&project_id);
+    Ok(serde_json::json!({
+        "success": true,
+        "project_id": project_id,
+        "logs": logs,
+        "active_grants": manager.active_project_grants(&project_id),
+        "skipped_lines": skipped_lines,
+        "audit_file_warning": manager.audit_file_warning()
     }))
 }
 
@@ -271,11 +744,40 @@ pub async fn clear_permission_logs(
     }
 }
 
+/// Parse une date RFC 3339 d'un filtre d'export
+fn parse_filter_date(name: &str, value: Option<String>) -> Result<Option<chrono::DateTime<chrono::Utc>>, String> {
+    value
+        .map(|v| {
+            chrono::DateTime::parse_from_rfc3339(&v)
+                .map(|d| d.with_timezone(&chrono::Utc))
+                .map_err(|e| format!("Invalid {} date {}: {}", name, v, e))
+        })
+        .transpose()
+}
+
+/// Exporte le journal d'audit, éventuellement restreint à une période (`since`/`until`, RFC 3339)
+/// et à certaines permissions
 #[tauri::command]
 pub async fn export_permission_logs(
     state: State<'_, Mutex<PermissionManager<Wry>>>,
     path: String,
+    since: Option<String>,
+    until: Option<String>,
+    permissions: Option<Vec<String>>,
 ) -> Result<serde_json::Value, String> {
+    let filter = AuditLogFilter {
+        since: parse_filter_date("since", since)?,
+        until: parse_filter_date("until", until)?,
+        permissions: permissions
+            .map(|list| list.iter().map(|p| parse_permission(p)).collect::<Result<_, _>>())
+            .transpose()?,
+    };
+    if let (Some(since), Some(until)) = (filter.since, filter.until) {
+        if since > until {
+            return Err("Invalid export range: since is after until".to_string());
+        }
+    }
+
     let manager = match state.lock() {
         Ok(guard) => guard,
         Err(e) => {
@@ -287,11 +789,12 @@ pub async fn export_permission_logs(
         }
     };
 
-    match manager.export_audit_logs(PathBuf::from(path.clone())) {
-        Ok(_) => Ok(serde_json::json!({
+    match manager.export_audit_logs(PathBuf::from(path.clone()), &filter) {
+        Ok(exported) => Ok(serde_json::json!({
             "success": true,
             "message": "Permission logs exported successfully",
-            "path": path
+            "path": path,
+            "exported": exported
         })),
         Err(err) => Ok(serde_json::json!({
             "error": true,
@@ -341,7 +844,7 @@ pub async fn set_parano_mode(
         }
     };
 
-    manager.set_parano_mode(enabled);
+    manager.set_parano_mode(enabled, ConfigSource::Runtime);
     Ok(serde_json::json!({
         "success": true,
         "parano_mode": enabled,
@@ -352,3 +855,54 @@ pub async fn set_parano_mode(
         }
     }))
 }
+
+/// Récupère l'état du mode sandbox
+#[tauri::command]
+pub async fn get_sandbox_mode(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+) -> Result<serde_json::Value, String> {
+    let manager = match state.lock() {
+        Ok(guard) => guard,
+        Err(e) => {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "MUTEX_LOCK_ERROR",
+                "message": format!("Failed to acquire lock: {}", e),
+            }));
+        }
+    };
+
+    Ok(serde_json::json!({
+        "success": true,
+        "sandbox_mode": manager.is_sandbox_mode()
+    }))
+}
+
+/// Active/désactive le mode sandbox (toutes les commandes consommatrices refusées)
+#[tauri::command]
+pub async fn set_sandbox_mode(
+    state: State<'_, Mutex<PermissionManager<Wry>>>,
+    enabled: bool,
+) -> Result<serde_json::Value, String> {
+    let mut manager = match state.lock() {
+        Ok(guard) => guard,
+        Err(e) => {
+            return Ok(serde_json::json!({
+                "error": true,
+                "code": "MUTEX_LOCK_ERROR",
+                "message": format!("Failed to acquire lock: {}", e),
+            }));
+        }
+    };
+
+    manager.set_sandbox_mode(enabled, ConfigSource::Runtime);
+    Ok(serde_json::json!({
+        "success": true,
+        "sandbox_mode": enabled,
+        "message": if enabled {
+            "Sandbox mode enabled successfully"
+        } else {
+            "Sandbox mode disabled successfully"
+        }
+    }))
+}
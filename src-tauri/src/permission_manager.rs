@@ -1,10 +1,11 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use serde::{Serialize, Deserialize};
 use tauri::{AppHandle, Runtime, Emitter, Manager};
 use chrono::{Utc, DateTime, Duration};
 use std::fs::{File, OpenOptions};
 use std::io::Write;
+use crate::settings_profile::{read_settings_section, ConfigSource, ConfigSources};
 use std::sync::{Mutex, Arc};
 
 /// Permissions supportées
@@ -17,6 +18,102 @@ pub enum Permission {
     RemoteAccess,
     MemoryAccess,
     RepoAnalyze,  // V2.1 Phase 3 : Nouvelle permission pour analyse repository
+    /// Permission déclarée par un plugin (`register_custom_permission`), sérialisée par son nom seul
+    #[serde(untagged)]
+    Custom(String),
+}
+
+/// Longueur maximale du nom d'une permission personnalisée
+const MAX_CUSTOM_PERMISSION_NAME_LEN: usize = 64;
+
+impl Permission {
+    /// Permissions intégrées (hors permissions personnalisées)
+    pub const BUILTIN: [Permission; 7] = [
+        Permission::FileRead,
+        Permission::FileWrite,
+        Permission::CommandExecute,
+        Permission::NetworkAccess,
+        Permission::RemoteAccess,
+        Permission::MemoryAccess,
+        Permission::RepoAnalyze,
+    ];
+
+    /// Nom de la permission tel qu'utilisé par le frontend et le journal d'audit
+    pub fn name(&self) -> String {
+        match self {
+            Permission::Custom(name) => name.clone(),
+            builtin => format!("{:?}", builtin),
+        }
+    }
+
+    /// Valide le nom d'une permission personnalisée (lettres, chiffres, `_`, `-`, `.`, `:`)
+    pub fn validate_custom_name(name: &str) -> Result<(), String> {
+        if name.is_empty() || name.len() > MAX_CUSTOM_PERMISSION_NAME_LEN {
+            return Err(format!(
+                "Custom permission name must be 1 to {} characters long",
+                MAX_CUSTOM_PERMISSION_NAME_LEN
+            ));
+        }
+        if !name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':')) {
+            return Err(format!(
+                "Invalid custom permission name '{}': only letters, digits, '_', '-', '.' and ':' are allowed",
+                name
+            ));
+        }
+        if Permission::BUILTIN.iter().any(|builtin| builtin.name() == name) {
+            return Err(format!("'{}' is a built-in permission", name));
+        }
+        Ok(())
+    }
+
+    /// Description lisible de ce qu'autorise la permission
+    pub fn description(&self) -> &'static str {
+        match self {
+            Permission::FileRead => "Read files and list directories inside the current scope",
+            Permission::FileWrite => "Modify or delete files inside the current scope",
+            Permission::CommandExecute => "Run allowlisted shell commands",
+            Permission::NetworkAccess => "Access the network from the local backend",
+            Permission::RemoteAccess => "Fetch remote files (e.g. raw files from a git host)",
+            Permission::MemoryAccess => "Read and write the assistant's persistent memory",
+            Permission::RepoAnalyze => "Analyze the structure of the repository in scope",
+            Permission::Custom(_) => "Capability defined by a plugin",
+        }
+    }
+
+    /// Commandes Tauri protégées par la permission (`ensure_permission`)
+    /// A maintenir à jour lors de l'ajout d'une commande protégée
+    pub fn gated_commands(&self) -> &'static [&'static str] {
+        match self {
+            Permission::FileRead => &[
+                "read_file",
+                "read_file_from_offset",
+                "read_file_by_token_budget",
+                "read_file_confirmed",
+                "read_multiple_files",
+                "chunk_file",
+                "diff_files",
+                "load_context_manifest",
+                "scan_directory",
+                "scan_directory_stream",
+                "resolve_context_selection",
+            ],
+            Permission::FileWrite => &["delete_file"],
+            Permission::CommandExecute => &["run_command", "list_changed_files"],
+            Permission::NetworkAccess => &["ollama_chat_stream"],
+            Permission::RemoteAccess => &["read_remote_file"],
+            Permission::MemoryAccess => &[],
+            Permission::RepoAnalyze => &[
+                "analyze_repository",
+                "scope_extension_breakdown",
+                "find_duplicate_files",
+                "detect_project_language",
+                "analyze_dependencies",
+                "list_changed_files",
+            ],
+            // Vérifiées par le plugin qui les déclare, aucune commande intégrée
+            Permission::Custom(_) => &[],
+        }
+    }
 }
 
 /// Scope d'une permission (V2.1 Phase 3)
@@ -51,6 +148,69 @@ pub struct PermissionLog {
     pub scope: Option<String>,  // V2.1 Phase 3 : Scope de la permission
     #[serde(skip_serializing_if = "Option::is_none")]
     pub project_id: Option<String>,  // V2.1 Phase 3 : ProjectId si scope = Project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,  // Commande Tauri ayant consommé la permission (mode parano)
+}
+
+/// Filtre d'export du journal d'audit (None = pas de contrainte)
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub permissions: Option<HashSet<Permission>>,
+}
+
+impl AuditLogFilter {
+    fn matches(&self, log: &PermissionLog) -> bool {
+        self.since.map_or(true, |since| log.timestamp >= since)
+            && self.until.map_or(true, |until| log.timestamp <= until)
+            && self.permissions.as_ref().map_or(true, |set| set.contains(&log.permission))
+    }
+}
+
+/// Nombre maximum de demandes de permission accordées par minute (par type de permission)
+pub(crate) const RATE_LIMIT_MAX_REQUESTS: f64 = 10.0;
+/// Fenêtre de recharge complète du token bucket (secondes)
+pub(crate) const RATE_LIMIT_WINDOW_SECS: f64 = 60.0;
+
+/// Délai pendant lequel un second octroi identique (même permission et contexte) est ignoré
+/// (double-clic, rejeu d'événement côté UI)
+pub(crate) const GRANT_COOLDOWN_MS: i64 = 1000;
+
+/// Nombre d'entrées d'audit conservées en mémoire par défaut (le fichier garde l'historique complet)
+pub(crate) const DEFAULT_AUDIT_LOG_RETENTION: usize = 10_000;
+/// Bornes acceptées pour la rétention en mémoire
+const MIN_AUDIT_LOG_RETENTION: usize = 100;
+const MAX_AUDIT_LOG_RETENTION: usize = 1_000_000;
+
+/// Dernier octroi d'une permission pour un contexte donné (anti double-grant)
+#[derive(Debug, Clone)]
+pub struct RecentGrant {
+    pub granted_at: DateTime<Utc>,
+    pub scope: String,
+    pub project_id: Option<String>,
+}
+
+/// Instantané complet de l'état des permissions (reproduction de bugs par le support / la QA)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionStateSnapshot {
+    pub exported_at: DateTime<Utc>,
+    pub granted_permissions: HashMap<Permission, Vec<PermissionEntry>>,
+    pub parano_mode: bool,
+    pub sandbox_mode: bool,
+}
+
+/// Token bucket d'une permission (anti-flood des demandes)
+struct RateLimitBucket {
+    tokens: f64,
+    last_refill: DateTime<Utc>,
+    limited_logged: bool,  // Le rejet a déjà été loggé pour cette rafale
+}
+
+/// Résultat du contrôle de débit d'une demande de permission
+pub enum RateLimitDecision {
+    Allowed,
+    Limited(Option<PermissionLog>),  // Log présent uniquement pour le premier rejet d'une rafale
 }
 
 /// Gestionnaire central (V2.1 Phase 3 : Support permissions temporaires par scope)
@@ -59,43 +219,94 @@ pub struct PermissionManager<R: Runtime> {
     // Clé : Permission, Valeur : Liste des entrées actives (avec scope, expiration, etc.)
     granted_permissions: HashMap<Permission, Vec<PermissionEntry>>,
     audit_logs: Vec<PermissionLog>,
+    audit_log_retention: usize,  // Entrées récentes gardées en mémoire (les plus anciennes sont évincées)
     app_handle: AppHandle<R>,
-    log_file: Arc<Mutex<File>>,
+    log_file: Option<Arc<Mutex<File>>>,  // None = audit dégradé (mémoire uniquement)
+    log_path: Option<PathBuf>,
+    audit_file_warning: Option<String>,  // Raison de la dégradation de l'audit fichier
     parano_mode: bool, // Mode parano : permissions toujours explicites
+    sandbox_mode: bool, // Mode sandbox : toute commande consommatrice est refusée, même accordée
+    rate_limits: HashMap<Permission, RateLimitBucket>,
+    recent_grants: HashMap<(Permission, String), RecentGrant>,
+    custom_permissions: BTreeSet<String>,  // Permissions déclarées par les plugins (session courante)
+    config_sources: ConfigSources,  // Provenance des modes parano et sandbox
 }
 
 /// Handle async SAFE
 #[derive(Clone)]
 pub struct PermissionAsyncHandle<R: Runtime> {
     app_handle: AppHandle<R>,
-    log_file: Arc<Mutex<File>>,
+    log_file: Option<Arc<Mutex<File>>>,
+    log_path: Option<PathBuf>,  // Pour rouvrir le fichier s'il a été supprimé ou est devenu invalide
+}
+
+/// Nom du fichier d'audit (dans le dossier de logs ou le dossier choisi par l'administrateur)
+const AUDIT_LOG_FILE: &str = "permission_audit.log";
+/// Clé du fichier de réglages mémorisant le dossier d'audit choisi
+pub(crate) const AUDIT_LOG_PATH_SETTING: &str = "audit_log_path";
+
+/// Ouvre le fichier d'audit en ajout (en le recréant, ainsi que son dossier, s'il a disparu)
+fn open_audit_file(path: &Path) -> std::io::Result<File> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    OpenOptions::new().create(true).append(true).open(path)
 }
 
 impl<R: Runtime> PermissionManager<R> {
-    pub fn new(app_handle: &AppHandle<R>) -> Result<Self, String> {
-        let log_dir = app_handle
-            .path()
-            .app_log_dir()
-            .map_err(|e| e.to_string())?;
+    /// Ouvre (ou crée) le fichier d'audit dans le dossier choisi (`set_audit_log_path`),
+    /// à défaut dans le dossier de logs de l'application
+    fn open_log_file(app_handle: &AppHandle<R>) -> Result<(PathBuf, File), String> {
+        let custom_dir = read_settings_section(app_handle, AUDIT_LOG_PATH_SETTING)
+            .and_then(|value| value.as_str().map(PathBuf::from));
+        let log_dir = match custom_dir {
+            Some(dir) => dir,
+            None => app_handle
+                .path()
+                .app_log_dir()
+                .map_err(|e| e.to_string())?,
+        };
 
-        std::fs::create_dir_all(&log_dir)
-            .map_err(|e| e.to_string())?;
+        let log_path = log_dir.join(AUDIT_LOG_FILE);
 
-        let log_path = log_dir.join("permission_audit.log");
+        let file = open_audit_file(&log_path).map_err(|e| e.to_string())?;
 
-        let file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&log_path)
-            .map_err(|e| e.to_string())?;
+        Ok((log_path, file))
+    }
+
+    /// Si le dossier de logs est inaccessible (système verrouillé), l'audit reste en mémoire
+    /// uniquement et un événement "permission-audit-degraded" est émis au lieu d'empêcher le démarrage
+    pub fn new(app_handle: &AppHandle<R>) -> Self {
+        let (log_file, log_path, audit_file_warning) = match Self::open_log_file(app_handle) {
+            Ok((path, file)) => (Some(Arc::new(Mutex::new(file))), Some(path), None),
+            Err(err) => {
+                let warning = format!(
+                    "Permission audit log file unavailable, auditing is in-memory only: {}",
+                    err
+                );
+                eprintln!("⚠️ {}", warning);
+                let _ = app_handle.emit("permission-audit-degraded", serde_json::json!({
+                    "message": warning
+                }));
+                (None, None, Some(warning))
+            }
+        };
 
-        Ok(Self {
+        Self {
             granted_permissions: HashMap::new(),  // V2.1 Phase 3 : HashMap au lieu de HashSet
             audit_logs: Vec::new(),
+            audit_log_retention: DEFAULT_AUDIT_LOG_RETENTION,
             app_handle: app_handle.clone(),
-            log_file: Arc::new(Mutex::new(file)),
+            log_file,
+            log_path,
+            audit_file_warning,
             parano_mode: true, // Mode parano activé par défaut
-        })
+            sandbox_mode: false,
+            rate_limits: HashMap::new(),
+            recent_grants: HashMap::new(),
+            custom_permissions: BTreeSet::new(),
+            config_sources: ConfigSources::default(),
+        }
     }
 
     /// Handle async à utiliser hors Mutex
@@ -103,7 +314,100 @@ impl<R: Runtime> PermissionManager<R> {
         PermissionAsyncHandle {
             app_handle: self.app_handle.clone(),
             log_file: self.log_file.clone(),
+            log_path: self.log_path.clone(),
+        }
+    }
+
+    /// Retourne l'octroi identique (même permission et contexte) accordé il y a moins de
+    /// GRANT_COOLDOWN_MS, le cas échéant. Les octrois plus anciens sont oubliés.
+    pub fn recent_duplicate_grant(&mut self, permission: &Permission, context: &str) -> Option<RecentGrant> {
+        let now = Utc::now();
+        self.recent_grants
+            .retain(|_, grant| now - grant.granted_at < Duration::milliseconds(GRANT_COOLDOWN_MS));
+        self.recent_grants
+            .get(&(permission.clone(), context.to_string()))
+            .cloned()
+    }
+
+    /// Mémorise un octroi pour ignorer un doublon immédiat
+    pub fn remember_grant(&mut self, permission: &Permission, context: &str, scope: &str, project_id: Option<String>) {
+        self.recent_grants.insert(
+            (permission.clone(), context.to_string()),
+            RecentGrant {
+                granted_at: Utc::now(),
+                scope: scope.to_string(),
+                project_id,
+            },
+        );
+    }
+
+    /// Contrôle le débit des demandes (token bucket par type de permission)
+    /// Au-delà de la limite, la demande est rejetée et loggée une seule fois par rafale
+    pub fn check_rate_limit(&mut self, permission: &Permission, context: &str) -> RateLimitDecision {
+        let now = Utc::now();
+        let bucket = self.rate_limits
+            .entry(permission.clone())
+            .or_insert(RateLimitBucket {
+                tokens: RATE_LIMIT_MAX_REQUESTS,
+                last_refill: now,
+                limited_logged: false,
+            });
+
+        // Recharger proportionnellement au temps écoulé
+        let elapsed_secs = (now - bucket.last_refill).num_milliseconds().max(0) as f64 / 1000.0;
+        bucket.tokens = (bucket.tokens + elapsed_secs * RATE_LIMIT_MAX_REQUESTS / RATE_LIMIT_WINDOW_SECS)
+            .min(RATE_LIMIT_MAX_REQUESTS);
+        bucket.last_refill = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            bucket.limited_logged = false;
+            return RateLimitDecision::Allowed;
+        }
+
+        if bucket.limited_logged {
+            return RateLimitDecision::Limited(None);
         }
+        bucket.limited_logged = true;
+
+        let log = PermissionLog {
+            timestamp: now,
+            permission: permission.clone(),
+            granted: false,
+            context: context.to_string(),
+            user_action: format!(
+                "Permission request rate limited (max {} per minute)",
+                RATE_LIMIT_MAX_REQUESTS
+            ),
+            scope: None,
+            project_id: None,
+            command: None,
+        };
+        self.push_audit_log(log.clone());
+        RateLimitDecision::Limited(Some(log))
+    }
+
+    /// Ajoute un événement d'audit (ex: exécution d'une commande autorisée)
+    /// Retourne le log à persister hors Mutex via `PermissionAsyncHandle::write_log`
+    pub fn record_event(
+        &mut self,
+        permission: Permission,
+        context: &str,
+        user_action: String,
+        command: &str,
+    ) -> PermissionLog {
+        let log = PermissionLog {
+            timestamp: Utc::now(),
+            permission,
+            granted: true,
+            context: context.to_string(),
+            user_action,
+            scope: None,
+            project_id: None,
+            command: Some(command.to_string()),
+        };
+        self.push_audit_log(log.clone());
+        log
     }
 
     /// Prépare une permission avec scope (V2.1 Phase 3)
@@ -175,9 +479,10 @@ impl<R: Runtime> PermissionManager<R> {
             },
             scope: scope_str.clone(),
             project_id,
+            command: None,
         };
 
-        self.audit_logs.push(log.clone());
+        self.push_audit_log(log.clone());
         log
     }
 
@@ -283,6 +588,151 @@ impl<R: Runtime> PermissionManager<R> {
         false
     }
 
+    /// Retire toutes les entrées `Project { project_id }` d'un projet, toutes permissions confondues
+    /// Les permissions Global/Session/Temporary sont conservées
+    /// Retourne le nombre d'entrées retirées et un log récapitulatif (None si rien n'a été retiré)
+    pub fn revoke_project_permissions(&mut self, project_id: &str) -> (usize, Option<PermissionLog>) {
+        let mut removed_count = 0;
+        let mut revoked: Vec<Permission> = Vec::new();
+
+        for (permission, entries) in self.granted_permissions.iter_mut() {
+            let initial_len = entries.len();
+            entries.retain(|e| {
+                !matches!(&e.scope, PermissionScope::Project { project_id: entry_pid } if entry_pid == project_id)
+            });
+            if entries.len() < initial_len {
+                removed_count += initial_len - entries.len();
+                revoked.push(permission.clone());
+            }
+        }
+
+        self.granted_permissions.retain(|_, entries| !entries.is_empty());
+
+        if revoked.is_empty() {
+            return (0, None);
+        }
+
+        // Ordre stable pour le log récapitulatif
+        revoked.sort_by_key(|p| p.name());
+        let names: Vec<String> = revoked.iter().map(|p| p.name()).collect();
+
+        let log = PermissionLog {
+            timestamp: Utc::now(),
+            permission: revoked[0].clone(),
+            granted: false,
+            context: format!(
+                "Revoked {} project-scoped permission entries: {}",
+                removed_count,
+                names.join(", ")
+            ),
+            user_action: "Project permissions revoked".into(),
+            scope: Some(format!("project:{}", project_id)),
+            project_id: Some(project_id.to_string()),
+            command: None,
+        };
+        self.push_audit_log(log.clone());
+
+        (removed_count, Some(log))
+    }
+
+    /// Révoque toutes les permissions accordées, quel que soit leur scope (bouton panique)
+    /// Retourne le nombre d'entrées supprimées et le log récapitulatif (None si rien n'était accordé)
+    pub fn revoke_all_permissions(&mut self, enable_parano: bool) -> (usize, Option<PermissionLog>) {
+        let mut revoked: Vec<Permission> = self.granted_permissions.keys().cloned().collect();
+        let removed_count: usize = self.granted_permissions.values().map(|entries| entries.len()).sum();
+        self.granted_permissions.clear();
+        self.recent_grants.clear();
+        if enable_parano {
+            self.parano_mode = true;
+        }
+
+        if revoked.is_empty() {
+            return (0, None);
+        }
+
+        // Ordre stable pour le log récapitulatif
+        revoked.sort_by_key(|p| p.name());
+        let names: Vec<String> = revoked.iter().map(|p| p.name()).collect();
+
+        let log = PermissionLog {
+            timestamp: Utc::now(),
+            permission: revoked[0].clone(),
+            granted: false,
+            context: format!(
+                "Revoked all {} permission entries: {}",
+                removed_count,
+                names.join(", ")
+            ),
+            user_action: if enable_parano {
+                "All permissions revoked (parano mode enabled)".into()
+            } else {
+                "All permissions revoked".into()
+            },
+            scope: None,
+            project_id: None,
+            command: None,
+        };
+        self.push_audit_log(log.clone());
+
+        (removed_count, Some(log))
+    }
+
+    /// Instantané des permissions accordées et des modes (parano, sandbox), pour reproduire un bug
+    pub fn snapshot_state(&self) -> PermissionStateSnapshot {
+        PermissionStateSnapshot {
+            exported_at: Utc::now(),
+            granted_permissions: self.granted_permissions.clone(),
+            parano_mode: self.parano_mode,
+            sandbox_mode: self.sandbox_mode,
+        }
+    }
+
+    /// Remplace l'état en mémoire par un instantané, SANS passer par le flux de consentement.
+    /// Réservé à la reproduction de bugs : l'appelant doit avoir obtenu une confirmation explicite.
+    /// Les modes parano et sandbox de l'instantané ne peuvent que s'ajouter aux modes courants.
+    /// Retourne le nombre d'entrées importées et le log récapitulatif à persister.
+    pub fn restore_state(&mut self, snapshot: PermissionStateSnapshot) -> (usize, Option<PermissionLog>) {
+        let mut imported: Vec<Permission> = snapshot.granted_permissions.keys().cloned().collect();
+        let entry_count: usize = snapshot.granted_permissions.values().map(|entries| entries.len()).sum();
+        // Les permissions personnalisées de l'instantané sont déclarées pour rester vérifiables
+        for permission in &imported {
+            if let Permission::Custom(name) = permission {
+                self.custom_permissions.insert(name.clone());
+            }
+        }
+        self.granted_permissions = snapshot.granted_permissions;
+        // Un instantané peut renforcer la protection, jamais la lever
+        self.set_parano_mode(self.parano_mode || snapshot.parano_mode, ConfigSource::File);
+        self.set_sandbox_mode(self.sandbox_mode || snapshot.sandbox_mode, ConfigSource::File);
+        self.rate_limits.clear();
+        self.recent_grants.clear();
+
+        if imported.is_empty() {
+            return (0, None);
+        }
+
+        imported.sort_by_key(|p| p.name());
+        let names: Vec<String> = imported.iter().map(|p| p.name()).collect();
+        let log = PermissionLog {
+            timestamp: Utc::now(),
+            permission: imported[0].clone(),
+            granted: true,
+            context: format!(
+                "Imported {} permission entries from snapshot of {}: {}",
+                entry_count,
+                snapshot.exported_at.to_rfc3339(),
+                names.join(", ")
+            ),
+            user_action: "Permission state imported (consent flow bypassed)".into(),
+            scope: None,
+            project_id: None,
+            command: Some("import_permission_state".into()),
+        };
+        self.push_audit_log(log.clone());
+
+        (entry_count, Some(log))
+    }
+
     /// Nettoie les permissions expirées (appelé périodiquement) (V2.1 Phase 3)
     pub fn cleanup_expired_permissions(&mut self) -> usize {
         let now = Utc::now();
@@ -307,26 +757,209 @@ impl<R: Runtime> PermissionManager<R> {
         cleaned_count
     }
 
+    /// Chemin du fichier d'audit sur disque (None si audit dégradé)
+    pub fn log_path(&self) -> Option<PathBuf> {
+        self.log_path.clone()
+    }
+
+    /// Déplace le fichier d'audit dans `dir` (None = dossier de logs par défaut) : l'ancien contenu
+    /// est ajouté au nouveau fichier, les handles async existants écrivent ensuite au nouvel
+    /// emplacement et l'ancien fichier est supprimé. Retourne le nouveau chemin.
+    /// La persistance du choix est laissée à l'appelant.
+    pub fn set_audit_log_path(&mut self, dir: Option<PathBuf>) -> Result<PathBuf, String> {
+        let dir = match dir {
+            Some(dir) => {
+                let dir = dir
+                    .canonicalize()
+                    .map_err(|e| format!("Audit log directory {} is not accessible: {}", dir.display(), e))?;
+                if !dir.is_dir() {
+                    return Err(format!("{} is not a directory", dir.display()));
+                }
+                dir
+            }
+            None => self.app_handle.path().app_log_dir().map_err(|e| e.to_string())?,
+        };
+        let new_path = dir.join(AUDIT_LOG_FILE);
+        if self.log_path.as_ref() == Some(&new_path) {
+            return Ok(new_path);
+        }
+
+        // L'ouverture en écriture valide que le dossier est inscriptible
+        let mut new_file = open_audit_file(&new_path)
+            .map_err(|e| format!("Audit log directory {} is not writable: {}", dir.display(), e))?;
+
+        match &self.log_file {
+            Some(log_file) => {
+                let mut file = log_file.lock().map_err(|e| e.to_string())?;
+                file.sync_all().map_err(|e| e.to_string())?;
+                if let Some(old_path) = &self.log_path {
+                    let existing = std::fs::read(old_path).unwrap_or_default();
+                    new_file
+                        .write_all(&existing)
+                        .and_then(|_| new_file.sync_all())
+                        .map_err(|e| format!("Failed to move audit log to {}: {}", new_path.display(), e))?;
+                }
+                // Remplacer le handle partagé : l'ancien fichier est fermé
+                *file = new_file;
+                drop(file);
+                if let Some(old_path) = &self.log_path {
+                    if let Err(e) = std::fs::remove_file(old_path) {
+                        eprintln!("⚠️ Failed to remove previous audit log {}: {}", old_path.display(), e);
+                    }
+                }
+            }
+            // Audit dégradé : le nouvel emplacement rétablit l'audit fichier
+            None => {
+                self.log_file = Some(Arc::new(Mutex::new(new_file)));
+                self.audit_file_warning = None;
+            }
+        }
+
+        self.log_path = Some(new_path.clone());
+        Ok(new_path)
+    }
+
+    /// Raison de la dégradation de l'audit fichier, le cas échéant
+    pub fn audit_file_warning(&self) -> Option<String> {
+        self.audit_file_warning.clone()
+    }
+
+    /// Relit l'historique complet depuis le fichier d'audit (JSON délimité par lignes)
+    /// Couvre les sessions précédentes, contrairement à `audit_logs` (session courante)
+    /// Retourne les entrées et le nombre de lignes illisibles ignorées
+    pub fn read_audit_log_file(&self) -> Result<(Vec<PermissionLog>, usize), String> {
+        let path = self.log_path.as_ref().ok_or_else(|| {
+            self.audit_file_warning
+                .clone()
+                .unwrap_or_else(|| "Permission audit log file unavailable".to_string())
+        })?;
+
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read audit log {}: {}", path.display(), e))?;
+
+        let mut logs = Vec::new();
+        let mut skipped = 0;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<PermissionLog>(line) {
+                Ok(log) => logs.push(log),
+                Err(_) => skipped += 1,
+            }
+        }
+
+        Ok((logs, skipped))
+    }
+
+    /// Historique complet (fichier d'audit + session courante, sans doublons), trié par date
+    /// Sans fichier lisible, seuls les logs de la session sont retournés
+    /// Retourne aussi le nombre de lignes illisibles ignorées dans le fichier
+    fn merged_audit_logs(&self) -> (Vec<PermissionLog>, usize) {
+        let (file_logs, skipped) = self.read_audit_log_file().unwrap_or_default();
+
+        let mut seen = HashSet::new();
+        let mut logs: Vec<PermissionLog> = file_logs
+            .into_iter()
+            .chain(self.audit_logs.iter().cloned())
+            // Les logs de la session sont aussi dans le fichier : dédupliquer
+            .filter(|log| seen.insert(serde_json::to_string(log).unwrap_or_default()))
+            .collect();
+        logs.sort_by_key(|log| log.timestamp);
+        (logs, skipped)
+    }
+
+    /// Historique d'un projet (fichier d'audit + session courante, sans doublons), trié par date
+    /// Retourne aussi le nombre de lignes illisibles ignorées dans le fichier
+    pub fn project_permission_history(&self, project_id: &str) -> (Vec<PermissionLog>, usize) {
+        let project_scope = format!("project:{}", project_id);
+        let (logs, skipped) = self.merged_audit_logs();
+
+        let history = logs
+            .into_iter()
+            .filter(|log| {
+                log.project_id.as_deref() == Some(project_id)
+                    || log.scope.as_deref() == Some(project_scope.as_str())
+            })
+            .collect();
+        (history, skipped)
+    }
+
+    /// Permissions actuellement accordées avec le scope `Project { project_id }`
+    pub fn active_project_grants(&self, project_id: &str) -> Vec<PermissionEntry> {
+        let now = Utc::now();
+        let mut grants: Vec<PermissionEntry> = self
+            .granted_permissions
+            .values()
+            .flatten()
+            .filter(|entry| {
+                matches!(&entry.scope, PermissionScope::Project { project_id: pid } if pid == project_id)
+                    && entry.expires_at.map_or(true, |expires_at| expires_at > now)
+            })
+            .cloned()
+            .collect();
+        grants.sort_by_key(|entry| entry.granted_at);
+        grants
+    }
+
+    /// Ajoute un log en mémoire en évinçant les plus anciens au-delà de la rétention
+    fn push_audit_log(&mut self, log: PermissionLog) {
+        self.audit_logs.push(log);
+        self.enforce_audit_log_retention();
+    }
+
+    fn enforce_audit_log_retention(&mut self) {
+        if self.audit_logs.len() > self.audit_log_retention {
+            let excess = self.audit_logs.len() - self.audit_log_retention;
+            self.audit_logs.drain(..excess);
+        }
+    }
+
+    /// Logs récents de la session (au plus `audit_log_retention` entrées)
     pub fn get_audit_logs(&self) -> Vec<PermissionLog> {
         self.audit_logs.clone()
     }
 
+    pub fn audit_log_retention(&self) -> usize {
+        self.audit_log_retention
+    }
+
+    /// Modifie la rétention en mémoire et applique immédiatement l'éviction
+    pub fn set_audit_log_retention(&mut self, max_entries: usize) -> Result<(), String> {
+        if !(MIN_AUDIT_LOG_RETENTION..=MAX_AUDIT_LOG_RETENTION).contains(&max_entries) {
+            return Err(format!(
+                "Audit log retention must be between {} and {} entries",
+                MIN_AUDIT_LOG_RETENTION, MAX_AUDIT_LOG_RETENTION
+            ));
+        }
+        self.audit_log_retention = max_entries;
+        self.enforce_audit_log_retention();
+        Ok(())
+    }
+
     pub fn clear_audit_logs(&mut self) -> Result<(), String> {
-        let file = self.log_file.lock().map_err(|e| e.to_string())?;
-        file.set_len(0).map_err(|e| e.to_string())?;
+        if let Some(log_file) = &self.log_file {
+            let file = log_file.lock().map_err(|e| e.to_string())?;
+            file.set_len(0).map_err(|e| e.to_string())?;
+        }
         self.audit_logs.clear();
         Ok(())
     }
 
-    pub fn export_audit_logs(&self, path: PathBuf) -> Result<(), String> {
-        let json = serde_json::to_string_pretty(&self.audit_logs)
+    /// Exporte les logs correspondant au filtre, fichier d'audit compris (sessions précédentes)
+    /// Retourne le nombre d'entrées exportées
+    pub fn export_audit_logs(&self, path: PathBuf, filter: &AuditLogFilter) -> Result<usize, String> {
+        let (logs, _) = self.merged_audit_logs();
+        let logs: Vec<PermissionLog> = logs
+            .into_iter()
+            .filter(|log| filter.matches(log))
+            .collect();
+        let json = serde_json::to_string_pretty(&logs)
             .map_err(|e| e.to_string())?;
         std::fs::write(path, json).map_err(|e| e.to_string())?;
-        Ok(())
+        Ok(logs.len())
     }
 
     /// Active/désactive le mode parano
-    pub fn set_parano_mode(&mut self, enabled: bool) {
+    pub fn set_parano_mode(&mut self, enabled: bool, source: ConfigSource) {
+        self.config_sources.set_if_changed("parano_mode", &self.parano_mode, &enabled, source);
         self.parano_mode = enabled;
     }
 
@@ -335,13 +968,68 @@ impl<R: Runtime> PermissionManager<R> {
         self.parano_mode
     }
 
+    /// Active/désactive le mode sandbox (lecture seule, démos, sessions non fiables)
+    pub fn set_sandbox_mode(&mut self, enabled: bool, source: ConfigSource) {
+        self.config_sources.set_if_changed("sandbox_mode", &self.sandbox_mode, &enabled, source);
+        self.sandbox_mode = enabled;
+    }
+
+    /// Provenance des modes parano et sandbox (diagnostic)
+    pub fn config_sources(&self) -> ConfigSources {
+        self.config_sources.clone()
+    }
+
+    /// Déclare une permission personnalisée. Retourne false si elle était déjà déclarée
+    pub fn register_custom_permission(&mut self, name: &str) -> Result<bool, String> {
+        Permission::validate_custom_name(name)?;
+        Ok(self.custom_permissions.insert(name.to_string()))
+    }
+
+    /// Une permission intégrée est toujours connue, une permission personnalisée doit être déclarée
+    pub fn is_known_permission(&self, permission: &Permission) -> bool {
+        match permission {
+            Permission::Custom(name) => self.custom_permissions.contains(name),
+            _ => true,
+        }
+    }
+
+    /// Permissions intégrées puis personnalisées (ordre alphabétique)
+    pub fn list_permissions(&self) -> Vec<Permission> {
+        Permission::BUILTIN
+            .iter()
+            .cloned()
+            .chain(self.custom_permissions.iter().cloned().map(Permission::Custom))
+            .collect()
+    }
+
+    /// Vérifie si le mode sandbox est activé
+    pub fn is_sandbox_mode(&self) -> bool {
+        self.sandbox_mode
+    }
+
+    /// Refuse toute utilisation de permission en mode sandbox (ne peut pas être levé par un grant)
+    pub fn ensure_not_sandboxed(&self, permission: &Permission, context: &str) -> Result<(), String> {
+        if self.sandbox_mode {
+            return Err(format!(
+                "SANDBOXED: Permission {:?} cannot be used in sandbox mode ({})",
+                permission, context
+            ));
+        }
+        Ok(())
+    }
+
     /// V2.1 Phase 3 : Vérifie et consomme la permission avec contexte (scope + projectId)
+    /// Retourne le log de consommation (mode parano) à persister hors Mutex via `PermissionAsyncHandle::write_log`
     pub fn check_and_consume_permission_with_context(
         &mut self,
         permission: &Permission,
         context: &str,
         project_id: Option<&str>,
-    ) -> Result<(), String> {
+        command: &str,
+    ) -> Result<Option<PermissionLog>, String> {
+        // Mode sandbox : refuser avant toute vérification
+        self.ensure_not_sandboxed(permission, context)?;
+
         // Nettoyer les permissions expirées avant vérification
         self.cleanup_expired_permissions();
         
@@ -365,15 +1053,16 @@ impl<R: Runtime> PermissionManager<R> {
                 permission: permission.clone(),
                 granted: false,
                 context: context.to_string(),
-                user_action: "Permission consumed (parano mode)".into(),
+                user_action: format!("Permission consumed by {} (parano mode)", command),
                 scope: project_id.map(|pid| format!("project:{}", pid)),
                 project_id: project_id.map(String::from),
+                command: Some(command.to_string()),
             };
-            self.audit_logs.push(log.clone());
-            // Ne pas logger dans le fichier ici car c'est une consommation interne
+            self.push_audit_log(log.clone());
+            return Ok(Some(log));
         }
 
-        Ok(())
+        Ok(None)
     }
 
     /// En mode parano, vérifie et consomme la permission (expire après usage)
@@ -383,9 +1072,10 @@ impl<R: Runtime> PermissionManager<R> {
         &mut self,
         permission: &Permission,
         context: &str,
-    ) -> Result<(), String> {
+        command: &str,
+    ) -> Result<Option<PermissionLog>, String> {
         // V2.1 Phase 3 : Sans contexte projet (scope Global/Session)
-        self.check_and_consume_permission_with_context(permission, context, None)
+        self.check_and_consume_permission_with_context(permission, context, None, command)
     }
 }
 
@@ -394,11 +1084,48 @@ impl<R: Runtime> PermissionAsyncHandle<R> {
         let json = serde_json::to_string(&log)
             .map_err(|e| e.to_string())?;
 
-        let mut file = self.log_file.lock()
-            .map_err(|e| e.to_string())?;
+        // Audit dégradé : pas de fichier, le log reste en mémoire et est émis
+        if let Some(log_file) = &self.log_file {
+            let mut file = log_file.lock()
+                .map_err(|e| e.to_string())?;
 
-        writeln!(file, "{}", json)
-            .map_err(|e| e.to_string())?;
+            // Un fichier supprimé resterait inscriptible (inode orphelin) : le détecter par son chemin
+            let deleted = self.log_path.as_ref().is_some_and(|path| !path.exists());
+            let first_attempt = if deleted {
+                Err("audit log file was deleted".to_string())
+            } else {
+                writeln!(file, "{}", json).map_err(|e| e.to_string())
+            };
+
+            if let Err(first_err) = first_attempt {
+                // Rouvrir (recréer) le fichier et réessayer une fois ; le nouveau handle est
+                // partagé par tous les clones de l'Arc
+                let retry = match &self.log_path {
+                    Some(path) => open_audit_file(path).and_then(|mut reopened| {
+                        writeln!(reopened, "{}", json)?;
+                        Ok(reopened)
+                    }),
+                    None => Err(std::io::Error::other("audit log path unknown")),
+                };
+
+                match retry {
+                    Ok(reopened) => *file = reopened,
+                    Err(retry_err) => {
+                        let message = format!(
+                            "Failed to write permission audit log ({}), reopen failed: {}",
+                            first_err, retry_err
+                        );
+                        eprintln!("⚠️ {}", message);
+                        let _ = self.app_handle.emit("audit-log-write-failed", serde_json::json!({
+                            "message": message,
+                            "path": self.log_path.as_ref().map(|p| p.to_string_lossy().into_owned()),
+                            "log": log
+                        }));
+                        return Err(message);
+                    }
+                }
+            }
+        }
 
         self.app_handle
             .emit("permission-log", log)
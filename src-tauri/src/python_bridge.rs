@@ -1,13 +1,69 @@
-use std::collections::HashMap;
-use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Runtime};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tauri_plugin_shell::ShellExt;
-use tauri_plugin_shell::process::CommandEvent;
-use tokio::sync::{mpsc, Mutex, oneshot};
+use tauri_plugin_shell::process::{CommandChild, CommandEvent};
+use tokio::sync::{mpsc, Mutex, Notify, oneshot};
 use tokio::time::{timeout, Duration};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+/// Identifiant du message de handshake émis par le worker une fois initialisé
+const WORKER_READY_ID: &str = "WORKER_READY";
+/// Variable d'environnement indiquant au worker le dossier où écrire `worker.log`
+const WORKER_LOG_DIR_ENV: &str = "HORIZON_LOG_DIR";
+/// Nom du log écrit par le worker (worker/core/logger.py)
+const WORKER_LOG_FILE: &str = "worker.log";
+/// Version du protocole IPC attendue par le bridge (annoncée par le worker dans le handshake)
+pub(crate) const IPC_PROTOCOL_VERSION: u64 = 1;
+/// Attente maximale de la disponibilité du worker avant d'envoyer une commande
+pub(crate) const READY_TIMEOUT_SECS: u64 = 20;
+/// Événements de fin de stream toujours relayés (sinon l'UI attendrait indéfiniment)
+const TERMINAL_STREAM_EVENTS: [&str; 3] = ["done", "error", "cancelled"];
+/// Identifiants réservés aux messages "push" du worker (ne peuvent pas servir d'id de requête)
+const RESERVED_REQUEST_IDS: [&str; 2] = [WORKER_READY_ID, "SYSTEM_STATS"];
+/// Longueur maximale d'un id de requête fourni par le frontend
+const MAX_REQUEST_ID_LEN: usize = 128;
+/// Nombre maximal de commandes dans une trame batch
+const MAX_BATCH_SIZE: usize = 100;
+/// Délai maximal d'attente d'une réponse du worker
+pub(crate) const RESPONSE_TIMEOUT_SECS: u64 = 30;
+/// Délai par défaut de `set_model` (le chargement d'un modèle en mémoire est lent)
+pub(crate) const MODEL_LOAD_TIMEOUT_SECS: u64 = 300;
+/// Délai maximal configurable pour une commande
+const MAX_COMMAND_TIMEOUT_SECS: u64 = 3600;
+/// Nombre maximal par défaut de requêtes en attente de réponse (au-delà : "worker-backpressure")
+pub(crate) const DEFAULT_MAX_PENDING: usize = 64;
+/// Plafond configurable du nombre de requêtes en attente
+const MAX_PENDING_LIMIT: usize = 10_000;
+/// Nombre maximal d'événements conservés pour un stream en pause (les plus anciens sont abandonnés)
+const MAX_PAUSED_STREAM_EVENTS: usize = 1000;
+/// Nombre maximal d'événements conservés par stream pour le rattrapage d'une fenêtre
+const MAX_REPLAY_EVENTS: usize = 2000;
+/// Nombre maximal de streams conservés pour le rattrapage (les moins récents sont oubliés)
+const MAX_REPLAY_STREAMS: usize = 32;
+/// Durée de conservation d'un stream terminé (une fenêtre rechargée peut encore le rattraper)
+const REPLAY_RETENTION_SECS: u64 = 60;
+/// Sous-intervalles linéaires par puissance de 2 de l'histogramme de latence (précision ~3%)
+const LATENCY_SUB_BUCKETS: u64 = 64;
+/// Latence maximale enregistrée (µs, ~19 h) : au-delà, la valeur est plafonnée
+const LATENCY_MAX_TRACKABLE_US: u64 = 1 << 36;
+/// Nombre maximal de commandes suivies (les noms viennent du frontend)
+const MAX_LATENCY_COMMANDS: usize = 128;
+/// Commande du worker qui télécharge un modèle (soumise au budget réseau quotidien)
+const PULL_COMMAND: &str = "pull";
+/// Commande du worker qui interrompt le téléchargement d'un modèle
+const CANCEL_PULL_COMMAND: &str = "cancel_pull";
+/// Délai avant de relancer un worker arrêté inopinément
+const WORKER_RESTART_DELAY_MS: u64 = 1000;
+/// Nombre maximal de redémarrages successifs (au-delà, le worker est jugé instable et reste arrêté)
+const MAX_WORKER_RESTARTS: u32 = 3;
+/// Durée de fonctionnement après laquelle un worker est jugé stable (remise à zéro des redémarrages)
+const WORKER_STABLE_SECS: u64 = 60;
+/// Attente maximale du worker relancé avant de renvoyer une requête perdue (retry_on_crash)
+const CRASH_RETRY_WINDOW_SECS: u64 = READY_TIMEOUT_SECS;
+
 #[derive(Serialize)]
 struct PyRequest {
     id: String,
@@ -15,6 +71,13 @@ struct PyRequest {
     payload: Value,
 }
 
+/// Trame batch : plusieurs requêtes résolues ensemble par une seule réponse (tableau de PyResponse)
+#[derive(Serialize)]
+struct PyBatchRequest {
+    id: String,
+    batch: Vec<PyRequest>,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 struct PyResponse {
     id: String,
@@ -23,122 +86,711 @@ struct PyResponse {
     error: Option<Value>,
 }
 
+/// Réponse d'une commande worker, avec l'id de requête pour corréler
+/// les événements "python-stream" / "worker-timeout" à l'appel d'origine
+#[derive(Serialize, Debug, Clone)]
+pub struct DispatchedResponse {
+    pub request_id: String,
+    pub data: Value,
+}
+
+/// Échec de l'attente d'une réponse du worker
+enum ResponseError {
+    // Le worker s'est arrêté : la requête est perdue (éligible à un renvoi)
+    WorkerLost,
+    // Timeout ou autre échec (pas de renvoi)
+    Failed(String),
+}
+
+impl From<ResponseError> for String {
+    fn from(error: ResponseError) -> Self {
+        match error {
+            ResponseError::WorkerLost => "Worker crashed or request lost".to_string(),
+            ResponseError::Failed(message) => message,
+        }
+    }
+}
+
+/// Résultat d'un élément de batch (les erreurs sont par élément, pas globales)
+#[derive(Serialize, Debug, Clone)]
+pub struct BatchItemResult {
+    pub id: String,
+    pub cmd: String,
+    pub status: String,
+    pub data: Option<Value>,
+    pub error: Option<Value>,
+}
+
+/// Événements d'un stream en pause, relayés à la reprise
+#[derive(Default)]
+struct PausedStream {
+    buffered: VecDeque<Value>,
+    dropped: usize,
+}
+
+/// Bilan de la reprise d'un stream
+#[derive(Debug, Clone, Serialize)]
+pub struct ResumedStream {
+    pub request_id: String,
+    pub flushed: usize,  // Événements mis en attente puis relayés
+    pub dropped: usize,  // Événements abandonnés (plafond atteint pendant la pause)
+}
+
+/// Événements récents d'un stream, rejoués à une fenêtre ouverte en cours de génération
+struct RecentStream {
+    events: VecDeque<Value>,
+    truncated: usize,  // Premiers événements oubliés (plafond MAX_REPLAY_EVENTS)
+    last_event_at: std::time::Instant,
+    finished: bool,
+}
+
+/// Bilan du rattrapage d'un stream par une fenêtre
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayedStream {
+    pub request_id: String,
+    pub replayed: usize,
+    pub truncated: usize,  // Événements du début du stream qui ne sont plus disponibles
+    pub finished: bool,
+}
+
+/// Ligne de log du worker relayée au frontend (événement "python-log")
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerLogLine {
+    pub level: String,
+    pub message: String,
+    pub timestamp: String,
+}
+
+/// Extrait le niveau d'une ligne de log ("INFO ...", "[WARN] ...", "ERROR: ...")
+/// Sans niveau reconnu, la ligne entière est conservée en INFO
+fn parse_log_line(line: &str) -> WorkerLogLine {
+    let (token, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let normalized = token
+        .trim_matches(|c| c == '[' || c == ']' || c == ':')
+        .to_ascii_uppercase();
+    let level = match normalized.as_str() {
+        "DEBUG" | "TRACE" => Some("DEBUG"),
+        "INFO" => Some("INFO"),
+        "WARN" | "WARNING" => Some("WARN"),
+        "ERROR" | "CRITICAL" | "FATAL" => Some("ERROR"),
+        _ => None,
+    };
+
+    let (level, message) = match level {
+        Some(level) if !rest.trim().is_empty() => (level, rest.trim_start_matches([':', ' ']).trim()),
+        _ => ("INFO", line),
+    };
+    WorkerLogLine {
+        level: level.to_string(),
+        message: message.to_string(),
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Cycle de vie du worker (suivi de fiabilité)
+#[derive(Default)]
+struct WorkerLifecycle {
+    started_at: Option<std::time::Instant>,  // None tant qu'aucun worker ne tourne
+    crash_count: u32,
+    restart_count: u32,  // Workers relancés automatiquement après un arrêt
+    last_crash_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// État du worker pour la surveillance et les rapports d'instabilité
+#[derive(Debug, Clone, Serialize)]
+pub struct WorkerStatus {
+    pub uptime_secs: Option<u64>,
+    pub crash_count: u32,
+    pub restart_count: u32,
+    pub last_crash_at: Option<String>,
+    pub ready: bool,
+}
+
+type PausedStreams = Arc<std::sync::Mutex<HashMap<String, PausedStream>>>;
+type RecentStreams = Arc<std::sync::Mutex<HashMap<String, RecentStream>>>;
+
+/// Mémorise un événement de stream pour `replay_stream` et oublie les streams expirés
+fn record_stream_event(recent: &RecentStreams, request_id: &str, event_name: &str, event: &Value) {
+    let Ok(mut streams) = recent.lock() else {
+        return;
+    };
+    let now = std::time::Instant::now();
+    streams.retain(|_, stream| {
+        !stream.finished || now.duration_since(stream.last_event_at).as_secs() < REPLAY_RETENTION_SECS
+    });
+    if !streams.contains_key(request_id) && streams.len() >= MAX_REPLAY_STREAMS {
+        let oldest = streams
+            .iter()
+            .min_by_key(|(_, stream)| stream.last_event_at)
+            .map(|(id, _)| id.clone());
+        if let Some(oldest) = oldest {
+            streams.remove(&oldest);
+        }
+    }
+
+    let stream = streams.entry(request_id.to_string()).or_insert_with(|| RecentStream {
+        events: VecDeque::new(),
+        truncated: 0,
+        last_event_at: now,
+        finished: false,
+    });
+    if stream.events.len() >= MAX_REPLAY_EVENTS {
+        stream.events.pop_front();
+        stream.truncated += 1;
+    }
+    stream.events.push_back(event.clone());
+    stream.last_event_at = now;
+    stream.finished |= TERMINAL_STREAM_EVENTS.contains(&event_name);
+}
+
+/// Comptabilise dans le budget réseau les octets d'un événement de progression de `pull`.
+/// Le worker rapporte les octets cumulés par couche : seul l'écart depuis le dernier événement
+/// de la même couche est compté. `pulled` est vidé pour un stream terminé.
+/// Retourne une erreur si le budget est dépassé après comptage (le téléchargement doit être interrompu).
+fn meter_pull_progress<R: Runtime>(
+    app: &AppHandle<R>,
+    pulled: &mut HashMap<(String, String), u64>,
+    event_name: &str,
+    event: &Value,
+) -> Result<(), String> {
+    let Some(request_id) = event.get("id").and_then(|id| id.as_str()) else {
+        return Ok(());
+    };
+    if TERMINAL_STREAM_EVENTS.contains(&event_name) {
+        pulled.retain(|(id, _), _| id != request_id);
+        return Ok(());
+    }
+    let digest = event.get("digest").and_then(|d| d.as_str());
+    let completed = event.get("completed_bytes").and_then(|b| b.as_u64());
+    let (Some(digest), Some(completed)) = (digest, completed) else {
+        return Ok(());
+    };
+
+    let previous = pulled
+        .entry((request_id.to_string(), digest.to_string()))
+        .or_insert(0);
+    if completed > *previous {
+        crate::egress_budget::record_egress(app, completed - *previous);
+        *previous = completed;
+        crate::egress_budget::check_egress(app, PULL_COMMAND)?;
+    }
+    Ok(())
+}
+
+/// Histogramme de latence façon HdrHistogram : intervalles linéaires à l'intérieur de chaque
+/// puissance de 2, soit une erreur relative bornée quelle que soit la latence, en mémoire fixe
+struct LatencyHistogram {
+    counts: Vec<u64>,
+    total: u64,
+    sum_us: u64,
+    min_us: u64,
+    max_us: u64,
+}
+
+/// Distribution de la latence aller-retour d'une commande (millisecondes)
+#[derive(Debug, Clone, Serialize)]
+pub struct LatencyPercentiles {
+    pub cmd: String,
+    pub count: u64,
+    pub min_ms: Option<f64>,
+    pub mean_ms: Option<f64>,
+    pub p50_ms: Option<f64>,
+    pub p90_ms: Option<f64>,
+    pub p99_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        let magnitudes = 64 - LATENCY_MAX_TRACKABLE_US.leading_zeros() as u64;
+        Self {
+            counts: vec![0; (LATENCY_SUB_BUCKETS + magnitudes * LATENCY_SUB_BUCKETS / 2) as usize],
+            total: 0,
+            sum_us: 0,
+            min_us: u64::MAX,
+            max_us: 0,
+        }
+    }
+
+    /// Index de l'intervalle contenant `value` : valeur exacte sous LATENCY_SUB_BUCKETS,
+    /// puis LATENCY_SUB_BUCKETS / 2 intervalles par puissance de 2
+    fn bucket_index(value: u64) -> usize {
+        if value < LATENCY_SUB_BUCKETS {
+            return value as usize;
+        }
+        let half = LATENCY_SUB_BUCKETS / 2;
+        let magnitude = 63 - value.leading_zeros() as u64;
+        let shift = magnitude + 1 - LATENCY_SUB_BUCKETS.trailing_zeros() as u64;
+        let sub_bucket = (value >> shift) - half;
+        (LATENCY_SUB_BUCKETS + (shift - 1) * half + sub_bucket) as usize
+    }
+
+    /// Plus grande valeur de l'intervalle `index` (inverse de bucket_index)
+    fn bucket_upper_bound(index: usize) -> u64 {
+        let index = index as u64;
+        if index < LATENCY_SUB_BUCKETS {
+            return index;
+        }
+        let half = LATENCY_SUB_BUCKETS / 2;
+        let shift = (index - LATENCY_SUB_BUCKETS) / half + 1;
+        let sub_bucket = (index - LATENCY_SUB_BUCKETS) % half + half;
+        ((sub_bucket + 1) << shift) - 1
+    }
+
+    fn record(&mut self, latency: Duration) {
+        let value = (latency.as_micros() as u64).min(LATENCY_MAX_TRACKABLE_US);
+        let index = Self::bucket_index(value).min(self.counts.len() - 1);
+        self.counts[index] += 1;
+        self.total += 1;
+        self.sum_us = self.sum_us.saturating_add(value);
+        self.min_us = self.min_us.min(value);
+        self.max_us = self.max_us.max(value);
+    }
+
+    /// Valeur sous laquelle se trouvent `quantile` des mesures (borne haute de l'intervalle)
+    fn value_at_quantile(&self, quantile: f64) -> Option<u64> {
+        if self.total == 0 {
+            return None;
+        }
+        let target = ((quantile * self.total as f64).ceil() as u64).clamp(1, self.total);
+        let mut seen = 0;
+        self.counts.iter().enumerate().find_map(|(index, count)| {
+            seen += count;
+            (seen >= target).then(|| Self::bucket_upper_bound(index).clamp(self.min_us, self.max_us))
+        })
+    }
+
+    fn percentiles(&self, cmd: &str) -> LatencyPercentiles {
+        let to_ms = |us: u64| us as f64 / 1000.0;
+        let recorded = self.total > 0;
+        LatencyPercentiles {
+            cmd: cmd.to_string(),
+            count: self.total,
+            min_ms: recorded.then(|| to_ms(self.min_us)),
+            mean_ms: recorded.then(|| self.sum_us as f64 / self.total as f64 / 1000.0),
+            p50_ms: self.value_at_quantile(0.50).map(to_ms),
+            p90_ms: self.value_at_quantile(0.90).map(to_ms),
+            p99_ms: self.value_at_quantile(0.99).map(to_ms),
+            max_ms: recorded.then(|| to_ms(self.max_us)),
+        }
+    }
+}
+
 pub struct PythonBridge<R: Runtime> {
     tx_command: mpsc::Sender<String>,
     pending: Arc<Mutex<HashMap<String, oneshot::Sender<PyResponse>>>>,
-    #[allow(dead_code)]
     app_handle: AppHandle<R>,
     // ✅ Channel pour signaler l'arrêt du worker
     shutdown_tx: mpsc::Sender<()>,
+    // Handshake : passe à true quand le worker a fini ses imports et accepte des commandes
+    ready: Arc<AtomicBool>,
+    ready_notify: Arc<Notify>,
+    // Filtre des événements "python-stream" relayés (None = tout relayer)
+    stream_filter: Arc<RwLock<Option<HashSet<String>>>>,
+    // Streams en pause (request_id -> événements en attente)
+    paused_streams: PausedStreams,
+    // Événements récents par requête (rattrapage d'une fenêtre ouverte en cours de stream)
+    recent_streams: RecentStreams,
+    // Délais spécifiques par commande (les autres utilisent RESPONSE_TIMEOUT_SECS)
+    command_timeouts: RwLock<HashMap<String, u64>>,
+    // Nombre maximal de requêtes en attente de réponse
+    max_pending: AtomicUsize,
+    // Latence aller-retour par commande (envoi -> réponse du worker)
+    latencies: std::sync::Mutex<HashMap<String, LatencyHistogram>>,
+    // Démarrage, arrêts et redémarrages du worker
+    lifecycle: Arc<std::sync::Mutex<WorkerLifecycle>>,
+    // Données du dernier handshake (pid, version, protocol_version)
+    worker_info: Arc<RwLock<Option<Value>>>,
+    // Raison pour laquelle le worker n'a pas pu être lancé (backend manquant/corrompu)
+    unavailable_reason: Option<String>,
 }
 
-impl<R: Runtime> PythonBridge<R> {
-    pub fn new(app: &AppHandle<R>) -> Self {
-        let (tx_command, mut rx_command) = mpsc::channel::<String>(100);
-        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
-        let pending: Arc<Mutex<HashMap<String, oneshot::Sender<PyResponse>>>> =
-            Arc::new(Mutex::new(HashMap::new()));
+/// Empreinte SHA-256 du sidecar backend calculée au build (None si le binaire était absent)
+const EXPECTED_BACKEND_SHA256: Option<&str> = option_env!("HORIZON_BACKEND_SHA256");
 
-        let pending_reader = pending.clone();
-        let app_emit = app.clone();
-
-        // ==========================================================
-        // DÉMARRAGE DU WORKER (SIDECAR OU DEV MODE)
-        // ==========================================================
-        // En mode DEV: utilise python ../worker/main.py
-        // En mode BUILD: utilise le sidecar backend.exe compilé
-        
-        #[cfg(debug_assertions)]
-        let (mut rx_sidecar, mut child) = {
-            #[cfg(windows)]
-            let primary_cmd = "pythonw";
-            #[cfg(not(windows))]
-            let primary_cmd = "python";
-
-            let spawn_primary = app
-                .shell()
-                .command(primary_cmd)
-                .args(["../worker/main.py"])
-                .spawn();
+/// Résultat de la vérification d'intégrité du sidecar backend
+#[derive(Debug, Clone, Serialize)]
+pub struct BackendIntegrity {
+    pub path: String,
+    pub expected_sha256: Option<String>,
+    pub actual_sha256: Option<String>,
+    pub verified: bool,
+    pub error: Option<String>,
+}
 
-            #[cfg(windows)]
-            let spawn_primary = spawn_primary.or_else(|_| {
-                app.shell()
-                    .command("python")
-                    .args(["../worker/main.py"])
-                    .spawn()
-            });
+/// Chemin du sidecar installé (à côté de l'exécutable de l'app, sans le suffixe de target)
+fn backend_sidecar_path() -> Result<std::path::PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate app executable: {}", e))?;
+    let dir = exe.parent().ok_or("Failed to locate app directory")?;
+    Ok(dir.join(format!("backend{}", std::env::consts::EXE_SUFFIX)))
+}
 
-            spawn_primary.expect("Failed to spawn python worker. Check if python is in PATH.")
-        };
-        
-        #[cfg(not(debug_assertions))]
-        let (mut rx_sidecar, mut child) = app
+/// Compare l'empreinte du sidecar installé à celle embarquée au build
+pub fn verify_backend_integrity() -> BackendIntegrity {
+    let expected_sha256 = EXPECTED_BACKEND_SHA256.map(String::from);
+    let path = match backend_sidecar_path() {
+        Ok(path) => path,
+        Err(error) => {
+            return BackendIntegrity {
+                path: String::new(),
+                expected_sha256,
+                actual_sha256: None,
+                verified: false,
+                error: Some(error),
+            };
+        }
+    };
+
+    let (actual_sha256, error) = match crate::ollama_installer::file_sha256(&path) {
+        Ok(hash) => (Some(hash), None),
+        Err(error) => (None, Some(error)),
+    };
+    let error = error.or_else(|| {
+        expected_sha256
+            .is_none()
+            .then(|| "No expected backend hash was embedded at build time".to_string())
+    });
+    let verified = error.is_none() && actual_sha256 == expected_sha256;
+
+    BackendIntegrity {
+        path: path.to_string_lossy().into_owned(),
+        error: error.or_else(|| (!verified).then(|| "Backend hash mismatch".to_string())),
+        expected_sha256,
+        actual_sha256,
+        verified,
+    }
+}
+
+/// Indication affichée quand le worker ne peut pas être lancé
+#[cfg(debug_assertions)]
+const SPAWN_HINT: &str = "Check if python is in PATH.";
+#[cfg(not(debug_assertions))]
+const SPAWN_HINT: &str = "The bundled backend is missing or damaged. Please reinstall Horizon AI.";
+
+// ==========================================================
+// DÉMARRAGE DU WORKER (SIDECAR OU DEV MODE)
+// ==========================================================
+// En mode DEV: utilise python ../worker/main.py
+// En mode BUILD: utilise le sidecar backend.exe compilé
+// Dans les deux cas, les arguments/variables de la section `worker` des réglages sont ajoutés
+// (relus à chaque lancement, y compris lors d'un redémarrage automatique)
+fn spawn_worker<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<(tauri::async_runtime::Receiver<CommandEvent>, CommandChild), String> {
+    let mut worker_settings = crate::settings_profile::load_worker_settings(app);
+    // Le worker écrit son log dans le dossier de logs de l'app (chemin confirmé par le handshake)
+    if let Ok(log_dir) = app.path().app_log_dir() {
+        worker_settings
+            .env
+            .entry(WORKER_LOG_DIR_ENV.to_string())
+            .or_insert_with(|| log_dir.to_string_lossy().into_owned());
+    }
+
+    #[cfg(debug_assertions)]
+    {
+        #[cfg(windows)]
+        let primary_cmd = "pythonw";
+        #[cfg(not(windows))]
+        let primary_cmd = "python";
+
+        let spawn_primary = app
             .shell()
+            .command(primary_cmd)
+            .args(["../worker/main.py"])
+            .args(&worker_settings.args)
+            .envs(worker_settings.env.clone())
+            .spawn();
+
+        #[cfg(windows)]
+        let spawn_primary = spawn_primary.or_else(|_| {
+            app.shell()
+                .command("python")
+                .args(["../worker/main.py"])
+                .args(&worker_settings.args)
+                .envs(worker_settings.env.clone())
+                .spawn()
+        });
+
+        spawn_primary.map_err(|e| format!("Failed to spawn python worker: {}", e))
+    }
+
+    // Ne jamais lancer un backend (accès complet au système) dont l'empreinte ne correspond pas
+    // à celle du build. Sans empreinte embarquée, la vérification est impossible et ignorée.
+    #[cfg(not(debug_assertions))]
+    {
+        let integrity = verify_backend_integrity();
+        if EXPECTED_BACKEND_SHA256.is_some() && !integrity.verified {
+            let _ = app.emit("backend-integrity-failure", &integrity);
+            return Err(format!(
+                "Backend integrity check failed: {}",
+                integrity.error.clone().unwrap_or_default()
+            ));
+        }
+        app.shell()
             .sidecar("backend")
-            .expect("Failed to create sidecar command")
-            .spawn()
-            .expect("Failed to spawn backend sidecar");
+            .map_err(|e| format!("Failed to create sidecar command: {}", e))
+            .and_then(|command| {
+                command
+                    .args(&worker_settings.args)
+                    .envs(worker_settings.env.clone())
+                    .spawn()
+                    .map_err(|e| format!("Failed to spawn backend sidecar: {}", e))
+            })
+    }
+}
 
-        // ==============================
-        // LECTURE STDOUT PYTHON
-        // ==============================
-        tauri::async_runtime::spawn(async move {
-            while let Some(event) = rx_sidecar.recv().await {
-                match event {
-                    CommandEvent::Stdout(bytes) => {
-                        let text = String::from_utf8_lossy(&bytes);
-
-                        for line in text.lines() {
-                            let trimmed = line.trim();
-                            if trimmed.is_empty() {
-                                continue;
-                            }
+/// État partagé entre le bridge et la tâche qui lit la sortie du worker
+/// (une nouvelle tâche est lancée pour chaque worker, y compris après un redémarrage)
+struct WorkerOutput<R: Runtime> {
+    app: AppHandle<R>,
+    tx_command: mpsc::Sender<String>,  // Commandes émises par le bridge lui-même (cancel_pull)
+    pending: Arc<Mutex<HashMap<String, oneshot::Sender<PyResponse>>>>,
+    ready: Arc<AtomicBool>,
+    ready_notify: Arc<Notify>,
+    stream_filter: Arc<RwLock<Option<HashSet<String>>>>,
+    paused_streams: PausedStreams,
+    recent_streams: RecentStreams,
+    lifecycle: Arc<std::sync::Mutex<WorkerLifecycle>>,
+    worker_info: Arc<RwLock<Option<Value>>>,
+}
 
-                            // Tentative de parsing JSON
-                            if let Ok(val) = serde_json::from_str::<Value>(trimmed) {
+/// Interrompt un téléchargement de modèle qui a dépassé le budget réseau : le worker reçoit
+/// cancel_pull (il termine le stream par "cancelled") et l'UI est prévenue
+async fn abort_pull<R: Runtime>(output: &WorkerOutput<R>, request_id: &str, event: &Value, reason: String) {
+    eprintln!("[BRIDGE] Aborting model pull {}: {}", request_id, reason);
+    let model = event.get("model").cloned().unwrap_or(Value::Null);
+    let cancel = PyRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        cmd: CANCEL_PULL_COMMAND.to_string(),
+        payload: serde_json::json!({ "model": model }),
+    };
+    // Réponse attendue par personne : l'enregistrer pour qu'elle ne soit pas relayée en "python-push"
+    let (tx, _) = oneshot::channel();
+    output.pending.lock().await.insert(cancel.id.clone(), tx);
+    if let Ok(cancel_json) = serde_json::to_string(&cancel) {
+        if output.tx_command.send(cancel_json).await.is_err() {
+            output.pending.lock().await.remove(&cancel.id);
+        }
+    }
 
-                                // CAS 1: Événement de Stream (Tokens IA)
-                                if val.get("event").is_some() {
-                                    let _ = app_emit.emit("python-stream", val);
+    let _ = output.app.emit("egress-budget-exceeded", serde_json::json!({
+        "request_id": request_id,
+        "cmd": PULL_COMMAND,
+        "model": model,
+        "message": reason
+    }));
+}
+
+// ==============================
+// LECTURE STDOUT PYTHON
+// ==============================
+// `terminated_tx` signale l'arrêt du worker au superviseur (tâche d'écriture)
+fn spawn_output_reader<R: Runtime>(
+    output: Arc<WorkerOutput<R>>,
+    mut rx_sidecar: tauri::async_runtime::Receiver<CommandEvent>,
+    terminated_tx: mpsc::Sender<()>,
+) {
+    tauri::async_runtime::spawn(async move {
+        // Octets déjà comptés par (requête, couche) pour les téléchargements de modèles
+        let mut pulled_bytes: HashMap<(String, String), u64> = HashMap::new();
+        // Téléchargements déjà interrompus pour dépassement du budget (une seule annulation)
+        let mut aborted_pulls: HashSet<String> = HashSet::new();
+        while let Some(event) = rx_sidecar.recv().await {
+            match event {
+                CommandEvent::Stdout(bytes) => {
+                    let text = String::from_utf8_lossy(&bytes);
+
+                    for line in text.lines() {
+                        let trimmed = line.trim();
+                        if trimmed.is_empty() {
+                            continue;
+                        }
+
+                        // Tentative de parsing JSON
+                        if let Ok(val) = serde_json::from_str::<Value>(trimmed) {
+
+                            // CAS 1: Événement de Stream (Tokens IA)
+                            if let Some(event) = val.get("event") {
+                                let event_name = event.as_str().unwrap_or_default();
+                                if event_name == "progress" || TERMINAL_STREAM_EVENTS.contains(&event_name) {
+                                    let metered = meter_pull_progress(&output.app, &mut pulled_bytes, event_name, &val);
+                                    let request_id = val.get("id").and_then(|id| id.as_str()).unwrap_or_default();
+                                    if TERMINAL_STREAM_EVENTS.contains(&event_name) {
+                                        aborted_pulls.remove(request_id);
+                                    } else if let Err(reason) = metered {
+                                        if aborted_pulls.insert(request_id.to_string()) {
+                                            abort_pull(&output, request_id, &val, reason).await;
+                                        }
+                                    }
+                                }
+                                let forward = TERMINAL_STREAM_EVENTS.contains(&event_name)
+                                    || match output.stream_filter.read() {
+                                        Ok(filter) => filter
+                                            .as_ref()
+                                            .map_or(true, |allowed| allowed.contains(event_name)),
+                                        Err(_) => true,
+                                    };
+                                if !forward {
                                     continue;
                                 }
 
-                                // CAS 2: Réponse classique (RPC)
-                                let val_clone = val.clone();
-
-                                if let Ok(resp) = serde_json::from_value::<PyResponse>(val_clone) {
-                                    let mut map = pending_reader.lock().await;
-                                    
-                                    if let Some(tx) = map.remove(&resp.id) {
-                                        let _ = tx.send(resp);
-                                    } else {
-                                        // Cas B : Message "Push" (ex: Monitoring stats)
-                                        let _ = app_emit.emit("python-push", val);
+                                if let Some(id) = val.get("id").and_then(|id| id.as_str()) {
+                                    record_stream_event(&output.recent_streams, id, event_name, &val);
+                                }
+
+                                // Stream en pause : mettre en attente au lieu de relayer
+                                if let Ok(mut paused) = output.paused_streams.lock() {
+                                    let stream = val
+                                        .get("id")
+                                        .and_then(|id| id.as_str())
+                                        .and_then(|id| paused.get_mut(id));
+                                    if let Some(stream) = stream {
+                                        if stream.buffered.len() >= MAX_PAUSED_STREAM_EVENTS {
+                                            stream.buffered.pop_front();
+                                            stream.dropped += 1;
+                                        }
+                                        stream.buffered.push_back(val);
+                                        continue;
                                     }
-                                    continue;
                                 }
+
+                                let _ = output.app.emit("python-stream", val);
+                                continue;
                             }
 
-                            // LOGS: Si ce n'est pas du JSON, on l'affiche comme log classique
-                            println!("Python Log: {}", trimmed);
+                            // CAS 2: Réponse classique (RPC)
+                            let val_clone = val.clone();
+
+                            if let Ok(resp) = serde_json::from_value::<PyResponse>(val_clone) {
+                                // Handshake : le worker est prêt à recevoir des commandes
+                                if resp.id == WORKER_READY_ID {
+                                    if let Ok(mut info) = output.worker_info.write() {
+                                        *info = resp.data.clone();
+                                    }
+                                    output.ready.store(true, Ordering::SeqCst);
+                                    output.ready_notify.notify_waiters();
+                                    let _ = output.app.emit("worker-ready", resp.data.unwrap_or(Value::Null));
+                                    continue;
+                                }
+
+                                let mut map = output.pending.lock().await;
+                                
+                                if let Some(tx) = map.remove(&resp.id) {
+                                    let _ = tx.send(resp);
+                                } else {
+                                    // Cas B : Message "Push" (ex: Monitoring stats)
+                                    let _ = output.app.emit("python-push", val);
+                                }
+                                continue;
+                            }
                         }
-                    }
 
-                    CommandEvent::Stderr(bytes) => {
-                        let err = String::from_utf8_lossy(&bytes);
-                        eprintln!("Python STDERR: {}", err);
+                        // LOGS: Si ce n'est pas du JSON, relayer comme log structuré
+                        #[cfg(debug_assertions)]
+                        println!("Python Log: {}", trimmed);
+                        let _ = output.app.emit("python-log", parse_log_line(trimmed));
                     }
+                }
 
-                    _ => {}
+                CommandEvent::Stderr(bytes) => {
+                    let err = String::from_utf8_lossy(&bytes);
+                    eprintln!("Python STDERR: {}", err);
                 }
+
+                CommandEvent::Terminated(status) => {
+                    eprintln!("[BRIDGE ERROR] Python worker terminated (code: {:?})", status.code);
+                    // Plus de worker : les requêtes en attente sont perdues.
+                    // Fermer leurs oneshots pour qu'elles échouent immédiatement (au lieu d'un timeout)
+                    output.ready.store(false, Ordering::SeqCst);
+                    if let Ok(mut lifecycle) = output.lifecycle.lock() {
+                        lifecycle.started_at = None;
+                        lifecycle.crash_count += 1;
+                        lifecycle.last_crash_at = Some(chrono::Utc::now());
+                    }
+                    output.pending.lock().await.clear();
+                    let _ = output.app.emit("worker-terminated", serde_json::json!({
+                        "code": status.code,
+                        "signal": status.signal
+                    }));
+                    // Le superviseur relance un nouveau worker
+                    let _ = terminated_tx.send(()).await;
+                }
+
+                _ => {}
             }
+        }
+    });
+}
+
+impl<R: Runtime> PythonBridge<R> {
+    pub fn new(app: &AppHandle<R>) -> Self {
+        let (tx_command, mut rx_command) = mpsc::channel::<String>(100);
+        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+        let pending: Arc<Mutex<HashMap<String, oneshot::Sender<PyResponse>>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+
+        let ready = Arc::new(AtomicBool::new(false));
+        let ready_notify = Arc::new(Notify::new());
+        let stream_filter: Arc<RwLock<Option<HashSet<String>>>> = Arc::new(RwLock::new(None));
+        let paused_streams: PausedStreams = Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let recent_streams: RecentStreams = Arc::new(std::sync::Mutex::new(HashMap::new()));
+        let lifecycle: Arc<std::sync::Mutex<WorkerLifecycle>> = Arc::default();
+        let worker_info: Arc<RwLock<Option<Value>>> = Arc::new(RwLock::new(None));
+
+        // Backend introuvable : ne pas faire planter l'app, rester en mode indisponible
+        let (rx_sidecar, mut child) = match spawn_worker(app) {
+            Ok(spawned) => spawned,
+            Err(reason) => {
+                eprintln!("[BRIDGE ERROR] {}", reason);
+                let _ = app.emit("backend-missing", serde_json::json!({
+                    "reason": reason,
+                    "hint": SPAWN_HINT
+                }));
+                return Self {
+                    tx_command,
+                    pending,
+                    app_handle: app.clone(),
+                    shutdown_tx,
+                    ready,
+                    ready_notify,
+                    stream_filter,
+                    paused_streams,
+                    recent_streams,
+                    command_timeouts: RwLock::new(HashMap::new()),
+                    max_pending: AtomicUsize::new(DEFAULT_MAX_PENDING),
+                    latencies: std::sync::Mutex::new(HashMap::new()),
+                    lifecycle,
+                    worker_info,
+                    unavailable_reason: Some(format!("{} {}", reason, SPAWN_HINT)),
+                };
+            }
+        };
+
+        if let Ok(mut lifecycle) = lifecycle.lock() {
+            lifecycle.started_at = Some(std::time::Instant::now());
+        }
+
+        let output = Arc::new(WorkerOutput {
+            app: app.clone(),
+            tx_command: tx_command.clone(),
+            pending: pending.clone(),
+            ready: ready.clone(),
+            ready_notify: ready_notify.clone(),
+            stream_filter: stream_filter.clone(),
+            paused_streams: paused_streams.clone(),
+            recent_streams: recent_streams.clone(),
+            lifecycle: lifecycle.clone(),
+            worker_info: worker_info.clone(),
         });
+        let (terminated_tx, mut terminated_rx) = mpsc::channel::<()>(1);
+        spawn_output_reader(output.clone(), rx_sidecar, terminated_tx.clone());
 
         // ==============================
-        // ÉCRITURE STDIN PYTHON + GESTION SHUTDOWN
+        // ÉCRITURE STDIN PYTHON + GESTION SHUTDOWN ET REDÉMARRAGE
         // ==============================
         tauri::async_runtime::spawn(async move {
+            let mut started_at = std::time::Instant::now();
+            let mut restarts: u32 = 0;  // Redémarrages successifs d'un worker instable
             loop {
                 tokio::select! {
                     // Recevoir une commande à envoyer au worker
@@ -147,6 +799,45 @@ impl<R: Runtime> PythonBridge<R> {
                             eprintln!("Failed to write to python worker: {}", e);
                         }
                     }
+                    // Le worker s'est arrêté sans shutdown : en relancer un nouveau
+                    Some(()) = terminated_rx.recv() => {
+                        if started_at.elapsed().as_secs() >= WORKER_STABLE_SECS {
+                            restarts = 0;
+                        }
+                        if restarts >= MAX_WORKER_RESTARTS {
+                            eprintln!("[BRIDGE ERROR] Python worker crashed {} times in a row, not restarting", restarts);
+                            let _ = output.app.emit("worker-restart-failed", serde_json::json!({
+                                "reason": format!("Worker crashed {} times in a row", restarts),
+                                "hint": SPAWN_HINT
+                            }));
+                            continue;
+                        }
+                        restarts += 1;
+                        tokio::time::sleep(Duration::from_millis(WORKER_RESTART_DELAY_MS)).await;
+
+                        match spawn_worker(&output.app) {
+                            Ok((rx_sidecar, new_child)) => {
+                                println!("🔄 Python worker restarted (attempt {})", restarts);
+                                child = new_child;
+                                started_at = std::time::Instant::now();
+                                if let Ok(mut lifecycle) = output.lifecycle.lock() {
+                                    lifecycle.started_at = Some(started_at);
+                                    lifecycle.restart_count += 1;
+                                }
+                                spawn_output_reader(output.clone(), rx_sidecar, terminated_tx.clone());
+                                let _ = output.app.emit("worker-restarted", serde_json::json!({
+                                    "attempt": restarts
+                                }));
+                            }
+                            Err(reason) => {
+                                eprintln!("[BRIDGE ERROR] Failed to restart python worker: {}", reason);
+                                let _ = output.app.emit("worker-restart-failed", serde_json::json!({
+                                    "reason": reason,
+                                    "hint": SPAWN_HINT
+                                }));
+                            }
+                        }
+                    }
                     // Recevoir le signal de shutdown
                     _ = shutdown_rx.recv() => {
                         println!("🛑 Shutting down Python worker...");
@@ -171,39 +862,458 @@ impl<R: Runtime> PythonBridge<R> {
             pending,
             app_handle: app.clone(),
             shutdown_tx,
+            ready,
+            ready_notify,
+            stream_filter,
+            paused_streams,
+            recent_streams,
+            command_timeouts: RwLock::new(HashMap::new()),
+            max_pending: AtomicUsize::new(DEFAULT_MAX_PENDING),
+            latencies: std::sync::Mutex::new(HashMap::new()),
+            lifecycle,
+            worker_info,
+            unavailable_reason: None,
         }
     }
 
-    pub async fn send(&self, cmd: String, payload: Value) -> Result<Value, String> {
-        let id = uuid::Uuid::new_v4().to_string();
-        let (tx, rx) = oneshot::channel();
+    /// Raison de l'indisponibilité du worker (None = worker lancé)
+    pub fn unavailable_reason(&self) -> Option<&str> {
+        self.unavailable_reason.as_deref()
+    }
 
-        {
-            let mut map = self.pending.lock().await;
-            map.insert(id.clone(), tx);
+    /// Échoue immédiatement si le worker n'a jamais pu être lancé
+    fn ensure_available(&self) -> Result<(), String> {
+        match &self.unavailable_reason {
+            Some(reason) => Err(format!("Python backend unavailable: {}", reason)),
+            None => Ok(()),
+        }
+    }
+
+    /// Définit les événements de stream relayés au frontend (None = tous)
+    /// Les événements de fin (done/error/cancelled) sont toujours relayés
+    pub fn set_stream_filter(&self, events: Option<Vec<String>>) -> Result<(), String> {
+        let mut filter = self.stream_filter.write().map_err(|e| e.to_string())?;
+        *filter = events.map(|list| list.into_iter().collect());
+        Ok(())
+    }
+
+    /// Suspend le relais des événements de stream d'une requête (la génération continue)
+    pub fn pause_stream(&self, request_id: &str) -> Result<(), String> {
+        let mut paused = self.paused_streams.lock().map_err(|e| e.to_string())?;
+        paused.entry(request_id.to_string()).or_default();
+        Ok(())
+    }
+
+    /// Reprend le relais : les événements mis en attente sont relayés dans l'ordre
+    /// (sous le lock, pour qu'aucun nouvel événement ne les double)
+    pub fn resume_stream(&self, request_id: &str) -> Result<ResumedStream, String> {
+        let mut paused = self.paused_streams.lock().map_err(|e| e.to_string())?;
+        let stream = paused
+            .remove(request_id)
+            .ok_or_else(|| format!("Stream {} is not paused", request_id))?;
+
+        let flushed = stream.buffered.len();
+        for event in stream.buffered {
+            let _ = self.app_handle.emit("python-stream", event);
+        }
+
+        Ok(ResumedStream {
+            request_id: request_id.to_string(),
+            flushed,
+            dropped: stream.dropped,
+        })
+    }
+
+    /// Réémet les événements récents d'un stream vers une seule fenêtre (marqués "replayed")
+    /// pour qu'une fenêtre ouverte ou rechargée en cours de génération rattrape la réponse
+    pub fn replay_stream(&self, request_id: &str, window_label: &str) -> Result<ReplayedStream, String> {
+        let streams = self.recent_streams.lock().map_err(|e| e.to_string())?;
+        let stream = streams
+            .get(request_id)
+            .ok_or_else(|| format!("No recent stream events for request {}", request_id))?;
+
+        for event in &stream.events {
+            let mut event = event.clone();
+            if let Some(object) = event.as_object_mut() {
+                object.insert("replayed".to_string(), Value::Bool(true));
+            }
+            let _ = self.app_handle.emit_to(window_label, "python-stream", event);
+        }
+
+        Ok(ReplayedStream {
+            request_id: request_id.to_string(),
+            replayed: stream.events.len(),
+            truncated: stream.truncated,
+            finished: stream.finished,
+        })
+    }
+
+    /// Délai de réponse appliqué à une commande (délai configuré, sinon délai intégré)
+    pub fn timeout_for(&self, cmd: &str) -> u64 {
+        self.command_timeouts
+            .read()
+            .ok()
+            .and_then(|timeouts| timeouts.get(cmd).copied())
+            .unwrap_or(match cmd {
+                "set_model" => MODEL_LOAD_TIMEOUT_SECS,
+                _ => RESPONSE_TIMEOUT_SECS,
+            })
+    }
+
+    /// Délais spécifiques configurés (commande -> secondes)
+    pub fn command_timeouts(&self) -> HashMap<String, u64> {
+        self.command_timeouts
+            .read()
+            .map(|timeouts| timeouts.clone())
+            .unwrap_or_default()
+    }
+
+    /// Définit le délai d'une commande (None = revenir au délai par défaut)
+    pub fn set_command_timeout(&self, cmd: String, secs: Option<u64>) -> Result<(), String> {
+        if cmd.trim().is_empty() {
+            return Err("Command name cannot be empty".to_string());
+        }
+        let mut timeouts = self.command_timeouts.write().map_err(|e| e.to_string())?;
+        match secs {
+            Some(secs) if secs == 0 || secs > MAX_COMMAND_TIMEOUT_SECS => {
+                return Err(format!(
+                    "Timeout must be between 1 and {} seconds",
+                    MAX_COMMAND_TIMEOUT_SECS
+                ));
+            }
+            Some(secs) => {
+                timeouts.insert(cmd, secs);
+            }
+            None => {
+                timeouts.remove(&cmd);
+            }
+        }
+        Ok(())
+    }
+
+    /// Enregistre la latence d'un aller-retour (les commandes au-delà de MAX_LATENCY_COMMANDS sont ignorées)
+    fn record_latency(&self, cmd: &str, latency: Duration) {
+        let mut latencies = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+        if !latencies.contains_key(cmd) && latencies.len() >= MAX_LATENCY_COMMANDS {
+            return;
+        }
+        latencies
+            .entry(cmd.to_string())
+            .or_insert_with(LatencyHistogram::new)
+            .record(latency);
+    }
+
+    /// p50/p90/p99 de la latence aller-retour d'une commande depuis le lancement
+    /// (count = 0 si la commande n'a pas encore reçu de réponse)
+    pub fn latency_percentiles(&self, cmd: &str) -> LatencyPercentiles {
+        let latencies = self.latencies.lock().unwrap_or_else(|e| e.into_inner());
+        match latencies.get(cmd) {
+            Some(histogram) => histogram.percentiles(cmd),
+            None => LatencyHistogram::new().percentiles(cmd),
+        }
+    }
+
+    /// Nombre de requêtes en attente de réponse du worker
+    pub async fn pending_count(&self) -> usize {
+        self.pending.lock().await.len()
+    }
+
+    /// Nombre maximal de requêtes en attente
+    pub fn max_pending(&self) -> usize {
+        self.max_pending.load(Ordering::SeqCst)
+    }
+
+    /// Ajuste le nombre maximal de requêtes en attente (les requêtes déjà en cours ne sont pas affectées)
+    pub fn set_max_pending(&self, max: usize) -> Result<(), String> {
+        if max == 0 || max > MAX_PENDING_LIMIT {
+            return Err(format!("Max pending must be between 1 and {}", MAX_PENDING_LIMIT));
         }
+        self.max_pending.store(max, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Refuse une nouvelle requête si la file d'attente est pleine (événement "worker-backpressure")
+    fn reject_if_full(&self, depth: usize, cmd: &str) -> Result<(), String> {
+        let max = self.max_pending();
+        if depth < max {
+            return Ok(());
+        }
+        let _ = self.app_handle.emit("worker-backpressure", serde_json::json!({
+            "cmd": cmd,
+            "pending": depth,
+            "max_pending": max,
+            "timestamp": chrono::Utc::now().to_rfc3339()
+        }));
+        Err(format!(
+            "Python worker is busy ({} requests pending, max {}), cannot run '{}'",
+            depth, max, cmd
+        ))
+    }
+
+    /// Champ du dernier handshake du worker (None avant le premier handshake)
+    pub fn worker_info_field(&self, field: &str) -> Option<Value> {
+        self.worker_info
+            .read()
+            .ok()
+            .and_then(|info| info.as_ref().and_then(|data| data.get(field).cloned()))
+    }
+
+    /// Fichier de log du worker : celui annoncé par le handshake, sinon l'emplacement
+    /// demandé au lancement (dossier de logs de l'app)
+    pub fn worker_log_file(&self) -> Option<std::path::PathBuf> {
+        self.worker_info_field("log_file")
+            .and_then(|value| value.as_str().map(std::path::PathBuf::from))
+            .or_else(|| {
+                self.app_handle
+                    .path()
+                    .app_log_dir()
+                    .ok()
+                    .map(|dir| dir.join(WORKER_LOG_FILE))
+            })
+    }
+
+    /// Durée de fonctionnement, arrêts et dernier arrêt du worker
+    pub fn status(&self) -> WorkerStatus {
+        let lifecycle = self.lifecycle.lock().unwrap_or_else(|e| e.into_inner());
+        WorkerStatus {
+            uptime_secs: lifecycle.started_at.map(|started| started.elapsed().as_secs()),
+            crash_count: lifecycle.crash_count,
+            restart_count: lifecycle.restart_count,
+            last_crash_at: lifecycle.last_crash_at.map(|at| at.to_rfc3339()),
+            ready: self.is_ready(),
+        }
+    }
+
+    /// Indique si le worker a terminé son initialisation (handshake reçu)
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::SeqCst)
+    }
 
+    /// Attend que le worker soit prêt, au plus `max_wait`
+    async fn wait_until_ready(&self, max_wait: Duration) -> bool {
+        if self.is_ready() {
+            return true;
+        }
+
+        // Enregistrer l'attente AVANT de revérifier le flag pour ne pas rater la notification
+        let notified = self.ready_notify.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+
+        if self.is_ready() {
+            return true;
+        }
+
+        timeout(max_wait, notified).await.is_ok() || self.is_ready()
+    }
+
+    /// Envoie une commande au worker. Si `request_id` est fourni (par le frontend), il est utilisé
+    /// tel quel afin de pouvoir filtrer les événements de stream avant même la fin de l'appel.
+    /// Avec `retry_on_crash`, une requête perdue parce que le worker s'est arrêté est renvoyée
+    /// une fois au worker relancé s'il est prêt à temps (à réserver aux commandes idempotentes).
+    pub async fn send(
+        &self,
+        cmd: String,
+        payload: Value,
+        request_id: Option<String>,
+        retry_on_crash: bool,
+    ) -> Result<DispatchedResponse, String> {
+        self.ensure_available()?;
+        if cmd == PULL_COMMAND {
+            crate::egress_budget::check_egress(&self.app_handle, &cmd)?;
+        }
+
+        // Ne pas envoyer de commande avant la fin de l'initialisation du worker
+        if !self.wait_until_ready(Duration::from_secs(READY_TIMEOUT_SECS)).await {
+            return Err(format!(
+                "Python worker is not ready yet (still starting after {} seconds), cannot run '{}'",
+                READY_TIMEOUT_SECS, cmd
+            ));
+        }
+
+        let id = match request_id {
+            Some(id) => {
+                if id.is_empty() || id.len() > MAX_REQUEST_ID_LEN {
+                    return Err(format!(
+                        "Invalid request id: must be 1 to {} characters",
+                        MAX_REQUEST_ID_LEN
+                    ));
+                }
+                if RESERVED_REQUEST_IDS.contains(&id.as_str()) {
+                    return Err(format!("Invalid request id: '{}' is reserved", id));
+                }
+                id
+            }
+            None => uuid::Uuid::new_v4().to_string(),
+        };
         let req = PyRequest { id: id.clone(), cmd: cmd.clone(), payload };
         let req_json = serde_json::to_string(&req).map_err(|e| e.to_string())?;
 
-        self.tx_command
-            .send(req_json)
-            .await
-            .map_err(|_| "Worker channel closed")?;
-
-        // ✅ Timeout de 30 secondes pour éviter les freeze UI si le worker crash
-        match timeout(Duration::from_secs(30), rx).await {
-            Ok(Ok(resp)) => {
-                if resp.status == "ok" {
-                    Ok(resp.data.unwrap_or(Value::Null))
-                } else {
-                    Err(resp
-                        .error
-                        .map(|v| v.to_string())
-                        .unwrap_or_else(|| "Unknown worker error".into()))
+        let mut retry_available = retry_on_crash;
+        let resp = loop {
+            let (tx, rx) = oneshot::channel();
+
+            {
+                let mut map = self.pending.lock().await;
+                if map.contains_key(&id) {
+                    return Err(format!("Request id '{}' is already in use", id));
+                }
+                self.reject_if_full(map.len(), &cmd)?;
+                map.insert(id.clone(), tx);
+            }
+
+            if self.tx_command.send(req_json.clone()).await.is_err() {
+                self.pending.lock().await.remove(&id);
+                return Err("Worker channel closed".into());
+            }
+
+            let _ = self.app_handle.emit("request-dispatched", serde_json::json!({
+                "request_id": id,
+                "cmd": cmd
+            }));
+
+            let dispatched_at = std::time::Instant::now();
+            match self.await_response(&id, &cmd, self.timeout_for(&cmd), rx).await {
+                Ok(resp) => {
+                    // Réponse reçue (succès ou erreur du worker) : les délais dépassés ne sont pas comptés
+                    self.record_latency(&cmd, dispatched_at.elapsed());
+                    break resp;
+                }
+                Err(ResponseError::WorkerLost) if retry_available => {
+                    retry_available = false;
+                    // Le superviseur relance le worker : attendre son handshake
+                    if !self.wait_until_ready(Duration::from_secs(CRASH_RETRY_WINDOW_SECS)).await {
+                        return Err(format!(
+                            "Worker crashed and was not ready again within {} seconds, '{}' was not retried",
+                            CRASH_RETRY_WINDOW_SECS, cmd
+                        ));
+                    }
+                    eprintln!("[BRIDGE] Worker restarted, retrying command: {}", cmd);
+                    let _ = self.app_handle.emit("request-retried", serde_json::json!({
+                        "request_id": id,
+                        "cmd": cmd
+                    }));
                 }
+                Err(e) => return Err(e.into()),
             }
-            Ok(Err(_)) => Err("Worker crashed or request lost".into()),
+        };
+
+        if resp.status == "ok" {
+            Ok(DispatchedResponse {
+                request_id: id,
+                data: resp.data.unwrap_or(Value::Null),
+            })
+        } else {
+            Err(resp
+                .error
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "Unknown worker error".into()))
+        }
+    }
+
+    /// Envoie plusieurs commandes dans une seule trame et attend un tableau de réponses
+    /// (une réponse par élément, associée par id). Les commandes de stream ne sont pas supportées.
+    pub async fn send_batch(&self, items: Vec<(String, Value)>) -> Result<Vec<BatchItemResult>, String> {
+        if items.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.ensure_available()?;
+        if items.len() > MAX_BATCH_SIZE {
+            return Err(format!(
+                "Batch too large ({} commands). Max allowed: {}",
+                items.len(),
+                MAX_BATCH_SIZE
+            ));
+        }
+
+        if !self.wait_until_ready(Duration::from_secs(READY_TIMEOUT_SECS)).await {
+            return Err(format!(
+                "Python worker is not ready yet (still starting after {} seconds), cannot run batch",
+                READY_TIMEOUT_SECS
+            ));
+        }
+
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let requests: Vec<PyRequest> = items
+            .into_iter()
+            .map(|(cmd, payload)| PyRequest {
+                id: uuid::Uuid::new_v4().to_string(),
+                cmd,
+                payload,
+            })
+            .collect();
+        // Le batch dispose du plus long des délais de ses commandes
+        let batch_timeout = requests
+            .iter()
+            .map(|r| self.timeout_for(&r.cmd))
+            .max()
+            .unwrap_or(RESPONSE_TIMEOUT_SECS);
+        let items: Vec<(String, String)> = requests
+            .iter()
+            .map(|r| (r.id.clone(), r.cmd.clone()))
+            .collect();
+
+        let (tx, rx) = oneshot::channel();
+        {
+            let mut map = self.pending.lock().await;
+            self.reject_if_full(map.len(), "batch")?;
+            map.insert(batch_id.clone(), tx);
+        }
+
+        let frame = PyBatchRequest { id: batch_id.clone(), batch: requests };
+        let frame_json = serde_json::to_string(&frame).map_err(|e| e.to_string())?;
+
+        if self.tx_command.send(frame_json).await.is_err() {
+            self.pending.lock().await.remove(&batch_id);
+            return Err("Worker channel closed".into());
+        }
+
+        let resp = self.await_response(&batch_id, "batch", batch_timeout, rx).await.map_err(String::from)?;
+        if resp.status != "ok" {
+            return Err(resp
+                .error
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "Unknown worker error".into()));
+        }
+
+        let responses: Vec<PyResponse> = serde_json::from_value(resp.data.unwrap_or(Value::Null))
+            .map_err(|e| format!("Invalid batch response from worker: {}", e))?;
+        let mut by_id: HashMap<String, PyResponse> =
+            responses.into_iter().map(|r| (r.id.clone(), r)).collect();
+
+        Ok(items
+            .into_iter()
+            .map(|(id, cmd)| match by_id.remove(&id) {
+                Some(r) => BatchItemResult {
+                    id,
+                    cmd,
+                    status: r.status,
+                    data: r.data,
+                    error: r.error,
+                },
+                None => BatchItemResult {
+                    id,
+                    cmd,
+                    status: "error".to_string(),
+                    data: None,
+                    error: Some(Value::String("No response for batch item".to_string())),
+                },
+            })
+            .collect())
+    }
+
+    /// Attend la réponse d'une requête en attente (timeout : événement "worker-timeout" + nettoyage)
+    async fn await_response(
+        &self,
+        id: &str,
+        cmd: &str,
+        timeout_secs: u64,
+        rx: oneshot::Receiver<PyResponse>,
+    ) -> Result<PyResponse, ResponseError> {
+        // ✅ Timeout pour éviter les freeze UI si le worker crash
+        match timeout(Duration::from_secs(timeout_secs), rx).await {
+            Ok(Ok(resp)) => Ok(resp),
+            Ok(Err(_)) => Err(ResponseError::WorkerLost),
             Err(_) => {
                 // ✅ AMÉLIORATION V2.1 : Timeout avec feedback utilisateur
                 // 1. Logger la tentative pour debugging
@@ -212,7 +1322,7 @@ impl<R: Runtime> PythonBridge<R> {
                 // 2. Émettre événement vers frontend pour notification utilisateur
                 let timeout_event = serde_json::json!({
                     "cmd": cmd,
-                    "timeout_secs": 30,
+                    "timeout_secs": timeout_secs,
                     "timestamp": chrono::Utc::now().to_rfc3339(),
                     "request_id": id
                 });
@@ -221,10 +1331,13 @@ impl<R: Runtime> PythonBridge<R> {
                 
                 // 3. Nettoyer la map des requêtes en attente pour éviter les fuites mémoire
                 let mut map = self.pending.lock().await;
-                map.remove(&id);
+                map.remove(id);
                 
                 // 4. Retourner une erreur détaillée
-                Err(format!("Request timeout: Python worker did not respond to '{}' within 30 seconds", cmd))
+                Err(ResponseError::Failed(format!(
+                    "Request timeout: Python worker did not respond to '{}' within {} seconds",
+                    cmd, timeout_secs
+                )))
             }
         }
     }
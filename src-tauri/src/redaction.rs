@@ -0,0 +1,62 @@
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+
+/// Texte de remplacement des secrets détectés
+pub const REDACTION_MARKER: &str = "***REDACTED***";
+
+lazy_static! {
+    /// Secrets reconnaissables à leur format (remplacés en entier)
+    static ref SECRET_PATTERNS: Vec<Regex> = [
+        // Blocs de clé privée (jusqu'à la fin du texte si le bloc est tronqué, ex. preview)
+        r"(?s)-----BEGIN [A-Z ]*PRIVATE KEY-----.*?(?:-----END [A-Z ]*PRIVATE KEY-----|\z)",
+        // Clé d'accès AWS
+        r"\b(?:AKIA|ASIA)[0-9A-Z]{16}\b",
+        // Tokens GitHub
+        r"\bgh[pousr]_[A-Za-z0-9]{36,}\b",
+        r"\bgithub_pat_[A-Za-z0-9_]{22,}\b",
+        // Tokens Slack
+        r"\bxox[abprs]-[A-Za-z0-9-]{10,}\b",
+        // Clés API Google
+        r"\bAIza[0-9A-Za-z_-]{35}\b",
+        // Clés API de type "sk-..." (OpenAI, Anthropic, Stripe...)
+        r"\b(?:sk|rk)[-_](?:live_|test_|ant-|proj-)?[A-Za-z0-9_-]{20,}\b",
+        // JWT
+        r"\beyJ[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\.[A-Za-z0-9_-]{10,}\b",
+    ]
+    .iter()
+    .map(|pattern| Regex::new(pattern).expect("invalid secret pattern"))
+    .collect();
+
+    /// Affectations de secrets (`password = ...`, `"api_key": "..."`) : seule la valeur est masquée
+    static ref SECRET_ASSIGNMENT: Regex = Regex::new(
+        r#"(?i)(\b(?:password|passwd|pwd|secret|api[_-]?key|access[_-]?key|access[_-]?token|auth[_-]?token|client[_-]?secret|private[_-]?key)\b["']?\s*[:=]\s*["']?)([^\s"',;]{4,})"#
+    )
+    .expect("invalid secret assignment pattern");
+}
+
+/// Masque les secrets détectés dans un texte. Retourne le texte masqué et le nombre de remplacements
+pub fn redact_secrets(text: &str) -> (String, usize) {
+    let mut redactions = 0;
+    let mut redacted = text.to_string();
+
+    for pattern in SECRET_PATTERNS.iter() {
+        let count = pattern.find_iter(&redacted).count();
+        if count > 0 {
+            redactions += count;
+            redacted = pattern.replace_all(&redacted, REDACTION_MARKER).into_owned();
+        }
+    }
+
+    redacted = SECRET_ASSIGNMENT
+        .replace_all(&redacted, |caps: &Captures| {
+            // Valeur déjà masquée par un motif précédent
+            if &caps[2] == REDACTION_MARKER {
+                return caps[0].to_string();
+            }
+            redactions += 1;
+            format!("{}{}", &caps[1], REDACTION_MARKER)
+        })
+        .into_owned();
+
+    (redacted, redactions)
+}
@@ -0,0 +1,350 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, Runtime, State, Wry};
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+use crate::command_executor::{CommandExecutor, CommandExecutorConfig};
+use crate::context_reader::ContextReader;
+use crate::permission_manager::PermissionManager;
+
+/// Version actuelle du format de profil de réglages
+const SETTINGS_SCHEMA_VERSION: u32 = 1;
+/// Réglages de l'app (dossier de configuration), au format SettingsProfile
+pub(crate) const SETTINGS_FILE: &str = "settings.json";
+
+/// Réglages du context reader exportés (sans le scope, propre à la machine)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContextSettings {
+    pub allowed_extensions: Vec<String>,
+    pub max_file_size: usize,
+    pub max_total_size: usize,
+}
+
+/// Modes du gestionnaire de permissions (les permissions accordées ne sont jamais exportées)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PermissionSettings {
+    pub parano_mode: bool,
+    pub sandbox_mode: bool,
+}
+
+/// Variables d'environnement que les réglages peuvent transmettre au worker
+const WORKER_ENV_ALLOWLIST: [&str; 7] = [
+    "HF_HOME",
+    "OLLAMA_HOST",
+    "OLLAMA_MODELS",
+    "OLLAMA_NO_GPU",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "NO_PROXY",
+];
+/// Préfixes des variables lues par l'interpréteur ou le chargeur (exécution de code au démarrage)
+const WORKER_ENV_FORBIDDEN_PREFIXES: [&str; 3] = ["PYTHON", "LD_", "DYLD_"];
+
+/// Options et variables d'environnement ajoutées au lancement du worker Python
+/// (ex: --debug, HF_HOME). Pris en compte au prochain démarrage du worker.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WorkerSettings {
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+}
+
+/// Provenance d'une valeur de configuration ("default" = valeur par défaut/compilée,
+/// "file" = fichier de configuration ou profil importé, "runtime" = modifiée pendant la session)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigSource {
+    Default,
+    File,
+    Runtime,
+}
+
+/// Provenance des valeurs modifiées depuis le démarrage (une clé absente vaut "default")
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSources(HashMap<&'static str, ConfigSource>);
+
+impl ConfigSources {
+    pub fn set(&mut self, key: &'static str, source: ConfigSource) {
+        self.0.insert(key, source);
+    }
+
+    /// Enregistre la provenance seulement si la valeur change
+    pub fn set_if_changed<T: PartialEq>(&mut self, key: &'static str, old: &T, new: &T, source: ConfigSource) {
+        if old != new {
+            self.set(key, source);
+        }
+    }
+
+    pub fn get(&self, key: &str) -> ConfigSource {
+        self.0.get(key).copied().unwrap_or(ConfigSource::Default)
+    }
+}
+
+impl WorkerSettings {
+    fn validate(&self) -> Result<(), String> {
+        for (key, value) in &self.env {
+            if key.is_empty() || key.contains('=') || key.contains('\0') || value.contains('\0') {
+                return Err(format!("Invalid worker environment variable: {:?}", key));
+            }
+            let upper = key.to_uppercase();
+            if WORKER_ENV_FORBIDDEN_PREFIXES.iter().any(|prefix| upper.starts_with(prefix)) {
+                return Err(format!("Worker environment variable {} is not allowed: it can run code at startup", key));
+            }
+            if !WORKER_ENV_ALLOWLIST.contains(&upper.as_str()) {
+                return Err(format!(
+                    "Worker environment variable {} is not allowed. Allowed variables: {:?}",
+                    key, WORKER_ENV_ALLOWLIST
+                ));
+            }
+        }
+        // Options du script uniquement (--nom ou --nom=valeur), passées après main.py
+        for arg in &self.args {
+            if arg.contains('\0') || !arg.starts_with("--") || arg.len() == 2 {
+                return Err(format!("Invalid worker argument: {:?}", arg));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Lit une section du fichier de réglages (None si le fichier ou la section est absent)
+pub(crate) fn read_settings_section<R: Runtime>(app: &AppHandle<R>, key: &str) -> Option<serde_json::Value> {
+    app.path()
+        .app_config_dir()
+        .ok()
+        .and_then(|dir| std::fs::read(dir.join(SETTINGS_FILE)).ok())
+        .and_then(|data| serde_json::from_slice::<serde_json::Value>(&data).ok())
+        .and_then(|raw| raw.get(key).cloned())
+}
+
+/// Enregistre une section du fichier de réglages (le reste du fichier est conservé)
+pub(crate) fn write_settings_section<R: Runtime>(
+    app: &AppHandle<R>,
+    key: &str,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    let dir = app.path().app_config_dir().map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(SETTINGS_FILE);
+
+    let mut raw = std::fs::read(&path)
+        .ok()
+        .and_then(|data| serde_json::from_slice::<serde_json::Value>(&data).ok())
+        .filter(|raw| raw.is_object())
+        .unwrap_or_else(|| serde_json::json!({}));
+    raw[key] = value;
+
+    let data = serde_json::to_vec_pretty(&raw).map_err(|e| e.to_string())?;
+    std::fs::write(&path, data).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+}
+
+/// Lit la section `worker` du fichier de réglages (réglages par défaut si absent ou invalide)
+pub(crate) fn load_worker_settings<R: Runtime>(app: &AppHandle<R>) -> WorkerSettings {
+    let settings = read_settings_section(app, "worker")
+        .and_then(|worker| serde_json::from_value::<WorkerSettings>(worker).ok())
+        .unwrap_or_default();
+
+    match settings.validate() {
+        Ok(()) => settings,
+        Err(e) => {
+            eprintln!("⚠️ Ignoring worker settings: {}", e);
+            WorkerSettings::default()
+        }
+    }
+}
+
+/// Enregistre la section `worker` dans le fichier de réglages
+fn save_worker_settings(app: &AppHandle<Wry>, worker: &WorkerSettings) -> Result<(), String> {
+    write_settings_section(app, "worker", serde_json::to_value(worker).map_err(|e| e.to_string())?)
+}
+
+/// Profil de réglages portable (la licence en est volontairement exclue)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsProfile {
+    pub schema_version: u32,
+    pub app_version: String,
+    pub exported_at: String,
+    pub context: ContextSettings,
+    pub permissions: PermissionSettings,
+    pub command_executor: CommandExecutorConfig,
+    #[serde(default)]
+    pub worker: WorkerSettings,
+}
+
+/// Met un profil au format courant. Chaque nouvelle version de schéma ajoute ici
+/// l'étape de migration depuis la précédente.
+fn migrate_settings(mut raw: serde_json::Value) -> Result<serde_json::Value, String> {
+    let version = raw
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .ok_or("Invalid settings profile: missing schema_version")? as u32;
+
+    if version == 0 || version > SETTINGS_SCHEMA_VERSION {
+        return Err(format!(
+            "Unsupported settings profile version {} (this app supports up to {})",
+            version, SETTINGS_SCHEMA_VERSION
+        ));
+    }
+
+    // Aucune migration nécessaire tant que seule la version 1 existe
+    raw["schema_version"] = serde_json::json!(SETTINGS_SCHEMA_VERSION);
+    Ok(raw)
+}
+
+/// Profil des réglages courants
+pub(crate) fn current_profile(
+    app: &AppHandle<Wry>,
+    context_state: &State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: &State<'_, Mutex<PermissionManager<Wry>>>,
+    executor_state: &State<'_, Mutex<CommandExecutor>>,
+) -> Result<SettingsProfile, String> {
+    let context = {
+        let context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        context_reader.get_config()
+    };
+    let permissions = {
+        let manager = permission_state.lock().map_err(|e| e.to_string())?;
+        PermissionSettings {
+            parano_mode: manager.is_parano_mode(),
+            sandbox_mode: manager.is_sandbox_mode(),
+        }
+    };
+    let command_executor = {
+        let executor = executor_state.lock().map_err(|e| e.to_string())?;
+        executor.get_config()
+    };
+
+    Ok(SettingsProfile {
+        schema_version: SETTINGS_SCHEMA_VERSION,
+        app_version: app.package_info().version.to_string(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        context: ContextSettings {
+            allowed_extensions: context.allowed_extensions,
+            max_file_size: context.max_file_size,
+            max_total_size: context.max_total_size,
+        },
+        permissions,
+        command_executor,
+        worker: load_worker_settings(app),
+    })
+}
+
+/// Exporte les réglages (extensions, limites, modes de permission, exécution de commandes)
+/// sous forme d'un JSON versionné
+#[tauri::command]
+pub async fn export_settings(
+    app: AppHandle<Wry>,
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    executor_state: State<'_, Mutex<CommandExecutor>>,
+) -> Result<SettingsProfile, String> {
+    current_profile(&app, &context_state, &permission_state, &executor_state)
+}
+
+/// Demande confirmation dans un dialogue natif (hors de portée de la webview)
+/// avant d'appliquer les changements d'un profil qui affaiblissent la sécurité
+async fn confirm_weakening_import(app: &AppHandle<Wry>, changes: &[String]) -> bool {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(format!(
+            "This settings profile will:\n\n- {}\n\nOnly import a profile you created yourself.",
+            changes.join("\n- ")
+        ))
+        .title("Import settings profile?")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom("Import".into(), "Cancel".into()))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+    rx.await.unwrap_or(false)
+}
+
+/// Importe un profil de réglages (migré si ancien). Tout est validé avant application :
+/// un profil invalide ne modifie rien. Le scope courant est conservé.
+/// Un profil peut activer les modes parano et sandbox sans confirmation ; les désactiver,
+/// modifier l'allowlist de commandes ou les réglages du worker doit être confirmé dans un dialogue natif.
+/// Les réglages du worker sont enregistrés et s'appliquent à son prochain démarrage.
+#[tauri::command]
+pub async fn import_settings(
+    app: AppHandle<Wry>,
+    context_state: State<'_, Mutex<ContextReader<Wry>>>,
+    permission_state: State<'_, Mutex<PermissionManager<Wry>>>,
+    executor_state: State<'_, Mutex<CommandExecutor>>,
+    settings: serde_json::Value,
+) -> Result<(), String> {
+    let profile: SettingsProfile = serde_json::from_value(migrate_settings(settings)?)
+        .map_err(|e| format!("Invalid settings profile: {}", e))?;
+
+    if profile.context.max_file_size == 0 || profile.context.max_total_size == 0 {
+        return Err("Invalid settings profile: size limits must be greater than 0".to_string());
+    }
+    let mut allowed_extensions: Vec<String> = Vec::new();
+    for extension in &profile.context.allowed_extensions {
+        let extension = extension.trim().trim_start_matches('.').to_lowercase();
+        if !extension.is_empty() && !allowed_extensions.contains(&extension) {
+            allowed_extensions.push(extension);
+        }
+    }
+
+    // Valider la configuration d'exécution avant de toucher à quoi que ce soit
+    let mut executor_candidate = CommandExecutor::new();
+    executor_candidate.apply_config(profile.command_executor.clone(), ConfigSource::File)?;
+    profile.worker.validate()?;
+
+    // Changements qui affaiblissent la sécurité : confirmés hors de la webview
+    let mut weakening = Vec::new();
+    {
+        let manager = permission_state.lock().map_err(|e| e.to_string())?;
+        if manager.is_parano_mode() && !profile.permissions.parano_mode {
+            weakening.push("turn paranoid mode off".to_string());
+        }
+        if manager.is_sandbox_mode() && !profile.permissions.sandbox_mode {
+            weakening.push("turn sandbox mode off".to_string());
+        }
+    }
+    {
+        let executor = executor_state.lock().map_err(|e| e.to_string())?;
+        let current = executor.get_config().allowed_programs;
+        if current != profile.command_executor.allowed_programs {
+            weakening.push(format!(
+                "replace the allowed commands {:?} with {:?}",
+                current, profile.command_executor.allowed_programs
+            ));
+        }
+    }
+    let current_worker = load_worker_settings(&app);
+    if current_worker.args != profile.worker.args || current_worker.env != profile.worker.env {
+        weakening.push(format!(
+            "change the worker startup options to {:?} and environment to {:?}",
+            profile.worker.args, profile.worker.env
+        ));
+    }
+    if !weakening.is_empty() && !confirm_weakening_import(&app, &weakening).await {
+        return Err("Settings import was not confirmed by the user".to_string());
+    }
+
+    save_worker_settings(&app, &profile.worker)?;
+
+    {
+        let mut context_reader = context_state.lock().map_err(|e| e.to_string())?;
+        let mut config = context_reader.get_config();
+        config.allowed_extensions = allowed_extensions;
+        config.max_file_size = profile.context.max_file_size;
+        config.max_total_size = profile.context.max_total_size;
+        context_reader.apply_config(config, ConfigSource::File);
+    }
+    {
+        let mut manager = permission_state.lock().map_err(|e| e.to_string())?;
+        manager.set_parano_mode(profile.permissions.parano_mode, ConfigSource::File);
+        manager.set_sandbox_mode(profile.permissions.sandbox_mode, ConfigSource::File);
+    }
+    {
+        // Déjà validée par executor_candidate
+        let mut executor = executor_state.lock().map_err(|e| e.to_string())?;
+        executor.apply_config(profile.command_executor, ConfigSource::File)?;
+    }
+
+    Ok(())
+}
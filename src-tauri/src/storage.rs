@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, State, Wry};
+
+use crate::licensing::store::LicenseStore;
+use crate::python_bridge::PythonBridge;
+
+/// Espace occupé par une catégorie de données de l'app
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageCategory {
+    pub category: String,  // logs | license | data | cache
+    pub paths: Vec<String>,
+    pub bytes: u64,
+}
+
+/// Répartition de l'espace disque utilisé par l'app
+#[derive(Debug, Clone, Serialize)]
+pub struct StorageUsage {
+    pub categories: Vec<StorageCategory>,
+    pub total_bytes: u64,
+}
+
+/// Taille d'un fichier ou d'un dossier (récursif, sans suivre les liens symboliques)
+/// Les chemins exclus (comptés dans une autre catégorie) sont ignorés
+fn path_size(path: &Path, excluded: &[PathBuf]) -> u64 {
+    if excluded.iter().any(|e| e == path) {
+        return 0;
+    }
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if metadata.is_file() {
+        return metadata.len();
+    }
+    if !metadata.is_dir() {
+        return 0;
+    }
+    fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|entry| path_size(&entry.path(), excluded)).sum())
+        .unwrap_or(0)
+}
+
+/// Construit une catégorie à partir des chemins existants
+fn category(name: &str, paths: Vec<PathBuf>, excluded: &[PathBuf]) -> StorageCategory {
+    let existing: Vec<PathBuf> = paths.into_iter().filter(|p| p.exists()).collect();
+    StorageCategory {
+        category: name.to_string(),
+        bytes: existing.iter().map(|p| path_size(p, excluded)).sum(),
+        paths: existing.iter().map(|p| p.to_string_lossy().into_owned()).collect(),
+    }
+}
+
+/// Retourne l'espace disque utilisé par l'app (logs, licence, données, cache)
+#[tauri::command]
+pub async fn get_app_storage_usage(
+    app: AppHandle<Wry>,
+    license_store: State<'_, LicenseStore>,
+    bridge: State<'_, PythonBridge<Wry>>,
+) -> Result<StorageUsage, String> {
+    let paths = app.path();
+    let log_dir: Vec<PathBuf> = paths.app_log_dir().into_iter().collect();
+    let data_dir: Vec<PathBuf> = paths.app_data_dir().into_iter().collect();
+
+    // Le worker écrit dans le dossier de logs de l'app, sauf si ses réglages en imposent un autre
+    let mut log_paths = log_dir.clone();
+    if let Some(worker_log) = bridge.worker_log_file() {
+        if !log_dir.iter().any(|dir| worker_log.starts_with(dir)) {
+            log_paths.push(worker_log);
+        }
+    }
+
+    // Selon l'OS, les logs sont dans le dossier de données (Linux) ou de cache (Windows) :
+    // ne pas les compter deux fois
+    let protected: Vec<PathBuf> = log_dir.iter().chain(data_dir.iter()).cloned().collect();
+    let categories = vec![
+        category("logs", log_paths, &[]),
+        category("license", vec![license_store.path().clone()], &[]),
+        category("data", data_dir, &log_dir),
+        category("cache", paths.app_cache_dir().into_iter().collect(), &protected),
+    ];
+    let total_bytes = categories.iter().map(|c| c.bytes).sum();
+
+    Ok(StorageUsage { categories, total_bytes })
+}
+
+/// Vide le cache de l'app (sans toucher aux logs ni aux données qui peuvent s'y trouver).
+/// Retourne le nombre d'octets libérés
+#[tauri::command]
+pub async fn clear_app_cache(app: AppHandle<Wry>) -> Result<u64, String> {
+    let paths = app.path();
+    let cache_dir = paths.app_cache_dir().map_err(|e| e.to_string())?;
+    if !cache_dir.exists() {
+        return Ok(0);
+    }
+    let protected: Vec<PathBuf> = paths
+        .app_log_dir()
+        .into_iter()
+        .chain(paths.app_data_dir())
+        .collect();
+    if protected.contains(&cache_dir) {
+        return Err("Cache directory is shared with app data, refusing to clear it".to_string());
+    }
+
+    let mut freed: u64 = 0;
+    let entries = fs::read_dir(&cache_dir)
+        .map_err(|e| format!("Failed to read cache directory {}: {}", cache_dir.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        // Ne jamais supprimer un dossier qui contient (ou est) les logs / données de l'app
+        if protected.iter().any(|p| p.starts_with(&path)) {
+            continue;
+        }
+        let size = path_size(&path, &[]);
+        let removed = if path.is_dir() {
+            fs::remove_dir_all(&path)
+        } else {
+            fs::remove_file(&path)
+        };
+        match removed {
+            Ok(()) => freed += size,
+            Err(e) => eprintln!("⚠️ Failed to remove cache entry {}: {}", path.display(), e),
+        }
+    }
+
+    Ok(freed)
+}
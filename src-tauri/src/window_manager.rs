@@ -1,5 +1,77 @@
-use tauri::{Manager, WebviewWindow, WebviewWindowBuilder, WebviewUrl, Emitter};
-use serde::{Deserialize, Serialize};
+use tauri::{Manager, Monitor, PhysicalPosition, PhysicalSize, WebviewWindow, WebviewWindowBuilder, WebviewUrl, Emitter};
+use serde::{Deserialize, Serialize, Serializer};
+use serde::ser::SerializeStruct;
+use std::fmt;
+
+/// Erreur structurée des commandes de fenêtre
+/// Sérialisée comme les autres erreurs du backend : { error, code, message }
+#[derive(Debug)]
+pub enum WindowError {
+    NotFound(String),
+    OsError(String),
+    InvalidMonitorIndex { index: usize, available: usize },
+    InvalidArgument(String),
+    Unsupported(String),
+}
+
+impl WindowError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            WindowError::NotFound(_) => "WINDOW_NOT_FOUND",
+            WindowError::OsError(_) => "WINDOW_OS_ERROR",
+            WindowError::InvalidMonitorIndex { .. } => "INVALID_MONITOR_INDEX",
+            WindowError::InvalidArgument(_) => "INVALID_ARGUMENT",
+            WindowError::Unsupported(_) => "UNSUPPORTED",
+        }
+    }
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowError::NotFound(window_id) => write!(f, "Window {} not found", window_id),
+            WindowError::OsError(message) => write!(f, "{}", message),
+            WindowError::InvalidMonitorIndex { index, available } => write!(
+                f,
+                "Invalid monitor index {} ({} monitor(s) available)",
+                index, available
+            ),
+            WindowError::InvalidArgument(message) => write!(f, "{}", message),
+            WindowError::Unsupported(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Serialize for WindowError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("WindowError", 3)?;
+        state.serialize_field("error", &true)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
+
+/// Convertit une erreur Tauri/OS avec le contexte de l'opération
+fn os_error(action: &str) -> impl FnOnce(tauri::Error) -> WindowError + '_ {
+    move |e| WindowError::OsError(format!("Failed to {}: {}", action, e))
+}
+
+/// Récupère une fenêtre par son label
+fn find_window(app: &tauri::AppHandle, window_id: &str) -> Result<WebviewWindow, WindowError> {
+    app.get_webview_window(window_id)
+        .ok_or_else(|| WindowError::NotFound(window_id.to_string()))
+}
+
+/// Récupère un écran par son index
+fn monitor_at(app: &tauri::AppHandle, index: usize) -> Result<Monitor, WindowError> {
+    let monitors = app.available_monitors().map_err(os_error("list monitors"))?;
+    let available = monitors.len();
+    monitors
+        .into_iter()
+        .nth(index)
+        .ok_or(WindowError::InvalidMonitorIndex { index, available })
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatWindowInfo {
@@ -9,13 +81,35 @@ pub struct ChatWindowInfo {
     pub title: String,
 }
 
+/// Taille logique par défaut d'une fenêtre de chat
+const CHAT_WINDOW_WIDTH: f64 = 1000.0;
+const CHAT_WINDOW_HEIGHT: f64 = 750.0;
+
+/// Calcule la position physique centrée d'une fenêtre de taille logique donnée sur un écran
+/// (tient compte du scale_factor propre à cet écran)
+fn centered_position_on(monitor: &Monitor, logical_width: f64, logical_height: f64) -> PhysicalPosition<i32> {
+    let scale_factor = monitor.scale_factor();
+    let width = (logical_width * scale_factor) as i32;
+    let height = (logical_height * scale_factor) as i32;
+    let monitor_size = monitor.size();
+    let monitor_pos = monitor.position();
+
+    PhysicalPosition::new(
+        monitor_pos.x + ((monitor_size.width as i32 - width) / 2).max(0),
+        monitor_pos.y + ((monitor_size.height as i32 - height) / 2).max(0),
+    )
+}
+
 /// Crée une nouvelle fenêtre de chat détachée
+/// Centrée sur l'écran `monitor_index` si fourni, sinon sur l'écran de la fenêtre appelante
 #[tauri::command]
 pub async fn create_chat_window(
     app: tauri::AppHandle,
+    parent: WebviewWindow,
     chat_id: Option<String>,
     model: Option<String>,
-) -> Result<ChatWindowInfo, String> {
+    monitor_index: Option<usize>,
+) -> Result<ChatWindowInfo, WindowError> {
     let window_id = format!("chat_{}", uuid::Uuid::new_v4().to_string().replace("-", ""));
     
     let title = if let Some(m) = &model {
@@ -24,21 +118,36 @@ pub async fn create_chat_window(
         "Nouveau Chat".to_string()
     };
 
+    // Écran cible : index explicite ou écran courant de la fenêtre appelante
+    let target_monitor = match monitor_index {
+        Some(index) => Some(monitor_at(&app, index)?),
+        None => parent.current_monitor().ok().flatten(),
+    };
+
+    // Créée masquée pour la positionner avant affichage (évite un saut visible)
     let window = WebviewWindowBuilder::new(
         &app,
         &window_id,
         WebviewUrl::App("index.html".into())
     )
     .title(&title)
-    .inner_size(1000.0, 750.0)
+    .inner_size(CHAT_WINDOW_WIDTH, CHAT_WINDOW_HEIGHT)
     .min_inner_size(500.0, 400.0)
     .max_inner_size(1920.0, 1080.0)
     .decorations(true)
     .resizable(true)
     .transparent(false)
-    .center()
+    .visible(false)
     .build()
-    .map_err(|e| format!("Failed to create window: {}", e))?;
+    .map_err(os_error("create window"))?;
+
+    match &target_monitor {
+        Some(monitor) => window
+            .set_position(centered_position_on(monitor, CHAT_WINDOW_WIDTH, CHAT_WINDOW_HEIGHT))
+            .map_err(os_error("position window"))?,
+        None => window.center().map_err(os_error("center window"))?,
+    }
+    window.show().map_err(os_error("show window"))?;
 
     // Envoyer les données initiales à la fenêtre
     window.emit("chat-window-init", serde_json::json!({
@@ -46,7 +155,7 @@ pub async fn create_chat_window(
         "model": model,
         "window_id": window_id.clone()
     }))
-    .map_err(|e| format!("Failed to emit init event: {}", e))?;
+    .map_err(os_error("emit init event"))?;
 
     Ok(ChatWindowInfo {
         window_id: window_id.clone(),
@@ -58,7 +167,7 @@ pub async fn create_chat_window(
 
 /// Liste toutes les fenêtres de chat ouvertes
 #[tauri::command]
-pub async fn list_chat_windows(app: tauri::AppHandle) -> Result<Vec<ChatWindowInfo>, String> {
+pub async fn list_chat_windows(app: tauri::AppHandle) -> Result<Vec<ChatWindowInfo>, WindowError> {
     let windows = app.webview_windows();
     let mut chat_windows = Vec::new();
 
@@ -84,13 +193,50 @@ pub async fn list_chat_windows(app: tauri::AppHandle) -> Result<Vec<ChatWindowIn
 pub async fn close_chat_window(
     app: tauri::AppHandle,
     window_id: String,
-) -> Result<(), String> {
-    if let Some(window) = app.get_webview_window(&window_id) {
-        window.close().map_err(|e| format!("Failed to close window: {}", e))?;
-        Ok(())
-    } else {
-        Err(format!("Window {} not found", window_id))
+) -> Result<(), WindowError> {
+    find_window(&app, &window_id)?
+        .close()
+        .map_err(os_error("close window"))
+}
+
+/// Recharge le webview d'une fenêtre de chat sans la recréer (conserve sa géométrie)
+#[tauri::command]
+pub async fn reload_chat_window(
+    app: tauri::AppHandle,
+    window_id: String,
+) -> Result<(), WindowError> {
+    find_window(&app, &window_id)?
+        .reload()
+        .map_err(os_error("reload window"))
+}
+
+/// Navigue une fenêtre de chat vers une route interne de l'application
+/// `path` est résolu par rapport à l'URL courante : toute autre origine est refusée
+#[tauri::command]
+pub async fn navigate_chat_window(
+    app: tauri::AppHandle,
+    window_id: String,
+    path: String,
+) -> Result<(), WindowError> {
+    let window = find_window(&app, &window_id)?;
+
+    let current = window.url().map_err(os_error("get window URL"))?;
+    let target = current
+        .join(&path)
+        .map_err(|e| WindowError::InvalidArgument(format!("Invalid navigation path {}: {}", path, e)))?;
+
+    // Comparer schéma/hôte/port : les schémas custom (tauri://) ont une origine opaque
+    let same_origin = target.scheme() == current.scheme()
+        && target.host_str() == current.host_str()
+        && target.port_or_known_default() == current.port_or_known_default();
+    if !same_origin {
+        return Err(WindowError::InvalidArgument(format!(
+            "Navigation outside the application is not allowed: {}",
+            target
+        )));
     }
+
+    window.navigate(target).map_err(os_error("navigate window"))
 }
 
 /// Met à jour le titre d'une fenêtre de chat
@@ -98,61 +244,245 @@ pub async fn close_chat_window(
 pub async fn update_chat_window_title(
     window: WebviewWindow,
     title: String,
-) -> Result<(), String> {
-    window.set_title(&title).map_err(|e| format!("Failed to update title: {}", e))?;
-    Ok(())
+) -> Result<(), WindowError> {
+    window.set_title(&title).map_err(os_error("update title"))
 }
 
-/// Déplace une fenêtre vers un écran spécifique (par index)
+/// Opacité minimale autorisée (évite une fenêtre totalement invisible et inaccessible)
+const MIN_WINDOW_OPACITY: f64 = 0.2;
+
+/// Application de l'opacité via les "layered windows" Win32 (user32)
+#[cfg(all(windows, target_pointer_width = "64"))]
+mod win_opacity {
+    const GWL_EXSTYLE: i32 = -20;
+    const WS_EX_LAYERED: isize = 0x0008_0000;
+    const LWA_ALPHA: u32 = 0x0000_0002;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetWindowLongPtrW(hwnd: isize, index: i32) -> isize;
+        fn SetWindowLongPtrW(hwnd: isize, index: i32, value: isize) -> isize;
+        fn SetLayeredWindowAttributes(hwnd: isize, color_key: u32, alpha: u8, flags: u32) -> i32;
+    }
+
+    pub fn set_opacity(hwnd: isize, opacity: f64) -> Result<(), super::WindowError> {
+        let alpha = (opacity * 255.0).round() as u8;
+        // SAFETY: hwnd provient de Tauri et reste valide tant que la fenêtre existe
+        unsafe {
+            let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE);
+            if ex_style & WS_EX_LAYERED == 0 {
+                SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style | WS_EX_LAYERED);
+            }
+            if SetLayeredWindowAttributes(hwnd, 0, alpha, LWA_ALPHA) == 0 {
+                return Err(super::WindowError::OsError(
+                    "Failed to set window opacity: SetLayeredWindowAttributes failed".to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Définit l'opacité d'une fenêtre de chat (0.2 à 1.0)
+/// Supporté sur Windows uniquement ; les autres plateformes retournent une erreur explicite
+#[tauri::command]
+pub async fn set_chat_window_opacity(
+    app: tauri::AppHandle,
+    window_id: String,
+    opacity: f64,
+) -> Result<(), WindowError> {
+    if !(MIN_WINDOW_OPACITY..=1.0).contains(&opacity) {
+        return Err(WindowError::InvalidArgument(format!(
+            "Opacity must be between {} and 1.0 (got {})",
+            MIN_WINDOW_OPACITY, opacity
+        )));
+    }
+
+    let window = find_window(&app, &window_id)?;
+
+    #[cfg(all(windows, target_pointer_width = "64"))]
+    {
+        let hwnd = window.hwnd().map_err(os_error("get window handle"))?;
+        win_opacity::set_opacity(hwnd.0 as isize, opacity)
+    }
+
+    #[cfg(not(all(windows, target_pointer_width = "64")))]
+    {
+        let _ = window;
+        Err(WindowError::Unsupported(format!(
+            "Window opacity is not supported on this platform ({})",
+            std::env::consts::OS
+        )))
+    }
+}
+
+/// Déplace une fenêtre vers un écran spécifique (par index), centrée sur cet écran
 #[tauri::command]
 pub async fn move_window_to_screen(
+    app: tauri::AppHandle,
     window: WebviewWindow,
-    _screen_index: usize,
-) -> Result<(), String> {
-    // Tauri 2.0 n'a pas encore d'API directe pour déplacer vers un écran spécifique
-    // On peut utiliser les coordonnées pour positionner la fenêtre
-    // Cette fonctionnalité nécessiterait des plugins supplémentaires ou des APIs système
-    
-    // Pour l'instant, on peut juste centrer la fenêtre
-    if let Some(monitor) = window.current_monitor().ok().flatten() {
-        let size = monitor.size();
-        let scale_factor = monitor.scale_factor();
-        let physical_size = window.inner_size().unwrap_or_default();
-        
-        let x = (size.width as f64 / scale_factor - physical_size.width as f64) / 2.0;
-        let y = (size.height as f64 / scale_factor - physical_size.height as f64) / 2.0;
-        
-        window.set_position(tauri::LogicalPosition::new(x, y))
-            .map_err(|e| format!("Failed to position window: {}", e))?;
+    screen_index: usize,
+) -> Result<(), WindowError> {
+    let target = monitor_at(&app, screen_index)?;
+
+    // Taille logique actuelle (indépendante du DPI de l'écran courant)
+    let current_scale = window.scale_factor().map_err(os_error("get scale factor"))?;
+    let outer_size = window.outer_size().map_err(os_error("get window size"))?;
+    let logical_width = outer_size.width as f64 / current_scale;
+    let logical_height = outer_size.height as f64 / current_scale;
+
+    window
+        .set_position(centered_position_on(&target, logical_width, logical_height))
+        .map_err(os_error("position window"))
+}
+
+/// Index de l'écran occupé par une fenêtre (comparaison position + taille, les Monitor n'ont pas d'identifiant)
+fn monitor_index_of(window: &WebviewWindow, monitors: &[Monitor]) -> Option<usize> {
+    let current = window.current_monitor().ok().flatten()?;
+    monitors.iter().position(|monitor| {
+        monitor.position() == current.position() && monitor.size() == current.size()
+    })
+}
+
+/// Zone d'ancrage d'une fenêtre sur son écran
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SnapRegion {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    Maximize,
+}
+
+impl SnapRegion {
+    /// Fractions (x, y, largeur, hauteur) de l'écran couvertes par la zone
+    fn fractions(self) -> (f64, f64, f64, f64) {
+        match self {
+            SnapRegion::Left => (0.0, 0.0, 0.5, 1.0),
+            SnapRegion::Right => (0.5, 0.0, 0.5, 1.0),
+            SnapRegion::Top => (0.0, 0.0, 1.0, 0.5),
+            SnapRegion::Bottom => (0.0, 0.5, 1.0, 0.5),
+            SnapRegion::TopLeft => (0.0, 0.0, 0.5, 0.5),
+            SnapRegion::TopRight => (0.5, 0.0, 0.5, 0.5),
+            SnapRegion::BottomLeft => (0.0, 0.5, 0.5, 0.5),
+            SnapRegion::BottomRight => (0.5, 0.5, 0.5, 0.5),
+            SnapRegion::Maximize => (0.0, 0.0, 1.0, 1.0),
+        }
     }
-    
-    Ok(())
+}
+
+/// Rectangle appliqué à une fenêtre (pixels physiques)
+#[derive(Debug, Clone, Serialize)]
+pub struct WindowRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+/// Ancre une fenêtre de chat sur une zone de son écran courant (moitié, quart ou plein écran)
+/// Retourne le rectangle appliqué
+#[tauri::command]
+pub async fn snap_chat_window(
+    app: tauri::AppHandle,
+    window_id: String,
+    region: SnapRegion,
+) -> Result<WindowRect, WindowError> {
+    let window = find_window(&app, &window_id)?;
+    let monitor = match window.current_monitor().map_err(os_error("get current monitor"))? {
+        Some(monitor) => monitor,
+        None => window
+            .primary_monitor()
+            .map_err(os_error("get primary monitor"))?
+            .ok_or_else(|| WindowError::OsError("No monitor available".to_string()))?,
+    };
+
+    let (fx, fy, fw, fh) = region.fractions();
+    let monitor_size = monitor.size();
+    let monitor_pos = monitor.position();
+    let rect = WindowRect {
+        x: monitor_pos.x + (monitor_size.width as f64 * fx) as i32,
+        y: monitor_pos.y + (monitor_size.height as f64 * fy) as i32,
+        width: (monitor_size.width as f64 * fw) as u32,
+        height: (monitor_size.height as f64 * fh) as u32,
+        scale_factor: monitor.scale_factor(),
+    };
+
+    // Une fenêtre maximisée ignorerait la nouvelle géométrie
+    if window.is_maximized().map_err(os_error("get window state"))? {
+        window.unmaximize().map_err(os_error("unmaximize window"))?;
+    }
+    window
+        .set_position(PhysicalPosition::new(rect.x, rect.y))
+        .map_err(os_error("position window"))?;
+    window
+        .set_size(PhysicalSize::new(rect.width, rect.height))
+        .map_err(os_error("resize window"))?;
+
+    Ok(rect)
 }
 
 /// Obtient les informations sur tous les écrans disponibles
+/// `window_label` désigne la fenêtre de référence (sinon n'importe quelle fenêtre ouverte) ;
+/// chaque écran indique les fenêtres de chat qu'il contient et s'il porte la fenêtre de référence
 #[tauri::command]
-pub async fn get_available_screens(app: tauri::AppHandle) -> Result<Vec<serde_json::Value>, String> {
-    let mut screens = Vec::new();
-    
-    if let Some(window) = app.get_webview_window("main") {
-        if let Some(monitors) = window.available_monitors().ok() {
-            for (index, monitor) in monitors.iter().enumerate() {
-                screens.push(serde_json::json!({
-                    "index": index,
-                    "name": monitor.name().map(|s| s.clone()).unwrap_or_else(|| format!("Screen {}", index)),
-                    "size": {
-                        "width": monitor.size().width,
-                        "height": monitor.size().height
-                    },
-                    "scale_factor": monitor.scale_factor(),
-                    "position": {
-                        "x": monitor.position().x,
-                        "y": monitor.position().y
-                    }
-                }));
-            }
+pub async fn get_available_screens(
+    app: tauri::AppHandle,
+    window_label: Option<String>,
+) -> Result<Vec<serde_json::Value>, WindowError> {
+    let reference = match window_label {
+        Some(label) => Some(find_window(&app, &label)?),
+        None => app
+            .get_webview_window("main")
+            .or_else(|| app.webview_windows().into_values().next()),
+    };
+    let Some(reference) = reference else {
+        return Ok(Vec::new());
+    };
+
+    let monitors = reference.available_monitors().map_err(os_error("list monitors"))?;
+    let current_index = monitor_index_of(&reference, &monitors);
+
+    let mut chat_windows_by_monitor: Vec<Vec<String>> = vec![Vec::new(); monitors.len()];
+    let mut chat_windows: Vec<(String, WebviewWindow)> = app
+        .webview_windows()
+        .into_iter()
+        .filter(|(label, _)| label.starts_with("chat_"))
+        .collect();
+    chat_windows.sort_by(|a, b| a.0.cmp(&b.0));
+    for (label, window) in chat_windows {
+        if let Some(index) = monitor_index_of(&window, &monitors) {
+            chat_windows_by_monitor[index].push(label);
         }
     }
-    
+
+    let screens = monitors
+        .iter()
+        .zip(chat_windows_by_monitor)
+        .enumerate()
+        .map(|(index, (monitor, chat_windows))| {
+            serde_json::json!({
+                "index": index,
+                "name": monitor.name().cloned().unwrap_or_else(|| format!("Screen {}", index)),
+                "size": {
+                    "width": monitor.size().width,
+                    "height": monitor.size().height
+                },
+                "scale_factor": monitor.scale_factor(),
+                "position": {
+                    "x": monitor.position().x,
+                    "y": monitor.position().y
+                },
+                "is_current": current_index == Some(index),
+                "chat_windows": chat_windows
+            })
+        })
+        .collect();
+
     Ok(screens)
 }
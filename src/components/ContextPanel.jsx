@@ -178,7 +178,7 @@ const ContextPanel = ({ language = 'fr', isDarkMode = true }) => {
     try {
       setLoading(true);
       setError(null);
-      const scannedFiles = await ContextService.scanDirectory(config.currentScope, false);
+      const { files: scannedFiles } = await ContextService.scanDirectory(config.currentScope, false);
       setFiles(scannedFiles);
     } catch (err) {
       console.error('Failed to scan files:', err);
@@ -197,6 +197,9 @@ const OllamaSetup = ({ onComplete, language = 'fr' }) => {
         if (status === 'downloading') {
           setProgress(30);
           setSubMessage(language === 'fr' ? 'Téléchargement en cours...' : 'Downloading...');
+        } else if (status === 'resuming') {
+          setProgress(30);
+          setSubMessage(language === 'fr' ? 'Reprise du téléchargement...' : 'Resuming download...');
         } else if (status === 'installing') {
           setProgress(60);
           setSubMessage(language === 'fr' ? 'Installation en cours...' : 'Installing...');
@@ -37,7 +37,14 @@ function createErrorObject(code, message, cmd, originalError = null) {
   };
 }
 
-export async function requestWorker(cmd, payload = {}, timeoutMs = 30000) {
+/**
+ * Envoie une commande au worker Python
+ * @param {string} cmd - Commande
+ * @param {object} payload - Paramètres
+ * @param {number} timeoutMs - Timeout côté UI
+ * @param {string|null} requestId - Id de requête (optionnel) pour corréler les événements "python-stream"
+ */
+export async function requestWorker(cmd, payload = {}, timeoutMs = 30000, requestId = null) {
   try {
     // 🔧 CORRECTION URGENTE : Timeout pour éviter freeze UI
     const timeoutPromise = new Promise((_, reject) => {
@@ -46,10 +53,19 @@ export async function requestWorker(cmd, payload = {}, timeoutMs = 30000) {
       }, timeoutMs);
     });
 
-    const requestPromise = invoke("call_python", { cmd, payload });
+    const requestPromise = invoke("call_python", {
+      cmd,
+      payload,
+      ...(requestId && { requestId })
+    });
 
     // Race entre la requête et le timeout
-    const response = await Promise.race([requestPromise, timeoutPromise]);
+    let response = await Promise.race([requestPromise, timeoutPromise]);
+
+    // Rust renvoie { request_id, data } : ne garder que les données
+    if (response && typeof response === "object" && "request_id" in response && "data" in response) {
+      response = response.data;
+    }
 
     if (!response) {
       logger.warn(`Empty response for command: ${cmd}`);
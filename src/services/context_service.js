@@ -18,10 +18,116 @@ class ContextService {
         }
     }
 
+    /**
+     * Découpe un fichier en morceaux ordonnés (frontières de ligne/fonction) avec recouvrement
+     * @param {string} filePath - Chemin du fichier
+     * @param {number} maxChunkBytes - Taille maximale d'un morceau (octets, min 256)
+     * @param {number|null} overlap - Nombre de lignes reprises du morceau précédent (défaut: 2)
+     * @returns {Promise<{path: string, total_lines: number, chunks: Array<{index: number, start_line: number, end_line: number, content: string}>}>}
+     */
+    static async chunkFile(filePath, maxChunkBytes, overlap = null) {
+        try {
+            return await invoke('chunk_file', { filePath, maxChunkBytes, overlap });
+        } catch (error) {
+            console.error('Failed to chunk file:', error);
+            throw new Error(`Failed to chunk file: ${error.message}`);
+        }
+    }
+
+    /**
+     * Exporte les fichiers lus récemment dans un manifeste JSON (chemin, taille, sha256, date d'accès)
+     * @param {string} path - Fichier de destination
+     * @returns {Promise<{version: number, created_at: string, scope: string|null, files: Array, missing: string[]}>}
+     */
+    static async exportContextManifest(path) {
+        try {
+            return await invoke('export_context_manifest', { path });
+        } catch (error) {
+            console.error('Failed to export context manifest:', error);
+            throw new Error(`Failed to export context manifest: ${error.message}`);
+        }
+    }
+
+    /**
+     * Relit les fichiers d'un manifeste de contexte (scope et permissions respectés)
+     * @param {string} path - Manifeste exporté par exportContextManifest
+     * @returns {Promise<{files: Array, changed: string[], failed: Array<{path: string, error: string}>, not_read: string[]}>}
+     */
+    static async loadContextManifest(path) {
+        try {
+            return await invoke('load_context_manifest', { path });
+        } catch (error) {
+            console.error('Failed to load context manifest:', error);
+            throw new Error(`Failed to load context manifest: ${error.message}`);
+        }
+    }
+
+    /**
+     * Métadonnées d'un fichier sans son contenu
+     * @param {string} filePath - Chemin du fichier
+     * @returns {Promise<{path: string, size_bytes: number, line_count: number, extension: string, mime_type: string, modified_at: string|null}>}
+     */
+    static async getFileStats(filePath) {
+        try {
+            return await invoke('get_file_stats', { filePath });
+        } catch (error) {
+            console.error('Failed to get file stats:', error);
+            throw new Error(`Failed to get file stats: ${error.message}`);
+        }
+    }
+
+    /**
+     * Lit les lignes ajoutées à un fichier depuis un offset (suivi de logs en direct)
+     * @param {string} filePath - Chemin du fichier
+     * @param {number} byteOffset - end_offset retourné par l'appel précédent (0 au départ)
+     * @returns {Promise<{path: string, content: string, start_offset: number, end_offset: number, file_size: number, reset: boolean}>}
+     *   - reset est true si le fichier a été tronqué/remplacé (la lecture reprend depuis le début)
+     */
+    static async readFileFromOffset(filePath, byteOffset = 0) {
+        try {
+            return await invoke('read_file_from_offset', { filePath, byteOffset });
+        } catch (error) {
+            console.error('Failed to read file from offset:', error);
+            throw new Error(`Failed to read file from offset: ${error.message}`);
+        }
+    }
+
+    /**
+     * Lit le début d'un fichier tenant dans un budget de tokens estimé (ratio chars_per_token de la config)
+     * @param {string} filePath - Chemin du fichier
+     * @param {number} maxTokens - Budget de tokens
+     * @returns {Promise<{path: string, content: string, size: number, max_tokens: number, tokens_estimated: number, chars_per_token: number, truncated: boolean}>}
+     */
+    static async readFileByTokenBudget(filePath, maxTokens) {
+        try {
+            return await invoke('read_file_by_token_budget', { filePath, maxTokens });
+        } catch (error) {
+            console.error('Failed to read file by token budget:', error);
+            throw new Error(`Failed to read file by token budget: ${error.message}`);
+        }
+    }
+
+    /**
+     * Pré-vérifie la lecture d'un fichier (scope, extension, taille, permission) sans le lire
+     * ni consommer la permission FileRead
+     * @param {string} filePath - Chemin du fichier
+     * @returns {Promise<{readable: boolean, checks: Array<{check: string, passed: boolean, error: string|null}>, permission_held: boolean, sandboxed: boolean}>}
+     */
+    static async canReadFile(filePath) {
+        try {
+            return await invoke('can_read_file', { filePath });
+        } catch (error) {
+            console.error('Failed to check file readability:', error);
+            throw new Error(`Failed to check file readability: ${error.message}`);
+        }
+    }
+
     /**
      * Lit plusieurs fichiers
      * @param {Array<string>} filePaths - Liste de chemins de fichiers
-     * @returns {Promise<Array<Object>>} - Liste des contenus de fichiers
+     * @returns {Promise<{files: Array<Object>, total_size: number, truncated: boolean, not_read: Array<string>, duplicates: Array<{path: string, duplicate_of: string}>}>}
+     *   - Contenus lus ; si la taille cumulée maximale est atteinte, les fichiers restants sont listés dans not_read
+     *   - Les chemins désignant un fichier déjà demandé ne sont lus qu'une fois et listés dans duplicates
      */
     static async readMultipleFiles(filePaths) {
         try {
@@ -36,13 +142,15 @@ class ContextService {
      * Scanne un répertoire pour lister les fichiers
      * @param {string} directoryPath - Chemin du répertoire
      * @param {boolean} recursive - Si vrai, scan récursif
-     * @returns {Promise<Array<string>>} - Liste des chemins de fichiers
+     * @param {string|null} requestId - Id permettant d'annuler le scan via cancelScan (optionnel)
+     * @returns {Promise<{files: Array<string>, cancelled: boolean}>} - Fichiers trouvés (partiels si annulé)
      */
-    static async scanDirectory(directoryPath, recursive = false) {
+    static async scanDirectory(directoryPath, recursive = false, requestId = null) {
         try {
             return await invoke('scan_directory', {
                 directoryPath,
-                recursive
+                recursive,
+                requestId
             });
         } catch (error) {
             console.error('Failed to scan directory:', error);
@@ -50,6 +158,109 @@ class ContextService {
         }
     }
 
+    /**
+     * Scan récursif progressif : les fichiers arrivent par lots via l'événement "scan-entry"
+     * ({request_id, files}), puis "scan-complete" ({request_id, total, truncated, cancelled})
+     * @param {string} directoryPath - Dossier à scanner
+     * @param {string} requestId - Id de corrélation des événements (et pour cancelScan)
+     * @param {number|null} maxFiles - Nombre maximal de fichiers (optionnel)
+     * @returns {Promise<{request_id: string, total: number, truncated: boolean, cancelled: boolean}>}
+     */
+    static async scanDirectoryStream(directoryPath, requestId, maxFiles = null) {
+        try {
+            return await invoke('scan_directory_stream', { directoryPath, requestId, maxFiles });
+        } catch (error) {
+            console.error('Failed to stream directory scan:', error);
+            throw new Error(`Failed to stream directory scan: ${error.message}`);
+        }
+    }
+
+    /**
+     * Transforme une sélection de fichiers et dossiers en liste plate de fichiers lisibles (dédupliquée)
+     * @param {string[]} paths - Fichiers et dossiers sélectionnés
+     * @param {boolean} recursive - Développer les sous-dossiers (défaut: true)
+     * @returns {Promise<{files: Array<{path: string, size: number}>, total_size: number, skipped: Array<{path: string, reason: string}>, truncated: boolean}>}
+     */
+    static async resolveContextSelection(paths, recursive = true) {
+        try {
+            return await invoke('resolve_context_selection', { paths, recursive });
+        } catch (error) {
+            console.error('Failed to resolve context selection:', error);
+            throw new Error(`Failed to resolve context selection: ${error.message}`);
+        }
+    }
+
+    /**
+     * Annule un scan en cours
+     * @param {string} requestId - Id passé à scanDirectory
+     * @returns {Promise<boolean>} - false si aucun scan ne correspond
+     */
+    static async cancelScan(requestId) {
+        try {
+            return await invoke('cancel_scan', { requestId });
+        } catch (error) {
+            console.error('Failed to cancel scan:', error);
+            throw new Error(`Failed to cancel scan: ${error.message}`);
+        }
+    }
+
+    /**
+     * Répartition du scope courant par extension (triée par volume, nécessite RepoAnalyze)
+     * @param {string|null} requestId - Id optionnel pour annuler via cancelScan
+     * @returns {Promise<{root: string, extensions: Array<{extension: string, file_count: number, total_bytes: number}>, cancelled: boolean}>}
+     */
+    static async scopeExtensionBreakdown(requestId = null) {
+        try {
+            return await invoke('scope_extension_breakdown', { requestId });
+        } catch (error) {
+            console.error('Failed to compute extension breakdown:', error);
+            throw new Error(`Failed to compute extension breakdown: ${error.message}`);
+        }
+    }
+
+    /**
+     * Recherche les fichiers identiques du scope courant (nécessite RepoAnalyze)
+     * @param {string|null} requestId - Id optionnel pour annuler via cancelScan
+     * @returns {Promise<{root: string, groups: Array<{sha256: string, size: number, paths: string[]}>, wasted_bytes: number, cancelled: boolean}>}
+     */
+    static async findDuplicateFiles(requestId = null) {
+        try {
+            return await invoke('find_duplicate_files', { requestId });
+        } catch (error) {
+            console.error('Failed to find duplicate files:', error);
+            throw new Error(`Failed to find duplicate files: ${error.message}`);
+        }
+    }
+
+    /**
+     * Graphe des imports entre fichiers du scope (Python, JS/TS, Rust), nécessite RepoAnalyze
+     * Mis en cache côté backend tant qu'aucun fichier du scope n'a changé
+     * @param {string|null} requestId - Id optionnel pour annuler via cancelScan (graphe partiel, non mis en cache)
+     * @returns {Promise<{root: string, file_count: number, edge_count: number, dependencies: Object<string, string[]>, external: Object<string, string[]>, cached: boolean, cancelled: boolean}>}
+     */
+    static async analyzeDependencies(requestId = null) {
+        try {
+            return await invoke('analyze_dependencies', { requestId });
+        } catch (error) {
+            console.error('Failed to analyze dependencies:', error);
+            throw new Error(`Failed to analyze dependencies: ${error.message}`);
+        }
+    }
+
+    /**
+     * Fichiers du scope modifiés depuis une référence git (revue de branche), nécessite RepoAnalyze et CommandExecute
+     * @param {string} gitRef - Référence git (ex: 'main', 'HEAD~3')
+     * @returns {Promise<{git_ref: string, is_git_repo: boolean, files: Array<{path: string, change: string, old_path?: string}>, filtered_out: number, truncated: boolean}>}
+     */
+    static async listChangedFiles(gitRef) {
+        try {
+            return await invoke('list_changed_files', { gitRef });
+        } catch (error) {
+            console.error('Failed to list changed files:', error);
+            throw new Error(`Failed to list changed files: ${error.message}`);
+        }
+    }
+
     /**
      * Obtient la configuration actuelle du contexte
      * @returns {Promise<Object>} - Configuration du contexte
@@ -63,20 +274,101 @@ class ContextService {
         }
     }
 
+    /**
+     * Obtient la validité (minutes) des tokens de confirmation de lecture
+     * @returns {Promise<number>}
+     */
+    static async getConfirmationTtl() {
+        try {
+            return await invoke('get_confirmation_ttl');
+        } catch (error) {
+            console.error('Failed to get confirmation TTL:', error);
+            throw new Error(`Failed to get confirmation TTL: ${error.message}`);
+        }
+    }
+
+    /**
+     * Modifie la validité des tokens de confirmation (1 à 60 minutes)
+     * @param {number} minutes - Durée de validité des prochains tokens
+     * @returns {Promise<void>}
+     */
+    static async setConfirmationTtl(minutes) {
+        try {
+            await invoke('set_confirmation_ttl', { minutes });
+        } catch (error) {
+            console.error('Failed to set confirmation TTL:', error);
+            throw new Error(`Failed to set confirmation TTL: ${error.message}`);
+        }
+    }
+
+    /**
+     * Liste les tokens de confirmation en attente (chemins et expirations, sans les valeurs)
+     * @returns {Promise<Array<{path: string, purpose: 'read'|'delete'|'remote', expires_at: string, expired: boolean, has_content_hash: boolean}>>}
+     */
+    static async getPendingConfirmationTokens() {
+        try {
+            return await invoke('get_pending_confirmation_tokens');
+        } catch (error) {
+            console.error('Failed to get pending confirmation tokens:', error);
+            throw new Error(`Failed to get pending confirmation tokens: ${error.message}`);
+        }
+    }
+
+    /**
+     * Supprime tous les tokens de confirmation
+     * @returns {Promise<number>} - Nombre de tokens supprimés
+     */
+    static async clearConfirmationTokens() {
+        try {
+            return await invoke('clear_confirmation_tokens');
+        } catch (error) {
+            console.error('Failed to clear confirmation tokens:', error);
+            throw new Error(`Failed to clear confirmation tokens: ${error.message}`);
+        }
+    }
+
     /**
      * Définit le scope de travail (dossier de projet)
      * @param {string} scopePath - Chemin du dossier de projet
-     * @returns {Promise<void>}
+     * @param {boolean} windowOnly - Si true, le scope ne s'applique qu'à la fenêtre courante
+     * @returns {Promise<boolean>} false si ce scope était déjà en vigueur (aucun "scope-changed" émis)
      */
-    static async setContextScope(scopePath) {
+    static async setContextScope(scopePath, windowOnly = false) {
         try {
-            await invoke('set_context_scope', { scopePath });
+            return await invoke('set_context_scope', { scopePath, windowOnly });
         } catch (error) {
             console.error('Failed to set context scope:', error);
             throw new Error(`Failed to set context scope: ${error.message}`);
         }
     }
 
+    /**
+     * Indique si un chemin est dans le scope courant (sans tenter de lecture)
+     * @param {string} path - Chemin à tester
+     * @returns {Promise<{in_scope: boolean, scope: string|null, reason: string|null}>}
+     */
+    static async isPathInScope(path) {
+        try {
+            return await invoke('is_path_in_scope', { path });
+        } catch (error) {
+            console.error('Failed to check path scope:', error);
+            throw new Error(`Failed to check path scope: ${error.message}`);
+        }
+    }
+
+    /**
+     * Supprime le scope propre à la fenêtre courante (retour au scope partagé)
+     * @returns {Promise<boolean>} - true si la fenêtre avait son propre scope
+     */
+    static async clearWindowScope() {
+        try {
+            return await invoke('clear_window_scope');
+        } catch (error) {
+            console.error('Failed to clear window scope:', error);
+            throw new Error(`Failed to clear window scope: ${error.message}`);
+        }
+    }
+
     /**
      * Obtient une preview d'un fichier (V2: retourne preview + token de confirmation)
      * @param {string} filePath - Chemin du fichier
@@ -97,6 +389,24 @@ class ContextService {
         }
     }
 
+    /**
+     * Obtient les previews de plusieurs fichiers en un seul appel
+     * @param {Array<string>} paths - Chemins des fichiers
+     * @param {number} maxLines - Nombre maximum de lignes par preview (défaut: 50)
+     * @returns {Promise<Object>} - {previews: [{path, preview} | {path, error}], confirmation_tokens: {path: token}}
+     */
+    static async getFilePreviews(paths, maxLines = 50) {
+        try {
+            return await invoke('get_file_previews', {
+                paths,
+                maxLines
+            });
+        } catch (error) {
+            console.error('Failed to get file previews:', error);
+            throw new Error(`Failed to get file previews: ${error.message}`);
+        }
+    }
+
     /**
      * Lit un fichier complet après confirmation (V2)
      * @param {string} filePath - Chemin du fichier
@@ -115,6 +425,36 @@ class ContextService {
         }
     }
 
+    /**
+     * Prépare la suppression d'un fichier du scope (aucune modification)
+     * @param {string} filePath - Chemin du fichier
+     * @returns {Promise<{path: string, confirmation_token: string}>}
+     */
+    static async prepareDeleteFile(filePath) {
+        try {
+            return await invoke('prepare_delete_file', { filePath });
+        } catch (error) {
+            console.error('Failed to prepare file deletion:', error);
+            throw new Error(`Failed to prepare file deletion: ${error.message}`);
+        }
+    }
+
+    /**
+     * Supprime un fichier après confirmation (nécessite FileWrite)
+     * Le fichier est déplacé dans la corbeille de l'app, il reste récupérable
+     * @param {string} filePath - Chemin du fichier
+     * @param {string} confirmationToken - Token obtenu via prepareDeleteFile
+     * @returns {Promise<{path: string, trash_path: string, size: number, sha256: string, deleted_at: string}>}
+     */
+    static async deleteFile(filePath, confirmationToken) {
+        try {
+            return await invoke('delete_file', { filePath, confirmationToken });
+        } catch (error) {
+            console.error('Failed to delete file:', error);
+            throw new Error(`Failed to delete file: ${error.message}`);
+        }
+    }
+
     /**
      * Met à jour la configuration du contexte
      * @param {Object} config - Nouvelle configuration
@@ -129,6 +469,21 @@ class ContextService {
         }
     }
 
+    /**
+     * Charge une configuration de contexte de projet (ex: .horizon/context.toml)
+     * @param {string} path - Chemin du fichier TOML
+     * @param {boolean} watch - Recharger automatiquement à chaque modification
+     * @returns {Promise<Object>} - Changements appliqués (scope_changed, extensions_changed, ...)
+     */
+    static async loadContextConfigFromFile(path, watch = false) {
+        try {
+            return await invoke('load_context_config_from_file', { path, watch });
+        } catch (error) {
+            console.error('Failed to load context config file:', error);
+            throw new Error(`Failed to load context config file: ${error.message}`);
+        }
+    }
+
     /**
      * Ajoute une extension autorisée
      * @param {string} extension - Extension à ajouter (sans le point)
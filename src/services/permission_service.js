@@ -101,6 +101,44 @@ class PermissionService {
         }
     }
 
+    /**
+     * Décrit une permission et les commandes qu'elle débloque
+     * @param {string} permission - Type de permission (ex: 'FileRead')
+     * @returns {Promise<{description: string, commands: string[]}|null>}
+     */
+    static async describePermission(permission) {
+        try {
+            const result = await invoke('describe_permission', { permission });
+            return result.error ? null : result;
+        } catch (error) {
+            console.error('Failed to describe permission:', error);
+            return null;
+        }
+    }
+
+    /**
+     * Déclare une permission personnalisée (plugin) pour la session courante
+     * @param {string} name - Nom de la permission (lettres, chiffres, '_', '-', '.', ':')
+     * @returns {Promise<boolean>} - false si elle était déjà déclarée
+     */
+    static async registerCustomPermission(name) {
+        const result = await invoke('register_custom_permission', { name });
+        return result.registered;
+    }
+
+    /**
+     * Liste les permissions intégrées et personnalisées
+     * @returns {Promise<Array<{permission: string, custom: boolean, description: string, commands: string[], granted: boolean}>>}
+     */
+    static async listPermissions() {
+        try {
+            return await invoke('list_permissions');
+        } catch (error) {
+            console.error('Failed to list permissions:', error);
+            return [];
+        }
+    }
+
     /**
      * Récupère les logs d'audit des permissions
      * @returns {Promise<Array>} - Liste des logs d'audit
@@ -130,17 +168,79 @@ class PermissionService {
     /**
      * Exporte les logs d'audit vers un fichier
      * @param {string} path - Chemin du fichier de destination
+     * @param {{since?: string, until?: string, permissions?: string[]}} [filters] - Période (RFC 3339) et permissions à exporter
      * @returns {Promise<void>}
      */
-    static async exportPermissionLogs(path) {
+    static async exportPermissionLogs(path, filters = null) {
         try {
-            await invoke('export_permission_logs', { path });
+            await invoke('export_permission_logs', filters ? { path, ...filters } : { path });
         } catch (error) {
             console.error('Failed to export permission logs:', error);
             throw error;
         }
     }
 
+    /**
+     * Déplace le journal d'audit dans un dossier choisi (null = dossier de logs par défaut)
+     * @param {string|null} path - Dossier de destination (doit exister et être inscriptible)
+     * @returns {Promise<string>} - Nouveau chemin du fichier d'audit
+     */
+    static async setAuditLogPath(path) {
+        try {
+            const result = await invoke('set_audit_log_path', { path });
+            return result.path;
+        } catch (error) {
+            console.error('Failed to set audit log path:', error);
+            throw error;
+        }
+    }
+
+    /**
+     * Modifie le nombre de logs d'audit gardés en mémoire (le fichier d'audit reste complet)
+     * @param {number} maxEntries - Nombre d'entrées récentes conservées
+     * @returns {Promise<void>}
+     */
+    static async setAuditLogRetention(maxEntries) {
+        try {
+            await invoke('set_audit_log_retention', { maxEntries });
+        } catch (error) {
+            console.error('Failed to set audit log retention:', error);
+            throw error;
+        }
+    }
+
+    /**
+     * Exporte l'état complet des permissions (reproduction de bugs)
+     * @returns {Promise<Object>} - Instantané {granted_permissions, parano_mode, sandbox_mode, exported_at}
+     */
+    static async exportPermissionState() {
+        try {
+            return await invoke('export_permission_state');
+        } catch (error) {
+            console.error('Failed to export permission state:', error);
+            throw error;
+        }
+    }
+
+    /**
+     * Remplace l'état des permissions par un instantané (contourne le consentement : l'utilisateur
+     * doit confirmer dans un dialogue natif). Les modes parano/sandbox ne peuvent pas être désactivés.
+     * @param {Object} snapshot - Instantané issu de exportPermissionState
+     * @returns {Promise<number>} - Nombre d'entrées importées
+     */
+    static async importPermissionState(snapshot) {
+        try {
+            const result = await invoke('import_permission_state', { snapshot });
+            if (result?.error) {
+                throw new Error(result.message);
+            }
+            return result.imported_count;
+        } catch (error) {
+            console.error('Failed to import permission state:', error);
+            throw error;
+        }
+    }
+
     /**
      * Récupère l'état du mode parano
      * @returns {Promise<boolean>} - True si mode parano activé
@@ -67,11 +67,14 @@ export class WindowService {
 
   /**
    * Obtient les écrans disponibles
+   * Chaque écran indique `is_current` (écran de la fenêtre de référence)
+   * et `chat_windows` (labels des fenêtres de chat qu'il contient)
+   * @param {string|null} windowLabel - Fenêtre de référence (optionnel)
    * @returns {Promise<Array>} Liste des écrans
    */
-  static async getAvailableScreens() {
+  static async getAvailableScreens(windowLabel = null) {
     try {
-      const screens = await invoke('get_available_screens');
+      const screens = await invoke('get_available_screens', { windowLabel });
       return screens;
     } catch (error) {
       console.error('Failed to get available screens:', error);
@@ -92,6 +95,21 @@ export class WindowService {
     }
   }
 
+  /**
+   * Ancre une fenêtre de chat sur une zone de son écran
+   * @param {string} windowId - Label de la fenêtre
+   * @param {string} region - Left, Right, Top, Bottom, TopLeft, TopRight, BottomLeft, BottomRight ou Maximize
+   * @returns {Promise<Object>} Rectangle appliqué { x, y, width, height, scale_factor }
+   */
+  static async snapChatWindow(windowId, region) {
+    try {
+      return await invoke('snap_chat_window', { windowId, region });
+    } catch (error) {
+      console.error('Failed to snap window:', error);
+      throw error;
+    }
+  }
+
   /**
    * Écoute les événements d'initialisation de fenêtre
    * @param {Function} callback - Callback appelé avec les données d'initialisation
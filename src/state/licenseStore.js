@@ -1,4 +1,5 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 export const defaultLicenseState = {
   plan: "free",
@@ -9,6 +10,7 @@ export const defaultLicenseState = {
   grace_days: null,
   device_fingerprint: null,
   error: null,
+  trial_started_at: null,
 };
 
 const REDUCE_ANIM_KEY = "horizon.reduceAnimations";
@@ -48,6 +50,38 @@ export async function activateLicense(licenseKey) {
   }
 }
 
+// Activation hors ligne : import d'un entitlement signé (JWS) fourni par l'éditeur
+export async function importLicenseJws(jws) {
+  if (!jws || !jws.trim()) {
+    return { ...defaultLicenseState, error: "missing_jws" };
+  }
+  try {
+    const resp = await invoke("license_import_jws", { jws: jws.trim() });
+    if (resp?.status) return resp.status;
+    return { ...defaultLicenseState, error: "import_failed" };
+  } catch (err) {
+    return { ...defaultLicenseState, error: err?.toString() || "import_failed" };
+  }
+}
+
+// Diagnostic du fichier licence : missing | unreadable | corrupt | tampered | unverified | valid
+export async function verifyLicenseFile() {
+  try {
+    return await invoke("verify_license_file");
+  } catch (err) {
+    return { status: "error", error: err?.toString() || "verify_failed" };
+  }
+}
+
+// Réparation : réactive l'entitlement stocké si possible, sinon `next_step` guide l'utilisateur
+export async function repairLicense() {
+  try {
+    return await invoke("repair_license");
+  } catch (err) {
+    return { repaired: false, next_step: "reactivate_license", error: err?.toString() || "repair_failed" };
+  }
+}
+
 export async function refreshLicense() {
   try {
     const resp = await invoke("license_refresh");
@@ -57,3 +91,45 @@ export async function refreshLicense() {
     return { ...defaultLicenseState, error: err?.toString() || "refresh_failed" };
   }
 }
+
+export async function startTrial() {
+  try {
+    const resp = await invoke("license_start_trial");
+    if (resp?.status) return resp.status;
+    return { ...defaultLicenseState, error: "trial_failed" };
+  } catch (err) {
+    return { ...defaultLicenseState, error: err?.toString() || "trial_failed" };
+  }
+}
+
+// Vérifie l'expiration prochaine (émet "license-expiring-soon" si elle tombe dans la fenêtre)
+export async function checkLicenseExpiryWarning(warningDays = null) {
+  try {
+    return await invoke("check_license_expiry_warning", warningDays ? { warningDays } : {});
+  } catch (err) {
+    console.warn("[license] expiry check failed", err);
+    return null;
+  }
+}
+
+// Notifié quand la licence expire bientôt (jours restants dans `days_remaining`)
+export async function onLicenseExpiringSoon(callback) {
+  try {
+    return await listen("license-expiring-soon", (event) => callback(event.payload));
+  } catch (err) {
+    console.warn("[license] expiry listener unavailable (Tauri unavailable?)", err);
+    return () => {};
+  }
+}
+
+// Notifié à chaque changement de licence (activation, refresh, essai), dans toutes les fenêtres
+export async function onLicenseChanged(callback) {
+  try {
+    return await listen("license-changed", (event) => {
+      callback({ ...defaultLicenseState, ...event.payload });
+    });
+  } catch (err) {
+    console.warn("[license] change listener unavailable (Tauri unavailable?)", err);
+    return () => {};
+  }
+}
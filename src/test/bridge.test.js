@@ -86,6 +86,20 @@ describe('bridge service', () => {
             expect(result.code).toBe('INVALID_JSON');
         });
 
+        it('unwraps request_id envelope', async () => {
+            const mockResponse = { success: true };
+            invoke.mockResolvedValueOnce({ request_id: 'req-1', data: mockResponse });
+
+            const result = await requestWorker('test_cmd', {}, 30000, 'req-1');
+
+            expect(invoke).toHaveBeenCalledWith('call_python', {
+                cmd: 'test_cmd',
+                payload: {},
+                requestId: 'req-1',
+            });
+            expect(result).toEqual(mockResponse);
+        });
+
         it('passes payload correctly', async () => {
             const payload = { model: 'llama', prompt: 'Hello' };
             invoke.mockResolvedValueOnce({ success: true });
@@ -289,6 +289,56 @@ describe('PermissionService', () => {
                 path: '/path/to/export.json'
             });
         });
+
+        it('passes time range and permission filters', async () => {
+            invoke.mockResolvedValueOnce(undefined);
+
+            await PermissionService.exportPermissionLogs('/path/to/export.json', {
+                since: '2024-01-01T00:00:00Z',
+                until: '2024-03-31T23:59:59Z',
+                permissions: ['CommandExecute']
+            });
+
+            expect(invoke).toHaveBeenCalledWith('export_permission_logs', {
+                path: '/path/to/export.json',
+                since: '2024-01-01T00:00:00Z',
+                until: '2024-03-31T23:59:59Z',
+                permissions: ['CommandExecute']
+            });
+        });
+    });
+
+    describe('importPermissionState', () => {
+        it('passes the snapshot', async () => {
+            const snapshot = { granted_permissions: {}, parano_mode: true, sandbox_mode: false };
+            invoke.mockResolvedValueOnce({ success: true, imported_count: 0 });
+
+            const count = await PermissionService.importPermissionState(snapshot);
+
+            expect(invoke).toHaveBeenCalledWith('import_permission_state', { snapshot });
+            expect(count).toBe(0);
+        });
+
+        it('throws when the native confirmation is declined', async () => {
+            invoke.mockResolvedValueOnce({
+                error: true,
+                code: 'CONFIRMATION_DECLINED',
+                message: 'Permission state import was not confirmed by the user'
+            });
+
+            await expect(PermissionService.importPermissionState({})).rejects.toThrow('not confirmed');
+        });
+    });
+
+    describe('registerCustomPermission', () => {
+        it('returns whether the permission was newly registered', async () => {
+            invoke.mockResolvedValueOnce({ success: true, permission: 'plugin:export', registered: true });
+
+            const registered = await PermissionService.registerCustomPermission('plugin:export');
+
+            expect(invoke).toHaveBeenCalledWith('register_custom_permission', { name: 'plugin:export' });
+            expect(registered).toBe(true);
+        });
     });
 
     describe('formatPermissionLog', () => {
@@ -1,11 +1,13 @@
 import logging
+import os
 import sys
 from pathlib import Path
 
-# Dossier pour les logs à la racine du projet
+# Dossier des logs : celui indiqué par le bridge Rust (dossier de logs de l'app),
+# sinon <racine du projet>/logs quand le worker est lancé seul
 current_file = Path(__file__).resolve()
-LOG_DIR = current_file.parent.parent.parent / "logs"
-LOG_DIR.mkdir(exist_ok=True)
+LOG_DIR = Path(os.environ.get("HORIZON_LOG_DIR") or current_file.parent.parent.parent / "logs")
+LOG_DIR.mkdir(parents=True, exist_ok=True)
 LOG_FILE = LOG_DIR / "worker.log"
 
 logging.basicConfig(
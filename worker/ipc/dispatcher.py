@@ -1,6 +1,7 @@
 import sys
 import os
 import json
+import time
 import ollama
 
 # Importation des services
@@ -36,6 +37,7 @@ class CommandDispatcher:
         self.remote_server = None  # Serveur HTTP pour accès distant
         self.active_chat_id = None  # 🔧 CORRECTION: ID du chat actif pour cancellation
         self.cancel_streaming = False  # 🔧 CORRECTION: Flag pour stopper le streaming
+        self.active_model = None  # Dernier modèle chargé via set_model
 
     def _create_error_response(self, error_code, error_message, context=None, details=None):
         """Crée une réponse d'erreur standardisée compatible avec le frontend"""
@@ -148,6 +150,9 @@ class CommandDispatcher:
             monitoring_service.add_log(f"INITIATING: Pulling model '{model_name}'...")
             return ollama_service.pull_model_stream(model_name)
 
+        if cmd == "cancel_pull":
+            return ollama_service.cancel_pull(payload.get("model"))
+
         if cmd == "get_models":
             try:
                 # Utilisation directe de la CLI 'ollama list' (Prouvé fonctionnel sur ce système)
@@ -251,6 +256,19 @@ class CommandDispatcher:
         if cmd == "delete_model":
             return ollama_service.delete_model(payload.get("name"))
 
+        # Charge un modèle Ollama et ne répond qu'une fois le modèle prêt (signal "model ready" côté UI)
+        if cmd == "set_model":
+            model = payload.get("model")
+            if not model:
+                raise ValueError("model is required")
+            started = time.monotonic()
+            # Une génération vide charge le modèle en mémoire sans produire de texte
+            ollama.generate(model=model, prompt="", keep_alive=payload.get("keep_alive", "30m"))
+            load_time_ms = int((time.monotonic() - started) * 1000)
+            self.active_model = model
+            monitoring_service.add_log(f"SUCCESS: Model {model} loaded in {load_time_ms} ms.")
+            return {"model": model, "load_time_ms": load_time_ms}
+
         # --- AIRLLM (Python sidecar) ---
         if cmd == "airllm_list_models":
             return airllm_manager.list_models()
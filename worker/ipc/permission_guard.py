@@ -88,8 +88,10 @@ class PermissionGuard:
         "load_settings",
         "save_settings",
         "pull",
+        "cancel_pull",
         "get_models",
         "delete_model",
+        "set_model",
         "list_conversations",
         "get_conversation_messages",
         "get_conversation_metadata",
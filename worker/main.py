@@ -20,11 +20,17 @@ if sys.platform == "win32":
 try:
     from ipc.handler import IpcHandler
     from ipc.dispatcher import CommandDispatcher
+    from core.logger import LOG_FILE
 except ImportError as e:
     print(f"ERREUR D'IMPORT DANS MAIN : {e}", file=sys.stderr)
     sys.exit(1)
 
 
+# Version du worker et du protocole IPC, annoncées au bridge Rust dans le handshake
+WORKER_VERSION = "2.0.0"
+IPC_PROTOCOL_VERSION = 1
+
+
 def start_ollama_background():
     import subprocess
     try:
@@ -141,6 +147,41 @@ class Worker:
             except:
                 pass
 
+    def _dispatch_batch(self, items):
+        """Exécute une trame batch et retourne une réponse par élément (associée par id)"""
+        results = []
+        for item in items:
+            item_id = item.get("id")
+            cmd = item.get("cmd")
+            payload = item.get("payload", {})
+
+            if not item_id or not cmd:
+                continue
+
+            try:
+                result = self.dispatcher.dispatch(cmd, payload)
+
+                # Les streams ne peuvent pas être résolus dans une réponse batch
+                if hasattr(result, "__iter__") and not isinstance(result, (dict, list)):
+                    if hasattr(result, "close"):
+                        result.close()
+                    results.append({
+                        "id": item_id,
+                        "status": "error",
+                        "error": {"code": "BATCH_STREAM_UNSUPPORTED", "message": f"Streaming command '{cmd}' cannot be batched"}
+                    })
+                else:
+                    results.append({"id": item_id, "status": "ok", "data": result})
+
+            except Exception as e:
+                results.append({
+                    "id": item_id,
+                    "status": "error",
+                    "error": {"code": "CMD_ERR", "message": str(e)}
+                })
+
+        return results
+
     def run(self):
         print("🚀 HorizonAI Worker started", file=sys.stderr)
 
@@ -158,12 +199,26 @@ class Worker:
             daemon=True
         ).start()
 
+        # Handshake : signaler au bridge Rust que les commandes peuvent être envoyées
+        self.ipc.send_response("WORKER_READY", "ok", data={
+            "pid": os.getpid(),
+            "version": WORKER_VERSION,
+            "protocol_version": IPC_PROTOCOL_VERSION,
+            "log_file": str(LOG_FILE),
+        })
+
         try:
             for request in self.ipc.read_requests():
                 req_id = request.get("id")
                 cmd = request.get("cmd")
                 payload = request.get("payload", {})
 
+                # 📦 BATCH : plusieurs commandes, une seule réponse
+                batch = request.get("batch")
+                if req_id and isinstance(batch, list):
+                    self.ipc.send_response(req_id, "ok", data=self._dispatch_batch(batch))
+                    continue
+
                 if not req_id or not cmd:
                     continue
 
@@ -11,6 +11,10 @@ SUBPROCESS_FLAGS = subprocess.CREATE_NO_WINDOW if sys.platform == "win32" else 0
 ANSI_ESCAPE = re.compile(r'\x1B(?:[@-Z\\-_]|\[[0-?]*[ -/]*[@-~])|\[[\d;]*[A-Za-z]|\x1b\[[^\x1b]*')
 SPINNER_CHARS = set('⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏')
 
+# Regex pour extraire la couche et les octets téléchargés ("pulling <digest>: 45% ... 913 MB/2.0 GB")
+PULL_BYTES = re.compile(r'pulling ([0-9a-f]+).*?([\d.]+)\s*([KMGT]?B)\s*/\s*([\d.]+)\s*([KMGT]?B)')
+BYTE_UNITS = {"B": 1, "KB": 1000, "MB": 1000 ** 2, "GB": 1000 ** 3, "TB": 1000 ** 4}
+
 def clean_line(line: str) -> str:
     """Nettoie les codes ANSI et les caractères de spinner"""
     # Supprimer les codes d'échappement ANSI
@@ -26,6 +30,8 @@ def clean_line(line: str) -> str:
 class OllamaService:
     def __init__(self, base_url="http://127.0.0.1:11434"):
         self.base_url = base_url
+        self.active_pulls = {}  # modèle -> processus "ollama pull" en cours
+        self.cancelled_pulls = set()
 
     def pull_model_stream(self, model: str) -> Generator[dict, None, None]:
         monitoring_service.add_log(f"OLLAMA: Starting subprocess for {model}")
@@ -40,6 +46,7 @@ class OllamaService:
             bufsize=1,  # Line buffered
             creationflags=SUBPROCESS_FLAGS  # ✅ Masquer la fenêtre CMD sur Windows
         )
+        self.active_pulls[model] = process
 
         try:
             for line in process.stdout:
@@ -65,23 +72,51 @@ class OllamaService:
                             percent = int(match.group(1))
                     except: pass
 
+                # Octets de la couche en cours, comptés côté Rust dans le budget egress
+                digest, completed_bytes, total_bytes = None, None, None
+                bytes_match = PULL_BYTES.search(line)
+                if bytes_match:
+                    digest = bytes_match.group(1)
+                    completed_bytes = int(float(bytes_match.group(2)) * BYTE_UNITS[bytes_match.group(3)])
+                    total_bytes = int(float(bytes_match.group(4)) * BYTE_UNITS[bytes_match.group(5)])
+
                 yield {
                     "event": "progress",
                     "model": model,
                     "message": line,
-                    "progress": percent
+                    "progress": percent,
+                    "digest": digest,
+                    "completed_bytes": completed_bytes,
+                    "total_bytes": total_bytes
                 }
             
+            # Téléchargement interrompu par cancel_pull (ex: budget réseau dépassé)
+            if model in self.cancelled_pulls:
+                monitoring_service.add_log(f"OLLAMA: Pull of {model} cancelled.")
+                yield {"event": "cancelled", "model": model, "message": "Pull cancelled"}
+                return
+
             # ✅ Envoyer l'événement "done" AVANT de fermer le processus
             monitoring_service.add_log(f"SUCCESS: Model {model} pulled successfully.")
             yield {"event": "done", "model": model}
             
         finally:
+            self.active_pulls.pop(model, None)
+            self.cancelled_pulls.discard(model)
             # ✅ CRITIQUE: Toujours fermer et attendre le processus pour éviter les zombies
             if process.stdout:
                 process.stdout.close()
             process.wait()
 
+    def cancel_pull(self, model: str) -> dict:
+        """Interrompt le téléchargement en cours d'un modèle"""
+        process = self.active_pulls.get(model)
+        if process is None:
+            return {"success": False, "error": f"No pull in progress for {model}"}
+        self.cancelled_pulls.add(model)
+        process.terminate()
+        return {"success": True, "message": f"Pull of {model} cancelled"}
+
     def delete_model(self, name: str) -> dict:
         """Supprime un modèle Ollama"""
         try: